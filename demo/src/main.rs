@@ -10,7 +10,12 @@ pub struct Descriptors {
 #[sierra::pipeline]
 pub struct Pipeline {
     descriptors: Descriptors,
-    sparse: sierra::SparseDescriptorSet<sierra::SampledImageDescriptor, 32>,
+    #[set]
+    sparse: sierra::SparseDescriptors<
+        sierra::SampledImageDescriptor,
+        32,
+        { sierra::ShaderStageFlags::FRAGMENT.bits() },
+    >,
 }
 
 #[sierra::pass]
@@ -39,8 +44,8 @@ fn main() -> eyre::Result<()> {
         sierra::SingleQueueQuery::GRAPHICS,
     )?;
 
-    let shader_module = device.create_shader_module(sierra::ShaderModuleInfo {
-        code: br#"
+    let shader_module = device.create_shader_module(sierra::ShaderModuleInfo::wgsl(
+        br#"
 [[stage(vertex)]]
 fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
     let x = f32(i32(in_vertex_index) - 1);
@@ -55,8 +60,7 @@ fn fs_main() -> [[location(0)]] vec4<f32> {
         "#
         .to_vec()
         .into_boxed_slice(),
-        language: sierra::ShaderLanguage::WGSL,
-    })?;
+    ))?;
 
     let mut swapchain = device.create_swapchain(&mut surface)?;
     swapchain.configure(
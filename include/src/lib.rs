@@ -0,0 +1,708 @@
+//! Expansion of `#include` directives in GLSL and HLSL source code.
+//!
+//! Shared by `sierra` for runtime compilation and by `sierra-proc`
+//! for `include_shader!` so that both expand includes identically.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error as StdError,
+    fmt::{self, Display, Write as _},
+};
+
+/// Maximum nesting of `#include` directives.
+pub const MAX_DEPTH: usize = 32;
+
+/// Resolves paths of included files.
+pub trait Resolver {
+    /// Identifies resolved file.
+    /// Paths included from that file may be resolved relative to it.
+    type File;
+
+    /// Returns included file and its content.
+    ///
+    /// `includer` is `None` for paths included from the root code.
+    fn resolve(
+        &mut self,
+        path: &str,
+        includer: Option<&Self::File>,
+    ) -> Option<(Self::File, String)>;
+}
+
+/// Resolves nothing when there is no resolver.
+impl<R> Resolver for Option<R>
+where
+    R: Resolver,
+{
+    type File = R::File;
+
+    fn resolve(&mut self, path: &str, includer: Option<&R::File>) -> Option<(R::File, String)> {
+        self.as_mut()?.resolve(path, includer)
+    }
+}
+
+/// Error that may occur when expanding includes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// `#include` directive is malformed or included file can't be resolved.
+    Unresolved { path: String },
+
+    /// `#include` directives are nested deeper than `MAX_DEPTH`.
+    DepthExceeded { path: String, depth: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unresolved { path } => write!(f, "Failed to resolve `#include` of `{}`", path),
+            Error::DepthExceeded { path, depth } => write!(
+                f,
+                "`#include` of `{}` exceeds maximum nesting depth of {}",
+                path, depth
+            ),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+/// Replaces `#include` directives in source code with content of included files.
+///
+/// `file` identifies the root code for the resolver, if any.
+///
+/// Included content is surrounded with `#line` directives so that
+/// line numbers reported by compiler match lines in original files.
+///
+/// Conditional blocks are evaluated against `defines` and macros defined
+/// in the code. Directives inside comments are left as is and directives
+/// in blocks that are never compiled are replaced with empty lines.
+/// When condition can't be evaluated here, included file is expanded
+/// if it can be resolved and left to the preprocessor otherwise.
+pub fn expand<'a, R>(
+    code: &'a str,
+    file: Option<&R::File>,
+    defines: impl IntoIterator<Item = (String, String)>,
+    resolver: &mut R,
+) -> Result<Cow<'a, str>, Error>
+where
+    R: Resolver,
+{
+    if !code.lines().any(is_include_directive) {
+        return Ok(Cow::Borrowed(code));
+    }
+
+    let mut expander = Expander {
+        resolver,
+        defines: Defines(
+            defines
+                .into_iter()
+                .map(|(name, value)| (name, Some(value)))
+                .collect(),
+        ),
+        result: String::with_capacity(code.len()),
+    };
+
+    expander.expand(code, file, 0, Some(true))?;
+    Ok(Cow::Owned(expander.result))
+}
+
+/// Checks if line is an `#include` directive.
+pub struct Expander<'a, R> {
+    resolver: &'a mut R,
+    defines: Defines,
+    result: String,
+}
+
+/// Macros defined so far.
+/// `None` value marks macros that may or may not be defined.
+struct Defines(HashMap<String, Option<String>>);
+
+/// Conditional block of the preprocessor.
+///
+/// `None` in any field means that value can't be determined
+/// without running the preprocessor.
+struct Conditional {
+    /// Whether enclosing code is compiled.
+    parent: Option<bool>,
+
+    /// Whether one of previous branches was taken.
+    taken: Option<bool>,
+
+    /// Whether current branch is compiled.
+    active: Option<bool>,
+}
+
+impl<R> Expander<'_, R>
+where
+    R: Resolver,
+{
+    fn expand(
+        &mut self,
+        code: &str,
+        file: Option<&R::File>,
+        depth: usize,
+        active: Option<bool>,
+    ) -> Result<(), Error> {
+        let mut in_comment = false;
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        for (index, line) in code.lines().enumerate() {
+            let starts_in_comment = in_comment;
+            in_comment = ends_in_comment(line, in_comment);
+
+            let active = conditionals.last().map_or(active, |c| c.active);
+
+            let (name, rest) = match parse_directive(line) {
+                Some(directive) if !starts_in_comment => directive,
+                _ => {
+                    self.result.push_str(line);
+                    self.result.push('\n');
+                    continue;
+                }
+            };
+
+            match name {
+                "if" | "ifdef" | "ifndef" => {
+                    let condition = match active {
+                        Some(false) => Some(false),
+                        _ => self.defines.condition(name, rest),
+                    };
+
+                    conditionals.push(Conditional {
+                        parent: active,
+                        taken: condition,
+                        active: tri_and(active, condition),
+                    });
+                }
+                "elif" | "else" => {
+                    if let Some(c) = conditionals.last_mut() {
+                        let condition = match c.parent {
+                            Some(false) => Some(false),
+                            _ => self.defines.condition(name, rest),
+                        };
+
+                        c.active = tri_and(c.parent, tri_and(c.taken.map(|t| !t), condition));
+                        c.taken = tri_or(c.taken, condition);
+                    }
+                }
+                "endif" => {
+                    conditionals.pop();
+                }
+                "define" | "undef" if active != Some(false) => {
+                    let rest = strip_comments(rest).trim_start();
+                    let end = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+
+                    let (macro_name, value) = rest.split_at(end);
+
+                    match (name, active) {
+                        ("define", Some(true)) => {
+                            self.defines
+                                .0
+                                .insert(macro_name.to_owned(), Some(value.trim().to_owned()));
+                        }
+                        ("undef", Some(true)) => {
+                            self.defines.0.remove(macro_name);
+                        }
+                        _ => {
+                            self.defines.0.insert(macro_name.to_owned(), None);
+                        }
+                    }
+                }
+                "extension" if rest.contains("GL_GOOGLE_include_directive") => {
+                    // Includes are expanded here.
+                    // Empty line keeps following line numbers intact.
+                    self.result.push('\n');
+                    continue;
+                }
+                "include" if active == Some(false) => {
+                    // Some preprocessors can't tokenize include paths
+                    // even in skipped blocks.
+                    self.result.push('\n');
+                    continue;
+                }
+                "include" => {
+                    let expanded = self.include(rest, file, depth, active)?;
+                    if expanded {
+                        // Line after the directive. Lines are numbered from 1.
+                        writeln!(self.result, "#line {}", index + 2).unwrap();
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            self.result.push_str(line);
+            self.result.push('\n');
+        }
+
+        Ok(())
+    }
+
+    /// Expands included file.
+    ///
+    /// Returns `false` if inclusion is left to the preprocessor.
+    fn include(
+        &mut self,
+        rest: &str,
+        file: Option<&R::File>,
+        depth: usize,
+        active: Option<bool>,
+    ) -> Result<bool, Error> {
+        let rest = rest.trim();
+        let path = rest
+            .strip_prefix('"')
+            .and_then(|path| path.split('"').next().filter(|_| path.contains('"')))
+            .or_else(|| {
+                rest.strip_prefix('<')
+                    .and_then(|path| path.split('>').next().filter(|_| path.contains('>')))
+            });
+
+        let included = path.and_then(|path| self.resolver.resolve(path, file));
+
+        let (path, (included, content)) = match (path, included) {
+            (Some(path), Some(included)) => (path, included),
+            _ if active.is_none() => return Ok(false),
+            (path, _) => {
+                return Err(Error::Unresolved {
+                    path: path.unwrap_or(rest).to_owned(),
+                })
+            }
+        };
+
+        if depth >= MAX_DEPTH {
+            return Err(Error::DepthExceeded {
+                path: path.to_owned(),
+                depth: MAX_DEPTH,
+            });
+        }
+
+        self.result.push_str("#line 1\n");
+        self.expand(&content, Some(&included), depth + 1, active)?;
+        Ok(true)
+    }
+}
+
+impl Defines {
+    /// Evaluates condition of `#if`, `#ifdef`, `#ifndef`, `#elif` or `#else`.
+    fn condition(&self, name: &str, rest: &str) -> Option<bool> {
+        let rest = strip_comments(rest).trim();
+
+        match name {
+            "ifdef" => self.is_defined(rest),
+            "ifndef" => self.is_defined(rest).map(|defined| !defined),
+            "else" => Some(true),
+            _ => {
+                let tokens = tokenize(rest)?;
+                let mut parser = ExprParser {
+                    defines: self,
+                    tokens: &tokens,
+                };
+
+                let value = parser.or();
+                if parser.tokens.is_empty() {
+                    value.map(|value| value != 0)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> Option<bool> {
+        match self.0.get(name) {
+            Some(Some(_)) => Some(true),
+            Some(None) => None,
+            None if is_predefined(name) => None,
+            None => Some(false),
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<i64> {
+        match self.0.get(name) {
+            Some(Some(value)) => parse_integer(value.trim()),
+            Some(None) => None,
+            None if is_predefined(name) => None,
+            None => Some(0),
+        }
+    }
+}
+
+/// Checks if macro name may be defined by the compiler.
+///
+/// Names starting with `GL_` or containing `__` are reserved
+/// for the implementation.
+fn is_predefined(name: &str) -> bool {
+    name.starts_with("GL_") || name.contains("__") || name == "VULKAN"
+}
+
+fn tri_and(lhs: Option<bool>, rhs: Option<bool>) -> Option<bool> {
+    match (lhs, rhs) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn tri_or(lhs: Option<bool>, rhs: Option<bool>) -> Option<bool> {
+    match (lhs, rhs) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Integer(i64),
+    Ident(&'a str),
+    Punct(&'static str),
+}
+
+/// Splits `#if` expression into tokens.
+///
+/// Returns `None` if expression contains tokens that are not supported.
+fn tokenize(mut expr: &str) -> Option<Vec<Token<'_>>> {
+    const PUNCTS: [&str; 13] = [
+        "&&", "||", "==", "!=", "<=", ">=", "(", ")", "!", "<", ">", "+", "-",
+    ];
+
+    let mut tokens = Vec::new();
+
+    loop {
+        expr = expr.trim_start();
+        let c = match expr.chars().next() {
+            None => return Some(tokens),
+            Some(c) => c,
+        };
+
+        let len = if is_ident_char(c) {
+            let len = expr.find(|c| !is_ident_char(c)).unwrap_or(expr.len());
+            if c.is_ascii_digit() {
+                tokens.push(Token::Integer(parse_integer(&expr[..len])?));
+            } else {
+                tokens.push(Token::Ident(&expr[..len]));
+            }
+            len
+        } else {
+            let punct = PUNCTS.iter().find(|punct| expr.starts_with(**punct))?;
+            tokens.push(Token::Punct(punct));
+            punct.len()
+        };
+
+        expr = &expr[len..];
+    }
+}
+
+fn parse_integer(value: &str) -> Option<i64> {
+    let value = value.trim_end_matches(&['u', 'U'][..]);
+
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Recursive descent parser for `#if` expressions.
+///
+/// `None` value means that value can't be determined.
+struct ExprParser<'a> {
+    defines: &'a Defines,
+    tokens: &'a [Token<'a>],
+}
+
+impl ExprParser<'_> {
+    fn eat(&mut self, punct: &str) -> bool {
+        match self.tokens.split_first() {
+            Some((Token::Punct(p), rest)) if *p == punct => {
+                self.tokens = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Option<i64> {
+        let mut lhs = truth(self.and());
+        while self.eat("||") {
+            lhs = tri_or(lhs, truth(self.and()));
+        }
+        lhs.map(i64::from)
+    }
+
+    fn and(&mut self) -> Option<i64> {
+        let mut lhs = truth(self.compare());
+        while self.eat("&&") {
+            lhs = tri_and(lhs, truth(self.compare()));
+        }
+        lhs.map(i64::from)
+    }
+
+    fn compare(&mut self) -> Option<i64> {
+        let mut lhs = self.sum();
+        loop {
+            let op: fn(&i64, &i64) -> bool = if self.eat("==") {
+                i64::eq
+            } else if self.eat("!=") {
+                i64::ne
+            } else if self.eat("<=") {
+                i64::le
+            } else if self.eat(">=") {
+                i64::ge
+            } else if self.eat("<") {
+                i64::lt
+            } else if self.eat(">") {
+                i64::gt
+            } else {
+                return lhs;
+            };
+
+            let rhs = self.sum();
+            lhs = lhs.and_then(|lhs| Some(i64::from(op(&lhs, &rhs?))));
+        }
+    }
+
+    fn sum(&mut self) -> Option<i64> {
+        let mut lhs = self.unary();
+        loop {
+            let op: fn(i64, i64) -> Option<i64> = if self.eat("+") {
+                i64::checked_add
+            } else if self.eat("-") {
+                i64::checked_sub
+            } else {
+                return lhs;
+            };
+
+            let rhs = self.unary();
+            lhs = lhs.and_then(|lhs| op(lhs, rhs?));
+        }
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        if self.eat("!") {
+            self.unary().map(|value| i64::from(value == 0))
+        } else if self.eat("-") {
+            self.unary().and_then(i64::checked_neg)
+        } else if self.eat("+") {
+            self.unary()
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Option<i64> {
+        if self.eat("(") {
+            let value = self.or();
+            return if self.eat(")") { value } else { None };
+        }
+
+        let (token, rest) = self.tokens.split_first()?;
+        self.tokens = rest;
+
+        match *token {
+            Token::Integer(value) => Some(value),
+            Token::Ident("defined") => {
+                let parens = self.eat("(");
+                let name = match self.tokens.split_first() {
+                    Some((Token::Ident(name), rest)) => {
+                        self.tokens = rest;
+                        name
+                    }
+                    _ => return None,
+                };
+                if parens && !self.eat(")") {
+                    return None;
+                }
+                self.defines.is_defined(name).map(i64::from)
+            }
+            Token::Ident(name) => {
+                // Function-like macros are not expanded.
+                if matches!(self.tokens.first(), Some(Token::Punct("("))) {
+                    return None;
+                }
+                self.defines.value(name)
+            }
+            Token::Punct(_) => None,
+        }
+    }
+}
+
+fn truth(value: Option<i64>) -> Option<bool> {
+    value.map(|value| value != 0)
+}
+
+/// Splits preprocessor directive into name and the rest of the line.
+fn parse_directive(line: &str) -> Option<(&str, &str)> {
+    let directive = line.trim_start().strip_prefix('#')?.trim_start();
+    let end = directive
+        .find(|c: char| !is_ident_char(c))
+        .unwrap_or(directive.len());
+
+    Some((&directive[..end], &directive[end..]))
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_include_directive(line: &str) -> bool {
+    matches!(parse_directive(line), Some(("include", _)))
+}
+
+/// Removes trailing comment from directive.
+fn strip_comments(rest: &str) -> &str {
+    let end = [rest.find("//"), rest.find("/*")]
+        .iter()
+        .flatten()
+        .min()
+        .copied()
+        .unwrap_or(rest.len());
+
+    &rest[..end]
+}
+
+/// Checks if line ends inside block comment.
+fn ends_in_comment(line: &str, mut in_comment: bool) -> bool {
+    let mut rest = line;
+
+    loop {
+        if in_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    rest = &rest[end + 2..];
+                    in_comment = false;
+                }
+                None => return true,
+            }
+        } else {
+            let block = rest.find("/*");
+            let line = rest.find("//");
+
+            match (block, line) {
+                (Some(block), Some(line)) if line < block => return false,
+                (Some(block), _) => {
+                    rest = &rest[block + 2..];
+                    in_comment = true;
+                }
+                (None, _) => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolves paths relative to directory of including file.
+    struct Files;
+
+    impl Resolver for Files {
+        type File = String;
+
+        fn resolve(&mut self, path: &str, includer: Option<&String>) -> Option<(String, String)> {
+            let path = match includer.and_then(|includer| includer.rfind('/')) {
+                Some(end) => format!("{}/{}", &includer.unwrap()[..end], path),
+                None => path.to_owned(),
+            };
+
+            let content = match &*path {
+                "common.glsl" => "float common;\n",
+                "self.glsl" => "#include \"self.glsl\"\n",
+                "lib/outer.glsl" => "#include \"inner.glsl\"\n",
+                "lib/inner.glsl" => "float inner;\n",
+                _ => return None,
+            };
+
+            Some((path, content.to_owned()))
+        }
+    }
+
+    fn expand_with(code: &str, defines: &[(&str, &str)]) -> Result<String, Error> {
+        let defines = defines
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        expand(code, None, defines, &mut Files).map(Cow::into_owned)
+    }
+
+    #[test]
+    fn includes_are_surrounded_with_line_directives() {
+        let code = "#version 450\n#include \"common.glsl\"\nvoid main() {}\n";
+
+        assert_eq!(
+            expand_with(code, &[]).unwrap(),
+            "#version 450\n#line 1\nfloat common;\n#line 3\nvoid main() {}\n"
+        );
+    }
+
+    #[test]
+    fn includes_are_relative_to_including_file() {
+        let code = "#include \"lib/outer.glsl\"\n";
+
+        assert_eq!(
+            expand_with(code, &[]).unwrap(),
+            "#line 1\n#line 1\nfloat inner;\n#line 2\n#line 2\n"
+        );
+    }
+
+    #[test]
+    fn disabled_includes_are_not_expanded() {
+        let code = "/*\n#include \"missing.glsl\"\n*/\n#if 0\n#ifdef A\n#endif\n#include \"missing.glsl\"\n#else\n#include \"common.glsl\"\n#endif\n";
+        let expanded = expand_with(code, &[]).unwrap();
+
+        assert!(expanded.contains("#if 0\n#ifdef A\n#endif\n\n#else\n"));
+        assert!(expanded.contains("float common;\n"));
+        assert!(expanded.starts_with("/*\n#include \"missing.glsl\"\n*/\n"));
+    }
+
+    #[test]
+    fn conditional_includes_follow_defines() {
+        let code = "#ifdef USE_SHADOWS\n#include \"missing.glsl\"\n#endif\n";
+
+        assert_eq!(
+            expand_with(code, &[]).unwrap(),
+            "#ifdef USE_SHADOWS\n\n#endif\n"
+        );
+        assert_eq!(
+            expand_with(code, &[("USE_SHADOWS", "")]),
+            Err(Error::Unresolved {
+                path: "missing.glsl".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn conditions_are_evaluated() {
+        let code = "#define QUALITY 2\n#if defined(QUALITY) && QUALITY > 1\n#include \"common.glsl\"\n#elif !defined QUALITY\n#include \"missing.glsl\"\n#else\n#include \"missing.glsl\"\n#endif\n#ifndef QUALITY\n#include \"missing.glsl\"\n#endif\n";
+        let expanded = expand_with(code, &[]).unwrap();
+
+        assert!(expanded.starts_with(
+            "#define QUALITY 2\n#if defined(QUALITY) && QUALITY > 1\n#line 1\nfloat common;\n#line 4\n"
+        ));
+        assert!(!expanded.contains("missing.glsl"));
+    }
+
+    #[test]
+    fn unknown_conditions_are_left_to_preprocessor() {
+        let code = "#ifdef GL_ES\n#include \"missing.glsl\"\n#include \"common.glsl\"\n#endif\n";
+
+        assert_eq!(
+            expand_with(code, &[]).unwrap(),
+            "#ifdef GL_ES\n#include \"missing.glsl\"\n#line 1\nfloat common;\n#line 4\n#endif\n"
+        );
+    }
+
+    #[test]
+    fn recursive_include_exceeds_depth() {
+        assert_eq!(
+            expand_with("#include \"self.glsl\"\n", &[]),
+            Err(Error::DepthExceeded {
+                path: "self.glsl".to_owned(),
+                depth: MAX_DEPTH,
+            })
+        );
+    }
+}
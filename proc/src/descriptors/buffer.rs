@@ -4,6 +4,7 @@ use crate::{find_unique, get_unique};
 pub struct Buffer {
     pub kind: Kind,
     pub ty: syn::Type,
+    pub count: Option<syn::Expr>,
 }
 
 impl Buffer {
@@ -22,6 +23,7 @@ pub enum Kind {
 enum Argument {
     Kind(Kind),
     Type(syn::Type),
+    Count(syn::Expr),
 }
 
 pub(super) fn parse_buffer_attr(attr: &syn::Attribute) -> syn::Result<Option<Buffer>> {
@@ -45,6 +47,12 @@ pub(super) fn parse_buffer_attr(attr: &syn::Attribute) -> syn::Result<Option<Buf
 
                         Ok(Argument::Type(ty))
                     }
+                    ident if ident == "count" => {
+                        let _eq = stream.parse::<syn::Token![=]>()?;
+                        let count = stream.parse::<syn::Expr>()?;
+
+                        Ok(Argument::Count(count))
+                    }
                     _ => {
                         return Err(stream.error("Unrecognized argument"));
                     }
@@ -78,5 +86,14 @@ pub(super) fn parse_buffer_attr(attr: &syn::Attribute) -> syn::Result<Option<Buf
         "Expected exactly one `type` argument",
     )?;
 
-    Ok(Some(Buffer { kind, ty }))
+    let count = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Count(count) => Some(count.clone()),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `count` argument",
+    )?;
+
+    Ok(Some(Buffer { kind, ty, count }))
 }
@@ -0,0 +1,149 @@
+use {
+    super::{
+        buffer, image,
+        layout::descriptor_count_tokens,
+        member_name,
+        parse::{DescriptorType, Input},
+        sampled_image, storage_image, texel_buffer,
+    },
+    crate::stage::{combined_stages_tokens, combined_stages_tokens_dedup},
+    proc_macro2::TokenStream,
+    std::convert::TryFrom as _,
+};
+
+pub(super) fn generate(input: &Input) -> TokenStream {
+    let ident = &input.item_struct.ident;
+    let struct_name = syn::LitStr::new(&ident.to_string(), ident.span());
+
+    let descriptors = input
+        .descriptors
+        .iter()
+        .enumerate()
+        .map(|(binding, descriptor)| {
+            let binding = u32::try_from(binding).expect("Too many descriptors");
+            let stages = combined_stages_tokens(descriptor.stages.iter().copied());
+            let count = descriptor_count_tokens(descriptor);
+            let name = member_name(&descriptor.member);
+
+            let declare = match &descriptor.desc_ty {
+                DescriptorType::Buffer(buffer::Buffer { kind, ty, .. }) => {
+                    let storage = matches!(kind, buffer::Kind::Storage);
+                    quote::quote!(ctx.declare_buffer::<#ty>(set, #binding, #count, #storage, #name))
+                }
+                DescriptorType::StorageImage(storage_image::StorageImage { shape, .. }) => {
+                    storage_image_tokens(shape, &shape.glsl_image(), binding, &count, &name)
+                }
+                DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+                    kind: texel_buffer::Kind::Storage,
+                    shape,
+                    ..
+                }) => {
+                    storage_image_tokens(shape, &shape.glsl_image_buffer(), binding, &count, &name)
+                }
+                desc_ty => {
+                    let glsl_ty = opaque_glsl_type(desc_ty);
+                    quote::quote!(ctx.declare_opaque(set, #binding, #count, #glsl_ty, #name))
+                }
+            };
+
+            quote::quote!(
+                if (#stages).contains(stage) {
+                    #declare;
+                }
+            )
+        })
+        .collect::<TokenStream>();
+
+    let uniforms = if input.uniforms.is_empty() {
+        TokenStream::new()
+    } else {
+        let stages = combined_stages_tokens_dedup(
+            input.uniforms.iter().flat_map(|u| u.stages.iter().copied()),
+        );
+
+        let binding = u32::try_from(input.descriptors.len()).expect("Too many descriptors");
+        let block_name = syn::LitStr::new(&format!("{}Uniforms", ident), ident.span());
+
+        let members = input.uniforms.iter().map(|u| {
+            let ty = &u.field.ty;
+            let name = member_name(&u.member);
+            quote::quote!(members.push_str(&ctx.block_member::<#ty>(#name));)
+        });
+
+        quote::quote!(
+            if (#stages).contains(stage) {
+                let mut members = ::std::string::String::new();
+                #(#members)*
+                ctx.declare_block(
+                    &::std::format!("set = {}, binding = {}, std140", set, #binding),
+                    "uniform",
+                    #block_name,
+                    &members,
+                );
+            }
+        )
+    };
+
+    quote::quote!(
+        impl ::sierra::glsl::GlslDescriptors for #ident {
+            fn glsl_declarations(
+                stage: ::sierra::ShaderStage,
+                set: u32,
+                _name: &str,
+                ctx: &mut ::sierra::glsl::GlslTypeContext,
+            ) {
+                let stage = ::sierra::ShaderStageFlags::from(stage);
+                #descriptors
+                #uniforms
+            }
+        }
+
+        impl ::sierra::glsl::ShaderInclude for #ident {
+            fn glsl(stage: ::sierra::ShaderStage) -> ::std::string::String {
+                let mut ctx = ::sierra::glsl::GlslTypeContext::new();
+                <Self as ::sierra::glsl::GlslDescriptors>::glsl_declarations(stage, 0, #struct_name, &mut ctx);
+                ctx.code()
+            }
+        }
+    )
+}
+
+fn storage_image_tokens(
+    shape: &image::Shape,
+    glsl_ty: &str,
+    binding: u32,
+    count: &TokenStream,
+    name: &str,
+) -> TokenStream {
+    let format = match shape.format {
+        Some(format) => {
+            let format = format.glsl;
+            quote::quote!(::std::option::Option::Some(#format))
+        }
+        None => quote::quote!(::std::option::Option::None),
+    };
+
+    quote::quote!(ctx.declare_storage_image(set, #binding, #count, #format, #glsl_ty, #name))
+}
+
+fn opaque_glsl_type(desc_ty: &DescriptorType) -> String {
+    match desc_ty {
+        DescriptorType::Sampler(_) => "sampler".to_owned(),
+        DescriptorType::SampledImage(sampled_image::SampledImage { shape, .. }) => {
+            shape.glsl_texture()
+        }
+        DescriptorType::CombinedImageSampler(_) => "sampler2D".to_owned(),
+        DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Uniform,
+            shape,
+            ..
+        }) => shape.glsl_texture_buffer(),
+        DescriptorType::AccelerationStructure(_) => "accelerationStructureEXT".to_owned(),
+        DescriptorType::StorageImage(_)
+        | DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Storage,
+            ..
+        }) => unreachable!("Storage images are declared with format"),
+        DescriptorType::Buffer(_) => unreachable!("Buffers are declared as blocks"),
+    }
+}
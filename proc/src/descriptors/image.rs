@@ -0,0 +1,361 @@
+use crate::find_unique;
+
+/// Image view kind declared in shaders.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    D1,
+    D2,
+    D3,
+    Cube,
+}
+
+/// Scalar type of texels read from or written to the descriptor.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SampleType {
+    Float,
+    Sint,
+    Uint,
+}
+
+/// Format of storage image or storage texel buffer.
+#[derive(Clone, Copy)]
+pub struct StorageFormat {
+    /// GLSL layout qualifier.
+    pub glsl: &'static str,
+
+    /// WGSL storage texel format, if the format is supported in WGSL.
+    pub wgsl: Option<&'static str>,
+
+    pub sample: SampleType,
+}
+
+/// Shader type of image or texel buffer descriptor.
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub kind: Kind,
+    pub array: bool,
+    pub sample: SampleType,
+    pub format: Option<StorageFormat>,
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape {
+            kind: Kind::D2,
+            array: false,
+            sample: SampleType::Float,
+            format: None,
+        }
+    }
+}
+
+impl Shape {
+    /// Returns GLSL type of sampled image with this shape.
+    pub fn glsl_texture(&self) -> String {
+        format!("{}texture{}", self.glsl_prefix(), self.glsl_dim())
+    }
+
+    /// Returns GLSL type of storage image with this shape.
+    pub fn glsl_image(&self) -> String {
+        format!("{}image{}", self.glsl_prefix(), self.glsl_dim())
+    }
+
+    /// Returns GLSL type of uniform texel buffer with this shape.
+    pub fn glsl_texture_buffer(&self) -> String {
+        format!("{}textureBuffer", self.glsl_prefix())
+    }
+
+    /// Returns GLSL type of storage texel buffer with this shape.
+    pub fn glsl_image_buffer(&self) -> String {
+        format!("{}imageBuffer", self.glsl_prefix())
+    }
+
+    /// Returns WGSL type of sampled image with this shape.
+    pub fn wgsl_texture(&self) -> Option<String> {
+        let dim = self.wgsl_dim()?;
+        let sample = match self.sample {
+            SampleType::Float => "f32",
+            SampleType::Sint => "i32",
+            SampleType::Uint => "u32",
+        };
+        Some(format!("texture_{}<{}>", dim, sample))
+    }
+
+    /// Returns WGSL type of storage image with this shape.
+    ///
+    /// Storage textures must have format and can't be cube maps in WGSL.
+    pub fn wgsl_storage_texture(&self) -> Option<String> {
+        if self.kind == Kind::Cube {
+            return None;
+        }
+        let dim = self.wgsl_dim()?;
+        let format = self.format?.wgsl?;
+        Some(format!("texture_storage_{}<{}, read_write>", dim, format))
+    }
+
+    fn glsl_prefix(&self) -> &'static str {
+        match self.sample {
+            SampleType::Float => "",
+            SampleType::Sint => "i",
+            SampleType::Uint => "u",
+        }
+    }
+
+    fn glsl_dim(&self) -> String {
+        let dim = match self.kind {
+            Kind::D1 => "1D",
+            Kind::D2 => "2D",
+            Kind::D3 => "3D",
+            Kind::Cube => "Cube",
+        };
+
+        if self.array {
+            format!("{}Array", dim)
+        } else {
+            dim.to_owned()
+        }
+    }
+
+    fn wgsl_dim(&self) -> Option<&'static str> {
+        Some(match (self.kind, self.array) {
+            (Kind::D1, false) => "1d",
+            (Kind::D1, true) => "1d_array",
+            (Kind::D2, false) => "2d",
+            (Kind::D2, true) => "2d_array",
+            (Kind::D3, false) => "3d",
+            (Kind::D3, true) => return None,
+            (Kind::Cube, false) => "cube",
+            (Kind::Cube, true) => "cube_array",
+        })
+    }
+}
+
+/// Arguments of image or texel buffer descriptor attribute.
+pub(super) struct Args {
+    pub shape: Shape,
+    pub count: Option<syn::Expr>,
+}
+
+/// Arguments accepted by particular attribute.
+#[derive(Clone, Copy)]
+pub(super) struct Accepts {
+    /// `kind = D1|D2|D3|Cube` and `array`.
+    pub kind: bool,
+
+    /// `format = Format`.
+    pub format: bool,
+}
+
+enum Argument {
+    Kind(syn::Ident, Kind),
+    Array(syn::Ident),
+    Sample(syn::Ident, SampleType),
+    Format(syn::Ident, StorageFormat),
+    Count(syn::Expr),
+}
+
+/// Parses `kind = D1|D2|D3|Cube`, `array`, `sample = f32|i32|u32`,
+/// `format = Format` and `count = N` arguments.
+///
+/// `sample` is derived from `format` when the latter is specified.
+pub(super) fn parse_args(attr: &syn::Attribute, accepts: Accepts) -> syn::Result<Args> {
+    if attr.tokens.is_empty() {
+        return Ok(Args {
+            shape: Shape::default(),
+            count: None,
+        });
+    }
+
+    let args = attr.parse_args_with(|stream: syn::parse::ParseStream<'_>| {
+        stream.parse_terminated::<_, syn::Token![,]>(|stream| {
+            let ident = stream.parse::<syn::Ident>()?;
+
+            match ident {
+                ident if ident == "kind" => {
+                    let _eq = stream.parse::<syn::Token![=]>()?;
+                    let value = stream.parse::<syn::Ident>()?;
+                    let kind = match value {
+                        value if value == "D1" => Kind::D1,
+                        value if value == "D2" => Kind::D2,
+                        value if value == "D3" => Kind::D3,
+                        value if value == "Cube" => Kind::Cube,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                "Unrecognized image kind. Expected `D1`, `D2`, `D3` or `Cube`",
+                            ))
+                        }
+                    };
+                    Ok(Argument::Kind(ident, kind))
+                }
+                ident if ident == "array" => Ok(Argument::Array(ident)),
+                ident if ident == "sample" => {
+                    let _eq = stream.parse::<syn::Token![=]>()?;
+                    let value = stream.parse::<syn::Ident>()?;
+                    let sample = match value {
+                        value if value == "f32" => SampleType::Float,
+                        value if value == "i32" => SampleType::Sint,
+                        value if value == "u32" => SampleType::Uint,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                "Unrecognized sample type. Expected `f32`, `i32` or `u32`",
+                            ))
+                        }
+                    };
+                    Ok(Argument::Sample(ident, sample))
+                }
+                ident if ident == "format" => {
+                    let _eq = stream.parse::<syn::Token![=]>()?;
+                    let value = stream.parse::<syn::Ident>()?;
+                    match storage_format(&value.to_string()) {
+                        Some(format) => Ok(Argument::Format(ident, format)),
+                        None => Err(syn::Error::new_spanned(
+                            &value,
+                            format!("Format `{}` can't be used for storage descriptors", value),
+                        )),
+                    }
+                }
+                ident if ident == "count" => {
+                    let _eq = stream.parse::<syn::Token![=]>()?;
+                    let count = stream.parse::<syn::Expr>()?;
+                    Ok(Argument::Count(count))
+                }
+                _ => Err(syn::Error::new_spanned(
+                    &ident,
+                    format!("Unrecognized argument `{}`", ident),
+                )),
+            }
+        })
+    })?;
+
+    for arg in &args {
+        let unexpected = match arg {
+            Argument::Kind(ident, _) | Argument::Array(ident) if !accepts.kind => ident,
+            Argument::Format(ident, _) if !accepts.format => ident,
+            _ => continue,
+        };
+        return Err(syn::Error::new_spanned(
+            unexpected,
+            format!("Unrecognized argument `{}`", unexpected),
+        ));
+    }
+
+    let kind = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Kind(_, kind) => Some(*kind),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `kind` argument",
+    )?;
+
+    let array = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Array(ident) => Some(ident),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `array` argument",
+    )?;
+
+    let sample = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Sample(ident, sample) => Some((ident, *sample)),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `sample` argument",
+    )?;
+
+    let format = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Format(ident, format) => Some((ident, *format)),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `format` argument",
+    )?;
+
+    let count = find_unique(
+        args.iter().filter_map(|arg| match arg {
+            Argument::Count(count) => Some(count.clone()),
+            _ => None,
+        }),
+        attr,
+        "Expected at most one `count` argument",
+    )?;
+
+    if let (Some((ident, _)), Some(_)) = (sample, format) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "`sample` is derived from `format` and can't be specified together with it",
+        ));
+    }
+
+    let kind = kind.unwrap_or(Kind::D2);
+
+    if let (Kind::D3, Some(ident)) = (kind, array) {
+        return Err(syn::Error::new_spanned(ident, "3D images can't be arrayed"));
+    }
+
+    let shape = Shape {
+        kind,
+        array: array.is_some(),
+        sample: match (sample, format) {
+            (Some((_, sample)), _) => sample,
+            (None, Some((_, format))) => format.sample,
+            (None, None) => SampleType::Float,
+        },
+        format: format.map(|(_, format)| format),
+    };
+
+    Ok(Args { shape, count })
+}
+
+/// Maps `sierra::Format` variant name to storage format.
+fn storage_format(name: &str) -> Option<StorageFormat> {
+    use SampleType::*;
+
+    let (glsl, wgsl, sample) = match name {
+        "R8Unorm" => ("r8", Some("r8unorm"), Float),
+        "R8Snorm" => ("r8_snorm", Some("r8snorm"), Float),
+        "R8Uint" => ("r8ui", Some("r8uint"), Uint),
+        "R8Sint" => ("r8i", Some("r8sint"), Sint),
+        "RG8Unorm" => ("rg8", Some("rg8unorm"), Float),
+        "RG8Snorm" => ("rg8_snorm", Some("rg8snorm"), Float),
+        "RG8Uint" => ("rg8ui", Some("rg8uint"), Uint),
+        "RG8Sint" => ("rg8i", Some("rg8sint"), Sint),
+        "RGBA8Unorm" => ("rgba8", Some("rgba8unorm"), Float),
+        "RGBA8Snorm" => ("rgba8_snorm", Some("rgba8snorm"), Float),
+        "RGBA8Uint" => ("rgba8ui", Some("rgba8uint"), Uint),
+        "RGBA8Sint" => ("rgba8i", Some("rgba8sint"), Sint),
+        "R16Unorm" => ("r16", None, Float),
+        "R16Snorm" => ("r16_snorm", None, Float),
+        "R16Uint" => ("r16ui", Some("r16uint"), Uint),
+        "R16Sint" => ("r16i", Some("r16sint"), Sint),
+        "R16Sfloat" => ("r16f", Some("r16float"), Float),
+        "RG16Unorm" => ("rg16", None, Float),
+        "RG16Snorm" => ("rg16_snorm", None, Float),
+        "RG16Uint" => ("rg16ui", Some("rg16uint"), Uint),
+        "RG16Sint" => ("rg16i", Some("rg16sint"), Sint),
+        "RG16Sfloat" => ("rg16f", Some("rg16float"), Float),
+        "RGBA16Unorm" => ("rgba16", None, Float),
+        "RGBA16Snorm" => ("rgba16_snorm", None, Float),
+        "RGBA16Uint" => ("rgba16ui", Some("rgba16uint"), Uint),
+        "RGBA16Sint" => ("rgba16i", Some("rgba16sint"), Sint),
+        "RGBA16Sfloat" => ("rgba16f", Some("rgba16float"), Float),
+        "R32Uint" => ("r32ui", Some("r32uint"), Uint),
+        "R32Sint" => ("r32i", Some("r32sint"), Sint),
+        "R32Sfloat" => ("r32f", Some("r32float"), Float),
+        "RG32Uint" => ("rg32ui", Some("rg32uint"), Uint),
+        "RG32Sint" => ("rg32i", Some("rg32sint"), Sint),
+        "RG32Sfloat" => ("rg32f", Some("rg32float"), Float),
+        "RGBA32Uint" => ("rgba32ui", Some("rgba32uint"), Uint),
+        "RGBA32Sint" => ("rgba32i", Some("rgba32sint"), Sint),
+        "RGBA32Sfloat" => ("rgba32f", Some("rgba32float"), Float),
+        _ => return None,
+    };
+
+    Some(StorageFormat { glsl, wgsl, sample })
+}
@@ -1,10 +1,12 @@
 use {
     super::{
         buffer,
-        layout::layout_type_name,
+        layout::{descriptor_count_tokens, layout_type_name},
         parse::{DescriptorType, Input},
+        texel_buffer,
     },
     proc_macro2::TokenStream,
+    quote::ToTokens as _,
     syn::spanned::Spanned,
 };
 
@@ -39,15 +41,35 @@ pub(super) fn generate(input: &Input) -> TokenStream {
             let descriptor_field = quote::format_ident!("descriptor_{}", input.member);
             let write_descriptor = quote::format_ident!("write_{}_descriptor", input.member);
 
+            // Storage images are accessed in `General` layout.
+            let layout = match input.desc_ty {
+                DescriptorType::StorageImage(_) => quote::quote!(::sierra::Layout::General),
+                _ => quote::quote!(::sierra::Layout::ShaderReadOnlyOptimal),
+            };
+
+            let count = descriptor_count_tokens(input);
+            let field_name = input.member.to_token_stream().to_string();
+
             let stream = quote::quote!(
                 let #write_descriptor;
                 match &elem.#descriptor_field {
                     Some(descriptors) if sierra::TypedDescriptorBinding::eq(&input.#field, descriptors) => {
-                        #write_descriptor = false;
+                        #write_descriptor = ::std::option::Option::None;
                     }
                     _ => {
-                        elem.#descriptor_field = Some(::sierra::TypedDescriptorBinding::get_descriptors(&input.#field, device)?);
-                        #write_descriptor = true;
+                        let descriptors = ::sierra::TypedDescriptorBinding::get_descriptors(&input.#field, device, #layout)?;
+
+                        let new = ::std::convert::AsRef::<[_]>::as_ref(&descriptors);
+                        ::std::assert!(
+                            new.len() <= (#count) as usize,
+                            "Too many descriptors for `{}` binding",
+                            #field_name,
+                        );
+
+                        // Only changed elements are written.
+                        let old = elem.#descriptor_field.as_ref().map(::std::convert::AsRef::<[_]>::as_ref);
+                        #write_descriptor = ::sierra::changed_descriptors_range(old, new);
+                        elem.#descriptor_field = Some(descriptors);
                     }
                 }
             );
@@ -69,6 +91,21 @@ pub(super) fn generate(input: &Input) -> TokenStream {
                 DescriptorType::SampledImage(_) => Some(quote::quote_spanned! {
                     span=> <::sierra::SampledImageDescriptor as ::sierra::TypedDescriptor>::descriptors(descriptors)
                 }),
+                DescriptorType::StorageImage(_) => Some(quote::quote_spanned! {
+                    span=> <::sierra::StorageImageDescriptor as ::sierra::TypedDescriptor>::descriptors(descriptors)
+                }),
+                DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+                    kind: texel_buffer::Kind::Uniform,
+                    ..
+                }) => Some(quote::quote_spanned! {
+                    span=> <::sierra::UniformTexelBufferDescriptor as ::sierra::TypedDescriptor>::descriptors(descriptors)
+                }),
+                DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+                    kind: texel_buffer::Kind::Storage,
+                    ..
+                }) => Some(quote::quote_spanned! {
+                    span=> <::sierra::StorageTexelBufferDescriptor as ::sierra::TypedDescriptor>::descriptors(descriptors)
+                }),
                 DescriptorType::CombinedImageSampler(_) => Some(quote::quote_spanned! {
                     span=> <::sierra::CombinedImageSamplerDescriptor as ::sierra::TypedDescriptor>::descriptors(descriptors)
                 }),
@@ -93,12 +130,13 @@ pub(super) fn generate(input: &Input) -> TokenStream {
             let write_descriptor = quote::format_ident!("write_{}_descriptor", input.member);
 
             let stream = quote::quote!(
-                if #write_descriptor {
-                    let descriptors: &_ = elem.#descriptor_field.as_ref().unwrap();
+                if let ::std::option::Option::Some(range) = #write_descriptor {
+                    let element = range.start as u32;
+                    let descriptors = &::std::convert::AsRef::<[_]>::as_ref(elem.#descriptor_field.as_ref().unwrap())[range];
                     writes.extend(Some(::sierra::WriteDescriptorSet {
                         set: &elem.set,
                         binding: #binding,
-                        element: 0,
+                        element,
                         descriptors: #descriptors,
                     }));
                 }
@@ -3,6 +3,7 @@ use {
         buffer, combined_binding_flags_dedup,
         instance::instance_type_name,
         parse::{Descriptor, DescriptorType, Input},
+        texel_buffer,
     },
     crate::stage::{combined_stages_tokens, combined_stages_tokens_dedup},
     proc_macro2::TokenStream,
@@ -69,12 +70,15 @@ pub(super) fn generate(input: &Input) -> TokenStream {
         }
 
         impl #layout_ident {
+            pub fn info() -> ::sierra::DescriptorSetLayoutInfo {
+                ::sierra::DescriptorSetLayoutInfo {
+                    bindings: ::std::vec![#(#bindings),*],
+                    flags: ::sierra::DescriptorSetLayoutFlags::empty(),
+                }
+            }
+
             pub fn new(device: &::sierra::Device) -> ::std::result::Result<Self, ::sierra::OutOfMemory> {
-                let layout =
-                    device.create_descriptor_set_layout(::sierra::DescriptorSetLayoutInfo {
-                        bindings: ::std::vec![#(#bindings),*],
-                        flags: ::sierra::DescriptorSetLayoutFlags::empty(),
-                    })?;
+                let layout = device.create_descriptor_set_layout(Self::info())?;
 
                 ::std::result::Result::Ok(#layout_ident { layout })
             }
@@ -112,9 +116,24 @@ fn generate_layout_binding(descriptor: &Descriptor, binding: u32) -> TokenStream
         DescriptorType::SampledImage(_) => {
             quote::format_ident!("SampledImage")
         }
+        DescriptorType::StorageImage(_) => {
+            quote::format_ident!("StorageImage")
+        }
         DescriptorType::CombinedImageSampler(_) => {
             quote::format_ident!("CombinedImageSampler")
         }
+        DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Uniform,
+            ..
+        }) => {
+            quote::format_ident!("UniformTexelBuffer")
+        }
+        DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Storage,
+            ..
+        }) => {
+            quote::format_ident!("StorageTexelBuffer")
+        }
         DescriptorType::Buffer(buffer::Buffer {
             kind: buffer::Kind::Uniform,
             ..
@@ -136,14 +155,27 @@ fn generate_layout_binding(descriptor: &Descriptor, binding: u32) -> TokenStream
     let flags = combined_binding_flags_dedup(descriptor.flags.iter().copied());
 
     let ty = &descriptor.field.ty;
+    let count = descriptor_count_tokens(descriptor);
 
     quote::quote!(
         ::sierra::DescriptorSetLayoutBinding {
             binding: #binding,
             ty: ::sierra::DescriptorType::#desc_ty,
-            count: <#ty as ::sierra::TypedDescriptorBinding>::COUNT,
+            count: #count,
             stages: #stages,
-            flags: #flags,
+            flags: #flags | <#ty as ::sierra::TypedDescriptorBinding>::FLAGS,
         }
     )
 }
+
+/// Returns number of descriptors in the binding.
+/// Count specified in attribute takes precedence over count of the field type.
+pub(super) fn descriptor_count_tokens(descriptor: &Descriptor) -> TokenStream {
+    match descriptor.count() {
+        Some(count) => quote::quote!(#count),
+        None => {
+            let ty = &descriptor.field.ty;
+            quote::quote!(<#ty as ::sierra::TypedDescriptorBinding>::COUNT)
+        }
+    }
+}
@@ -1,13 +1,18 @@
 mod acceleration_structure;
 mod buffer;
 mod combined_image_sampler;
+mod glsl;
+mod image;
 mod input;
 mod instance;
 mod layout;
 mod parse;
 mod sampled_image;
 mod sampler;
+mod storage_image;
+mod texel_buffer;
 mod uniform;
+mod wgsl;
 
 use {proc_macro2::TokenStream, quote::TokenStreamExt as _, std::collections::HashSet};
 
@@ -52,6 +57,28 @@ fn combined_binding_flags_dedup(flags: impl IntoIterator<Item = BindingFlag>) ->
     combined_binding_flags(flags)
 }
 
+/// Parses arguments of descriptor attribute
+/// that accepts only optional `count = N` argument.
+fn parse_count_args(attr: &syn::Attribute) -> syn::Result<Option<syn::Expr>> {
+    if attr.tokens.is_empty() {
+        return Ok(None);
+    }
+
+    attr.parse_args_with(|stream: syn::parse::ParseStream<'_>| {
+        let ident = stream.parse::<syn::Ident>()?;
+        if ident != "count" {
+            return Err(syn::Error::new_spanned(
+                &ident,
+                format!("Unrecognized argument `{}`. Expected `count = N`", ident),
+            ));
+        }
+
+        let _eq = stream.parse::<syn::Token![=]>()?;
+        let count = stream.parse::<syn::Expr>()?;
+        Ok(Some(count))
+    })
+}
+
 pub fn descriptors(
     attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
@@ -63,9 +90,18 @@ pub fn descriptors(
                 .chain(Some(input::generate(&input)))
                 .chain(Some(instance::generate(&input)))
                 .chain(Some(layout::generate(&input)))
-                // .chain(Some(generate_glsl_shader_input(&input)))
+                .chain(input.declarations.glsl.then(|| glsl::generate(&input)))
+                .chain(input.declarations.wgsl.then(|| wgsl::generate(&input)))
                 .collect::<proc_macro2::TokenStream>()
         }
         Err(err) => err.into_compile_error(),
     }
 }
+
+/// Returns name of the field to be used in generated shader code.
+fn member_name(member: &syn::Member) -> String {
+    match member {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => format!("_{}", index.index),
+    }
+}
@@ -5,10 +5,16 @@ use {
         combined_image_sampler::{parse_combined_image_sampler_attr, CombinedImageSampler},
         sampled_image::{parse_sampled_image_attr, SampledImage},
         sampler::{parse_sampler_attr, Sampler},
+        storage_image::{parse_storage_image_attr, StorageImage},
+        texel_buffer::{parse_texel_buffer_attr, TexelBuffer},
         uniform::parse_uniform_attr,
         BindingFlag,
     },
-    crate::{find_unique_attribute, stage::Stage, take_attributes},
+    crate::{
+        find_unique_attribute, parse_shader_declarations,
+        stage::{parse_stages, Stage},
+        take_attributes, ShaderDeclarations,
+    },
     std::convert::TryFrom as _,
     syn::spanned::Spanned as _,
 };
@@ -17,6 +23,7 @@ pub struct Input {
     pub descriptors: Vec<Descriptor>,
     pub uniforms: Vec<Uniform>,
     pub item_struct: syn::ItemStruct,
+    pub declarations: ShaderDeclarations,
 }
 
 pub struct Descriptor {
@@ -28,10 +35,33 @@ pub struct Descriptor {
 }
 
 impl Descriptor {
+    /// Returns descriptors count specified in attribute.
+    pub fn count(&self) -> Option<&syn::Expr> {
+        match &self.desc_ty {
+            DescriptorType::Sampler(args) => args.count.as_ref(),
+            DescriptorType::SampledImage(args) => args.count.as_ref(),
+            DescriptorType::StorageImage(args) => args.count.as_ref(),
+            DescriptorType::TexelBuffer(args) => args.count.as_ref(),
+            DescriptorType::Buffer(args) => args.count.as_ref(),
+            DescriptorType::CombinedImageSampler(_) | DescriptorType::AccelerationStructure(_) => {
+                None
+            }
+        }
+    }
+
     fn validate(&self, item_struct: &syn::ItemStruct) -> syn::Result<()> {
+        if self.count().is_none() && is_vec(&self.field.ty) {
+            return Err(syn::Error::new_spanned(
+                &self.field.ty,
+                "`Vec` field requires `count = N` argument to specify number of descriptors in the binding",
+            ));
+        }
+
         match &self.desc_ty {
             DescriptorType::Sampler(args) => args.validate(item_struct),
             DescriptorType::SampledImage(args) => args.validate(item_struct),
+            DescriptorType::StorageImage(args) => args.validate(item_struct),
+            DescriptorType::TexelBuffer(args) => args.validate(item_struct),
             DescriptorType::CombinedImageSampler(args) => args.validate(item_struct),
             DescriptorType::Buffer(args) => args.validate(item_struct),
             DescriptorType::AccelerationStructure(args) => args.validate(item_struct),
@@ -39,6 +69,16 @@ impl Descriptor {
     }
 }
 
+/// Checks if type is `Vec<T>`, which has no descriptors count of its own.
+fn is_vec(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => {
+            matches!(path.path.segments.last(), Some(segment) if segment.ident == "Vec")
+        }
+        _ => false,
+    }
+}
+
 pub struct Uniform {
     pub stages: Vec<Stage>,
     pub field: syn::Field,
@@ -55,18 +95,15 @@ impl Uniform {
 pub enum DescriptorType {
     Sampler(Sampler),
     SampledImage(SampledImage),
+    StorageImage(StorageImage),
     CombinedImageSampler(CombinedImageSampler),
+    TexelBuffer(TexelBuffer),
     Buffer(Buffer),
     AccelerationStructure(AccelerationStructure),
 }
 
 pub fn parse(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> syn::Result<Input> {
-    if !attr.is_empty() {
-        return Err(syn::Error::new_spanned(
-            proc_macro2::TokenStream::from(attr),
-            "#[descriptors] attribute does not accept arguments",
-        ));
-    }
+    let declarations = parse_shader_declarations(attr, "descriptors")?;
 
     let mut item_struct = syn::parse::<syn::ItemStruct>(item)?;
 
@@ -102,13 +139,16 @@ pub fn parse(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> sy
         item_struct,
         descriptors,
         uniforms,
+        declarations,
     })
 }
 
 enum FieldAttribute {
     Sampler(Sampler),
     SampledImage(SampledImage),
+    StorageImage(StorageImage),
     CombinedImageSampler(CombinedImageSampler),
+    TexelBuffer(TexelBuffer),
     Buffer(Buffer),
     AccelerationStructure(AccelerationStructure),
     Uniform,
@@ -130,31 +170,9 @@ fn parse_input_field(field: &mut syn::Field, field_index: u32) -> syn::Result<Op
         Some(ty) => {
             let stages: Vec<_> =
                 take_attributes(&mut field.attrs, |attr| match attr.path.get_ident() {
-                    Some(ident) if ident == "stages" => attr
-                        .parse_args_with(|stream: syn::parse::ParseStream<'_>| {
-                            let stages = stream.parse_terminated::<_, syn::Token![,]>(
-                                |stream| match stream.parse::<syn::Ident>()? {
-                                    i if i == "Vertex" => Ok(Stage::Vertex),
-                                    i if i == "TessellationControl" => {
-                                        Ok(Stage::TessellationControl)
-                                    }
-                                    i if i == "TessellationEvaluation" => {
-                                        Ok(Stage::TessellationEvaluation)
-                                    }
-                                    i if i == "Geometry" => Ok(Stage::Geometry),
-                                    i if i == "Fragment" => Ok(Stage::Fragment),
-                                    i if i == "Compute" => Ok(Stage::Compute),
-                                    i if i == "Raygen" => Ok(Stage::Raygen),
-                                    i if i == "AnyHit" => Ok(Stage::AnyHit),
-                                    i if i == "ClosestHit" => Ok(Stage::ClosestHit),
-                                    i if i == "Miss" => Ok(Stage::Miss),
-                                    i if i == "Intersection" => Ok(Stage::Intersection),
-                                    i => Err(stream.error(format!("Unrecognized stage `{}`", i))),
-                                },
-                            )?;
-                            Ok(stages)
-                        })
-                        .map(Some),
+                    Some(ident) if ident == "stages" => {
+                        attr.parse_args_with(parse_stages).map(Some)
+                    }
                     _ => Ok(None),
                 })?
                 .into_iter()
@@ -208,6 +226,20 @@ fn parse_input_field(field: &mut syn::Field, field_index: u32) -> syn::Result<Op
                     member,
                     field: field.clone(),
                 }),
+                FieldAttribute::StorageImage(value) => Field::Descriptor(Descriptor {
+                    desc_ty: DescriptorType::StorageImage(value),
+                    flags,
+                    stages,
+                    member,
+                    field: field.clone(),
+                }),
+                FieldAttribute::TexelBuffer(value) => Field::Descriptor(Descriptor {
+                    desc_ty: DescriptorType::TexelBuffer(value),
+                    flags,
+                    stages,
+                    member,
+                    field: field.clone(),
+                }),
                 FieldAttribute::CombinedImageSampler(value) => Field::Descriptor(Descriptor {
                     desc_ty: DescriptorType::CombinedImageSampler(value),
                     flags,
@@ -243,7 +275,9 @@ fn parse_input_field(field: &mut syn::Field, field_index: u32) -> syn::Result<Op
 fn parse_input_field_attr(attr: &syn::Attribute) -> syn::Result<Option<FieldAttribute>> {
     on_first_ok!(parse_sampler_attr(attr)?.map(FieldAttribute::Sampler));
     on_first_ok!(parse_sampled_image_attr(attr)?.map(FieldAttribute::SampledImage));
+    on_first_ok!(parse_storage_image_attr(attr)?.map(FieldAttribute::StorageImage));
     on_first_ok!(parse_combined_image_sampler_attr(attr)?.map(FieldAttribute::CombinedImageSampler));
+    on_first_ok!(parse_texel_buffer_attr(attr)?.map(FieldAttribute::TexelBuffer));
     on_first_ok!(parse_buffer_attr(attr)?.map(FieldAttribute::Buffer));
     on_first_ok!(
         parse_acceleration_structure_attr(attr)?.map(FieldAttribute::AccelerationStructure)
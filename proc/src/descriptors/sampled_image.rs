@@ -1,4 +1,9 @@
-pub struct SampledImage;
+use super::image::{parse_args, Accepts, Shape};
+
+pub struct SampledImage {
+    pub shape: Shape,
+    pub count: Option<syn::Expr>,
+}
 
 impl SampledImage {
     #[inline]
@@ -12,12 +17,16 @@ pub(super) fn parse_sampled_image_attr(attr: &syn::Attribute) -> syn::Result<Opt
         return Ok(None);
     }
 
-    if !attr.tokens.is_empty() {
-        return Err(syn::Error::new_spanned(
-            attr,
-            "`sampled_image` attribute does not accept arguments",
-        ));
-    }
+    let args = parse_args(
+        attr,
+        Accepts {
+            kind: true,
+            format: false,
+        },
+    )?;
 
-    Ok(Some(SampledImage))
+    Ok(Some(SampledImage {
+        shape: args.shape,
+        count: args.count,
+    }))
 }
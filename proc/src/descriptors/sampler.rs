@@ -1,4 +1,8 @@
-pub struct Sampler;
+use super::parse_count_args;
+
+pub struct Sampler {
+    pub count: Option<syn::Expr>,
+}
 
 impl Sampler {
     #[inline]
@@ -12,12 +16,7 @@ pub(super) fn parse_sampler_attr(attr: &syn::Attribute) -> syn::Result<Option<Sa
         return Ok(None);
     }
 
-    if !attr.tokens.is_empty() {
-        return Err(syn::Error::new_spanned(
-            attr,
-            "`sampler` attribute does not accept arguments",
-        ));
-    }
+    let count = parse_count_args(attr)?;
 
-    Ok(Some(Sampler))
+    Ok(Some(Sampler { count }))
 }
@@ -0,0 +1,32 @@
+use super::image::{parse_args, Accepts, Shape};
+
+pub struct StorageImage {
+    pub shape: Shape,
+    pub count: Option<syn::Expr>,
+}
+
+impl StorageImage {
+    #[inline]
+    pub fn validate(&self, _item_struct: &syn::ItemStruct) -> syn::Result<()> {
+        Ok(())
+    }
+}
+
+pub(super) fn parse_storage_image_attr(attr: &syn::Attribute) -> syn::Result<Option<StorageImage>> {
+    if !attr.path.is_ident("storage_image") {
+        return Ok(None);
+    }
+
+    let args = parse_args(
+        attr,
+        Accepts {
+            kind: true,
+            format: true,
+        },
+    )?;
+
+    Ok(Some(StorageImage {
+        shape: args.shape,
+        count: args.count,
+    }))
+}
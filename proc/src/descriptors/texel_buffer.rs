@@ -0,0 +1,42 @@
+use super::image::{parse_args, Accepts, Shape};
+
+pub struct TexelBuffer {
+    pub kind: Kind,
+    pub shape: Shape,
+    pub count: Option<syn::Expr>,
+}
+
+impl TexelBuffer {
+    #[inline]
+    pub fn validate(&self, _item_struct: &syn::ItemStruct) -> syn::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Uniform,
+    Storage,
+}
+
+pub(super) fn parse_texel_buffer_attr(attr: &syn::Attribute) -> syn::Result<Option<TexelBuffer>> {
+    let kind = match attr.path.get_ident() {
+        Some(ident) if ident == "uniform_texel_buffer" => Kind::Uniform,
+        Some(ident) if ident == "storage_texel_buffer" => Kind::Storage,
+        _ => return Ok(None),
+    };
+
+    let args = parse_args(
+        attr,
+        Accepts {
+            kind: false,
+            format: matches!(kind, Kind::Storage),
+        },
+    )?;
+
+    Ok(Some(TexelBuffer {
+        kind,
+        shape: args.shape,
+        count: args.count,
+    }))
+}
@@ -0,0 +1,167 @@
+use {
+    super::{
+        buffer, image,
+        layout::descriptor_count_tokens,
+        member_name,
+        parse::{DescriptorType, Input},
+        sampled_image, storage_image, texel_buffer,
+    },
+    proc_macro2::TokenStream,
+    std::convert::TryFrom as _,
+};
+
+pub(super) fn generate(input: &Input) -> TokenStream {
+    let ident = &input.item_struct.ident;
+    let struct_name = syn::LitStr::new(&snake_case(&ident.to_string()), ident.span());
+
+    let descriptors = input
+        .descriptors
+        .iter()
+        .enumerate()
+        .map(|(binding, descriptor)| {
+            let binding = u32::try_from(binding).expect("Too many descriptors");
+            let count = descriptor_count_tokens(descriptor);
+            let name = member_name(&descriptor.member);
+
+            match &descriptor.desc_ty {
+                DescriptorType::Buffer(buffer::Buffer { kind, ty, .. }) => {
+                    let storage = matches!(kind, buffer::Kind::Storage);
+                    quote::quote!(ctx.declare_buffer::<#ty>(group, #binding, #count, #storage, #name);)
+                }
+                DescriptorType::Sampler(_) => {
+                    quote::quote!(ctx.declare_opaque(group, #binding, #count, "sampler", #name);)
+                }
+                DescriptorType::SampledImage(sampled_image::SampledImage { shape, .. }) => {
+                    match shape.wgsl_texture() {
+                        Some(ty) => quote::quote!(ctx.declare_opaque(group, #binding, #count, #ty, #name);),
+                        None => quote::quote!(ctx.declare_unsupported(group, #binding, "sampled image", #name);),
+                    }
+                }
+                DescriptorType::StorageImage(storage_image::StorageImage { shape, .. }) => {
+                    match shape.wgsl_storage_texture() {
+                        Some(ty) => quote::quote!(ctx.declare_opaque(group, #binding, #count, #ty, #name);),
+                        None => {
+                            let what = unsupported_storage_image(shape);
+                            quote::quote!(ctx.declare_unsupported(group, #binding, #what, #name);)
+                        }
+                    }
+                }
+                desc_ty => {
+                    let what = unsupported_descriptor(desc_ty);
+                    quote::quote!(ctx.declare_unsupported(group, #binding, #what, #name);)
+                }
+            }
+        })
+        .collect::<TokenStream>();
+
+    let uniforms = if input.uniforms.is_empty() {
+        TokenStream::new()
+    } else {
+        let binding = u32::try_from(input.descriptors.len()).expect("Too many descriptors");
+        let block_name = syn::LitStr::new(&format!("{}Uniforms", ident), ident.span());
+
+        let struct_align_mask = input
+            .uniforms
+            .iter()
+            .fold(quote::quote!(15), |mut tokens, u| {
+                let ty = &u.field.ty;
+                tokens.extend(
+                    quote::quote!(| <#ty as ::sierra::ShaderRepr<::sierra::Std140>>::ALIGN_MASK),
+                );
+                tokens
+            });
+
+        let members = input.uniforms.iter().enumerate().map(|(index, u)| {
+            let ty = &u.field.ty;
+            let name = member_name(&u.member);
+
+            // First member is aligned as whole structure to get the same size.
+            let align_mask = if index == 0 {
+                struct_align_mask.clone()
+            } else {
+                quote::quote!(<#ty as ::sierra::ShaderRepr<::sierra::Std140>>::ALIGN_MASK)
+            };
+
+            quote::quote!(
+                ctx.add::<#ty, ::sierra::Std140>();
+                members.push_str(&::sierra::wgsl::struct_member::<#ty, ::sierra::Std140>(#name, #align_mask));
+            )
+        });
+
+        quote::quote!(
+            let mut members = ::std::string::String::new();
+            #(#members)*
+            ctx.declare_block(
+                &::std::format!("[[group({}), binding({})]]", group, #binding),
+                "uniform",
+                #block_name,
+                &members,
+                &::std::format!("{}_uniforms", name),
+            );
+        )
+    };
+
+    quote::quote!(
+        impl ::sierra::wgsl::WgslDescriptors for #ident {
+            fn wgsl_declarations(
+                group: u32,
+                name: &str,
+                ctx: &mut ::sierra::wgsl::WgslTypeContext,
+            ) {
+                let _ = name;
+                #descriptors
+                #uniforms
+            }
+        }
+
+        impl ::sierra::wgsl::WgslInclude for #ident {
+            fn wgsl() -> ::std::string::String {
+                let mut ctx = ::sierra::wgsl::WgslTypeContext::new();
+                <Self as ::sierra::wgsl::WgslDescriptors>::wgsl_declarations(0, #struct_name, &mut ctx);
+                ctx.code()
+            }
+        }
+    )
+}
+
+fn unsupported_storage_image(shape: &image::Shape) -> &'static str {
+    match shape.format {
+        _ if shape.kind == image::Kind::Cube => "cube storage image",
+        None => "storage image without format",
+        Some(_) => "storage image with format",
+    }
+}
+
+fn unsupported_descriptor(desc_ty: &DescriptorType) -> &'static str {
+    match desc_ty {
+        DescriptorType::CombinedImageSampler(_) => "combined image sampler",
+        DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Uniform,
+            ..
+        }) => "uniform texel buffer",
+        DescriptorType::TexelBuffer(texel_buffer::TexelBuffer {
+            kind: texel_buffer::Kind::Storage,
+            ..
+        }) => "storage texel buffer",
+        DescriptorType::AccelerationStructure(_) => "acceleration structure",
+        DescriptorType::Sampler(_)
+        | DescriptorType::SampledImage(_)
+        | DescriptorType::StorageImage(_)
+        | DescriptorType::Buffer(_) => unreachable!("Descriptor is supported in WGSL"),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index != 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -36,23 +36,25 @@ pub fn graphics_pipeline_desc(tokens: proc_macro::TokenStream) -> TokenStream {
                 &input.front_face,
                 &input.culling,
                 &input.polygon_mode,
+                &input.multisample,
                 &input.depth_test,
                 &input.stencil_tests,
                 &input.depth_bounds,
                 &input.fragment_shader,
                 &input.color_blend,
             ) {
-                (Some(_), Some((field, _)), _, _, _, _, _, _, _, _, _, _)
-                | (Some(_), _, Some((field, _)), _, _, _, _, _, _, _, _, _)
-                | (Some(_), _, _, Some((field, _)), _, _, _, _, _, _, _, _)
-                | (Some(_), _, _, _, Some((field, _)), _, _, _, _, _, _, _)
-                | (Some(_), _, _, _, _, Some((field, _)), _, _, _, _, _, _)
-                | (Some(_), _, _, _, _, _, Some((field, _)), _, _, _, _, _)
-                | (Some(_), _, _, _, _, _, _, Some((field, _)), _, _, _, _)
-                | (Some(_), _, _, _, _, _, _, _, Some((field, _)), _, _, _)
-                | (Some(_), _, _, _, _, _, _, _, _, Some((field, _)), _, _)
-                | (Some(_), _, _, _, _, _, _, _, _, _, Some((field, _)), _)
-                | (Some(_), _, _, _, _, _, _, _, _, _, _, Some((field, _))) => {
+                (Some(_), Some((field, _)), _, _, _, _, _, _, _, _, _, _, _)
+                | (Some(_), _, Some((field, _)), _, _, _, _, _, _, _, _, _, _)
+                | (Some(_), _, _, Some((field, _)), _, _, _, _, _, _, _, _, _)
+                | (Some(_), _, _, _, Some((field, _)), _, _, _, _, _, _, _, _)
+                | (Some(_), _, _, _, _, Some((field, _)), _, _, _, _, _, _, _)
+                | (Some(_), _, _, _, _, _, Some((field, _)), _, _, _, _, _, _)
+                | (Some(_), _, _, _, _, _, _, Some((field, _)), _, _, _, _, _)
+                | (Some(_), _, _, _, _, _, _, _, Some((field, _)), _, _, _, _)
+                | (Some(_), _, _, _, _, _, _, _, _, Some((field, _)), _, _, _)
+                | (Some(_), _, _, _, _, _, _, _, _, _, Some((field, _)), _, _)
+                | (Some(_), _, _, _, _, _, _, _, _, _, _, Some((field, _)), _)
+                | (Some(_), _, _, _, _, _, _, _, _, _, _, _, Some((field, _))) => {
                     return syn::Error::new_spanned(
                         field,
                         "`rasterizer` field must not be specified with any of its subfields",
@@ -73,6 +75,7 @@ pub fn graphics_pipeline_desc(tokens: proc_macro::TokenStream) -> TokenStream {
                     None,
                     None,
                     None,
+                    None,
                 ) => {
                     quote::quote! {
                         ::std::option::Option::Some(#rasterizer)
@@ -86,6 +89,7 @@ pub fn graphics_pipeline_desc(tokens: proc_macro::TokenStream) -> TokenStream {
                     front_face,
                     culling,
                     polygon_mode,
+                    multisample,
                     depth_test,
                     stencil_tests,
                     depth_bounds,
@@ -98,6 +102,7 @@ pub fn graphics_pipeline_desc(tokens: proc_macro::TokenStream) -> TokenStream {
                     let front_face = front_face.as_ref().map(|(_, v)| v).unwrap_or(&default);
                     let culling = culling.as_ref().map(|(_, v)| v).unwrap_or(&default);
                     let polygon_mode = polygon_mode.as_ref().map(|(_, v)| v).unwrap_or(&default);
+                    let multisample = multisample.as_ref().map(|(_, v)| v).unwrap_or(&default);
                     let depth_test = depth_test.as_ref().map(|(_, v)| v).unwrap_or(&default);
                     let stencil_tests = stencil_tests.as_ref().map(|(_, v)| v).unwrap_or(&default);
                     let depth_bounds = depth_bounds.as_ref().map(|(_, v)| v).unwrap_or(&default);
@@ -116,6 +121,7 @@ pub fn graphics_pipeline_desc(tokens: proc_macro::TokenStream) -> TokenStream {
                             front_face: #front_face,
                             culling: #culling,
                             polygon_mode: #polygon_mode,
+                            multisample: #multisample,
                             depth_test: #depth_test,
                             stencil_tests: #stencil_tests,
                             depth_bounds: #depth_bounds,
@@ -178,6 +184,7 @@ struct GraphicsPipelineInput {
     front_face: Option<(syn::Ident, syn::Expr)>,
     culling: Option<(syn::Ident, syn::Expr)>,
     polygon_mode: Option<(syn::Ident, syn::Expr)>,
+    multisample: Option<(syn::Ident, syn::Expr)>,
     depth_test: Option<(syn::Ident, syn::Expr)>,
     stencil_tests: Option<(syn::Ident, syn::Expr)>,
     depth_bounds: Option<(syn::Ident, syn::Expr)>,
@@ -207,6 +214,7 @@ fn parse(tokens: proc_macro::TokenStream) -> syn::Result<GraphicsPipelineInput>
     let mut front_face = None;
     let mut culling = None;
     let mut polygon_mode = None;
+    let mut multisample = None;
     let mut depth_test = None;
     let mut stencil_tests = None;
     let mut depth_bounds = None;
@@ -233,6 +241,7 @@ fn parse(tokens: proc_macro::TokenStream) -> syn::Result<GraphicsPipelineInput>
             syn::Member::Named(member) if member == "front_face" => { front_face = Some((member.clone(), field.expr)); }
             syn::Member::Named(member) if member == "culling" => { culling = Some((member.clone(), field.expr)); }
             syn::Member::Named(member) if member == "polygon_mode" => { polygon_mode = Some((member.clone(), field.expr)); }
+            syn::Member::Named(member) if member == "multisample" => { multisample = Some((member.clone(), field.expr)); }
             syn::Member::Named(member) if member == "depth_test" => { depth_test = Some((member.clone(), field.expr)); }
             syn::Member::Named(member) if member == "stencil_tests" => { stencil_tests = Some((member.clone(), field.expr)); }
             syn::Member::Named(member) if member == "depth_bounds" => { depth_bounds = Some((member.clone(), field.expr)); }
@@ -270,6 +279,7 @@ fn parse(tokens: proc_macro::TokenStream) -> syn::Result<GraphicsPipelineInput>
         front_face,
         culling,
         polygon_mode,
+        multisample,
         depth_test,
         stencil_tests,
         depth_bounds,
@@ -0,0 +1,316 @@
+use {
+    proc_macro2::{Span, TokenStream},
+    std::path::{Path, PathBuf},
+    syn::{
+        parse::{Parse, ParseStream},
+        punctuated::Punctuated,
+    },
+};
+
+struct Input {
+    path: syn::LitStr,
+    defines: Vec<(syn::Ident, syn::LitStr)>,
+}
+
+impl Parse for Input {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let path = stream.parse::<syn::LitStr>()?;
+
+        let mut defines = Vec::new();
+        if stream.parse::<Option<syn::Token![,]>>()?.is_some() {
+            let pairs = Punctuated::<Define, syn::Token![,]>::parse_terminated(stream)?;
+            defines.extend(pairs.into_iter().map(|define| (define.name, define.value)));
+        }
+
+        Ok(Input { path, defines })
+    }
+}
+
+struct Define {
+    name: syn::Ident,
+    value: syn::LitStr,
+}
+
+impl Parse for Define {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let name = stream.parse()?;
+        let _ = stream.parse::<syn::Token![=]>()?;
+        let value = stream.parse()?;
+        Ok(Define { name, value })
+    }
+}
+
+pub fn include_shader(tokens: proc_macro::TokenStream) -> TokenStream {
+    match syn::parse(tokens).and_then(|input| generate(&input)) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn generate(input: &Input) -> syn::Result<TokenStream> {
+    let span = input.path.span();
+    let path = shader_path(&input.path);
+
+    let (code, files) = if path.extension() == Some("spv".as_ref()) {
+        if let Some((name, _)) = input.defines.first() {
+            return Err(syn::Error::new(
+                name.span(),
+                "Defines are not supported for SPIR-V shaders",
+            ));
+        }
+
+        let code = std::fs::read(&path).map_err(|err| {
+            syn::Error::new(
+                span,
+                format!("Failed to read `{}`: {}", path.display(), err),
+            )
+        })?;
+        (code, vec![path])
+    } else {
+        let shader = load_shader(&path, &input.defines, span)?;
+
+        let spv = naga::back::spv::write_vec(
+            &shader.module,
+            &shader.info,
+            &naga::back::spv::Options::default(),
+        )
+        .map_err(|err| {
+            syn::Error::new(
+                span,
+                format!("Failed to write SPIR-V for `{}`: {}", path.display(), err),
+            )
+        })?;
+
+        let code = spv.iter().flat_map(|word| word.to_le_bytes()).collect();
+        (code, shader.files)
+    };
+
+    let track = track_files(&files);
+    let len = code.len();
+
+    Ok(quote::quote!({
+        #track
+        static SPIRV: [u8; #len] = [#(#code),*];
+        ::sierra::ShaderModuleInfo::spirv(&SPIRV[..])
+    }))
+}
+
+/// Shader module parsed and validated by naga.
+pub(crate) struct ShaderFile {
+    pub module: naga::Module,
+    pub info: naga::valid::ModuleInfo,
+
+    /// Shader file and all files it includes.
+    pub files: Vec<PathBuf>,
+}
+
+/// Returns path to shader file relative to the crate root.
+pub(crate) fn shader_path(path: &syn::LitStr) -> PathBuf {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    root.join(path.value())
+}
+
+/// Loads shader file with naga.
+/// Language is determined by file extension.
+/// `#include` directives are expanded in GLSL shaders.
+pub(crate) fn load_shader(
+    path: &Path,
+    defines: &[(syn::Ident, syn::LitStr)],
+    span: Span,
+) -> syn::Result<ShaderFile> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+
+    let stage = match extension {
+        "spv" | "wgsl" => None,
+        "vert" => Some(naga::ShaderStage::Vertex),
+        "frag" => Some(naga::ShaderStage::Fragment),
+        "comp" => Some(naga::ShaderStage::Compute),
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "Unsupported shader file extension. Expected one of `spv`, `wgsl`, `vert`, `frag` or `comp`.\n\
+                 Shaders in other languages and stages must be compiled at runtime with `ShaderCompiler`",
+            ))
+        }
+    };
+
+    if stage.is_none() {
+        if let Some((name, _)) = defines.first() {
+            return Err(syn::Error::new(
+                name.span(),
+                "Defines are supported only for GLSL shaders",
+            ));
+        }
+    }
+
+    let mut files = Vec::new();
+
+    let module = if extension == "spv" {
+        let code = std::fs::read(path).map_err(|err| {
+            syn::Error::new(
+                span,
+                format!("Failed to read `{}`: {}", path.display(), err),
+            )
+        })?;
+        files.push(path.to_owned());
+
+        naga::front::spv::parse_u8_slice(
+            &code,
+            &naga::front::spv::Options {
+                adjust_coordinate_space: false,
+                strict_capabilities: false,
+                flow_graph_dump_prefix: None,
+            },
+        )
+        .map_err(|err| {
+            syn::Error::new(
+                span,
+                format!("Failed to parse `{}`: {:?}", path.display(), err),
+            )
+        })?
+    } else {
+        let code = std::fs::read_to_string(path).map_err(|err| {
+            syn::Error::new(
+                span,
+                format!("Failed to read `{}`: {}", path.display(), err),
+            )
+        })?;
+        files.push(path.to_owned());
+
+        let source = match stage {
+            Some(_) => expand_includes(&code, path, defines, &mut files, span)?,
+            None => code,
+        };
+
+        match stage {
+            Some(stage) => naga::front::glsl::Parser::default()
+                .parse(
+                    &naga::front::glsl::Options {
+                        stage,
+                        defines: defines
+                            .iter()
+                            .map(|(name, value)| (name.to_string(), value.value()))
+                            .collect(),
+                    },
+                    &source,
+                )
+                .map_err(|errors| {
+                    let mut msg = format!("Failed to parse `{}`:", path.display());
+                    for error in errors {
+                        msg.push_str(&format!("\n{:?}", error));
+                    }
+                    syn::Error::new(span, msg)
+                })?,
+            None => naga::front::wgsl::parse_str(&source).map_err(|err| {
+                syn::Error::new(span, err.emit_to_string(&path.display().to_string()))
+            })?,
+        }
+    };
+
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|err| {
+        let mut msg = format!("Shader `{}` is invalid: {:?}", path.display(), err);
+        if let naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidArrayBaseType(base),
+            ..
+        } = err
+        {
+            if matches!(
+                module.types[base].inner,
+                naga::TypeInner::Image { .. } | naga::TypeInner::Sampler { .. }
+            ) {
+                msg.push_str("\nArrays of images and samplers are not supported by naga yet");
+            }
+        }
+        syn::Error::new(span, msg)
+    })?;
+
+    Ok(ShaderFile {
+        module,
+        info,
+        files,
+    })
+}
+
+/// Makes cargo rebuild the crate when shader files change.
+pub(crate) fn track_files(files: &[PathBuf]) -> TokenStream {
+    let files = files.iter().map(|file| file.to_string_lossy().into_owned());
+    quote::quote!(#(const _: &[u8] = ::std::include_bytes!(#files);)*)
+}
+
+/// Replaces `#include` directives with content of included files.
+/// Included paths are relative to the including file.
+fn expand_includes(
+    code: &str,
+    path: &Path,
+    defines: &[(syn::Ident, syn::LitStr)],
+    files: &mut Vec<PathBuf>,
+    span: Span,
+) -> syn::Result<String> {
+    let defines = defines
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.value()));
+
+    let mut resolver = IncludeFiles { files, error: None };
+
+    match sierra_include::expand(code, Some(&path.to_owned()), defines, &mut resolver) {
+        Ok(source) => Ok(source.into_owned()),
+        Err(sierra_include::Error::Unresolved { path: include }) => {
+            let msg = match resolver.error {
+                Some((file, err)) if file.ends_with(&include) => {
+                    format!("Failed to read `{}`: {}", file.display(), err)
+                }
+                _ => format!("Failed to resolve `#include` of `{}`", include),
+            };
+            Err(syn::Error::new(
+                span,
+                format!("{} in `{}`", msg, path.display()),
+            ))
+        }
+        Err(err) => Err(syn::Error::new(
+            span,
+            format!("{} in `{}`", err, path.display()),
+        )),
+    }
+}
+
+/// Reads included files relative to the including file
+/// and records them for `track_files`.
+struct IncludeFiles<'a> {
+    files: &'a mut Vec<PathBuf>,
+
+    /// Last failure to read included file.
+    error: Option<(PathBuf, std::io::Error)>,
+}
+
+impl sierra_include::Resolver for IncludeFiles<'_> {
+    type File = PathBuf;
+
+    fn resolve(&mut self, path: &str, includer: Option<&PathBuf>) -> Option<(PathBuf, String)> {
+        let file = includer
+            .and_then(|includer| includer.parent())
+            .unwrap_or_else(|| Path::new(""))
+            .join(path);
+
+        match std::fs::read_to_string(&file) {
+            Ok(content) => {
+                self.files.push(file.clone());
+                Some((file, content))
+            }
+            Err(err) => {
+                self.error = Some((file, err));
+                None
+            }
+        }
+    }
+}
@@ -12,11 +12,37 @@ macro_rules! on_first_ok {
 
 mod descriptors;
 mod graphics_pipeline;
+mod include_shader;
 mod pass;
 mod pipeline;
 mod repr;
+mod shader_descriptors;
 mod stage;
 
+/// Generates descriptor set layout and instance for the struct.
+///
+/// `glsl` and `wgsl` arguments also generate GLSL and WGSL declarations
+/// of the set. They require shader types for buffers and uniforms.
+///
+/// Image types in declarations are selected with `kind = D1|D2|D3|Cube`, `array`
+/// and `sample = f32|i32|u32` arguments of `#[sampled_image]` and `#[storage_image]`,
+/// defaulting to 2D float image. `#[storage_image]` and `#[storage_texel_buffer]`
+/// accept `format = Format` that sets format layout qualifier and sample type.
+/// Storage images without format are declared without qualifier in GLSL
+/// and are not supported in WGSL.
+///
+/// ```ignore
+/// #[sierra::descriptors(glsl, wgsl)]
+/// pub struct Material {
+///     #[sampled_image]
+///     #[stages(Fragment)]
+///     pub albedo: sierra::ImageView,
+///
+///     #[storage_image(kind = D3, format = RGBA16Sfloat)]
+///     #[stages(Compute)]
+///     pub radiance: sierra::ImageView,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn descriptors(
     attr: proc_macro::TokenStream,
@@ -25,6 +51,61 @@ pub fn descriptors(
     descriptors::descriptors(attr, item).into()
 }
 
+/// Generates std140 and std430 representations of the struct.
+///
+/// `assert_size` and `assert_size_std430` arguments and
+/// `#[offset = N]` and `#[offset_std430 = N]` field attributes
+/// fail compilation if layout does not match.
+///
+/// ```ignore
+/// #[sierra::shader_repr(assert_size = 32)]
+/// pub struct Light {
+///     pub pos: sierra::vec3,
+///     #[offset = 16]
+///     pub color: sierra::vec3,
+/// }
+/// ```
+///
+/// Last field may be `RuntimeArray<T>`. Such struct is represented
+/// only in std430 layout, implements `ShaderReprRuntime` instead of `ShaderRepr`
+/// and is inlined into storage buffer block in generated shader declarations.
+///
+/// ```ignore
+/// #[sierra::shader_repr]
+/// pub struct Particles {
+///     pub count: u32,
+///     pub items: sierra::RuntimeArray<Particle>,
+/// }
+///
+/// device.write_buffer(&mut buffer, 0, &particles.to_repr_bytes())?;
+/// ```
+///
+/// Fields of tuple structs are named `_0`, `_1`, etc. in shaders.
+/// `bool` fields are represented as `boolean`.
+///
+/// Generic struct is represented for each `instance = Type` argument.
+/// Instances are named in shaders after the struct and its generic arguments,
+/// e.g. `MaterialVec3` for `Material<sierra::vec3>`.
+///
+/// ```ignore
+/// #[sierra::shader_repr(instance = Material<f32>, instance = Material<sierra::vec3>)]
+/// pub struct Material<T> {
+///     pub factor: T,
+///     pub double_sided: bool,
+/// }
+/// ```
+///
+/// Fieldless enums are represented as `u32`, and their variants
+/// are declared as constants in shaders, e.g. `LightKind_Spot`.
+///
+/// ```ignore
+/// #[sierra::shader_repr]
+/// #[repr(u32)]
+/// pub enum LightKind {
+///     Point,
+///     Spot,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn shader_repr(
     attr: proc_macro::TokenStream,
@@ -33,6 +114,10 @@ pub fn shader_repr(
     repr::shader_repr(attr, item).into()
 }
 
+/// Generates pipeline layout for the struct with `#[set]` fields.
+///
+/// `glsl` and `wgsl` arguments also generate GLSL and WGSL declarations
+/// of all sets and push constants. Sets must be generated with the same arguments.
 #[proc_macro_attribute]
 pub fn pipeline(
     attr: proc_macro::TokenStream,
@@ -54,6 +139,86 @@ pub fn graphics_pipeline_desc(item: proc_macro::TokenStream) -> proc_macro::Toke
     graphics_pipeline::graphics_pipeline_desc(item).into()
 }
 
+/// Generates `#[descriptors]` struct from bindings of descriptor set
+/// declared in WGSL, GLSL or SPIR-V shader file.
+///
+/// Path is relative to the crate root.
+/// Set index defaults to 0. Preprocessor definitions for GLSL may follow.
+/// Bindings of the set must be contiguous starting from 0.
+/// Runtime-sized arrays become `Vec` fields with number of descriptors
+/// specified by `count = N` argument.
+///
+/// ```ignore
+/// #[sierra::shader_descriptors("shaders/main.wgsl", set = 1)]
+/// pub struct Material;
+/// ```
+#[proc_macro_attribute]
+pub fn shader_descriptors(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    shader_descriptors::shader_descriptors(attr, item).into()
+}
+
+/// Compiles WGSL or GLSL shader file into SPIR-V at build time
+/// and expands to `ShaderModuleInfo` with embedded SPIR-V.
+///
+/// Path is relative to the crate root.
+/// GLSL stage is determined by file extension: `vert`, `frag` or `comp`.
+/// Preprocessor definitions for GLSL may follow the path.
+/// `#include` directives in GLSL are resolved relative to the including file.
+///
+/// ```ignore
+/// let info = sierra::include_shader!("shaders/main.frag", SHADOWS = "1");
+/// ```
+#[proc_macro]
+pub fn include_shader(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    include_shader::include_shader(item).into()
+}
+
+/// Shader declarations requested in `#[descriptors]` or `#[pipeline]` arguments.
+#[derive(Clone, Copy, Default)]
+struct ShaderDeclarations {
+    glsl: bool,
+    wgsl: bool,
+}
+
+fn parse_shader_declarations(
+    attr: proc_macro::TokenStream,
+    macro_name: &str,
+) -> syn::Result<ShaderDeclarations> {
+    let args = syn::parse::Parser::parse(
+        syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated,
+        attr,
+    )?;
+
+    let mut declarations = ShaderDeclarations::default();
+    for arg in args {
+        let flag = if arg == "glsl" {
+            &mut declarations.glsl
+        } else if arg == "wgsl" {
+            &mut declarations.wgsl
+        } else {
+            return Err(syn::Error::new_spanned(
+                &arg,
+                format!(
+                    "Unrecognized #[{}] argument `{}`. Expected `glsl` or `wgsl`",
+                    macro_name, arg
+                ),
+            ));
+        };
+
+        if std::mem::replace(flag, true) {
+            return Err(syn::Error::new_spanned(
+                &arg,
+                format!("Duplicate #[{}] argument `{}`", macro_name, arg),
+            ));
+        }
+    }
+
+    Ok(declarations)
+}
+
 fn take_attributes<T>(
     attrs: &mut Vec<syn::Attribute>,
     mut f: impl FnMut(&syn::Attribute) -> syn::Result<Option<T>>,
@@ -1,7 +1,7 @@
 use {
-    super::parse::{ClearValue, Input, Layout, LoadOp, StoreOp},
+    super::parse::{ClearValue, Input, Layout, LoadOp, StoreOp, Subpass},
     proc_macro2::TokenStream,
-    std::convert::TryFrom,
+    std::{collections::BTreeSet, convert::TryFrom},
 };
 
 pub(super) fn generate(input: &Input) -> TokenStream {
@@ -64,12 +64,14 @@ pub(super) fn generate(input: &Input) -> TokenStream {
                         .subpasses
                         .iter()
                         .filter_map(|s| {
-                            if s.colors.iter().any(|&c| c == index) {
+                            if s.colors.iter().any(|&c| c == index) || s.resolves.contains(&index) {
                                 Some(quote::quote!(::sierra::Layout::ColorAttachmentOptimal))
                             } else if s.depth == Some(index) {
                                 Some(quote::quote!(
                                     ::sierra::Layout::DepthStencilAttachmentOptimal
                                 ))
+                            } else if s.inputs.contains(&index) {
+                                Some(input_layout(member))
                             } else {
                                 None
                             }
@@ -111,34 +113,68 @@ pub(super) fn generate(input: &Input) -> TokenStream {
                 )
                 .collect::<TokenStream>();
 
+            let push_inputs = s
+                .inputs
+                .iter()
+                .map(|&i| {
+                    let layout = input_layout(&input.attachments[i as usize].member);
+                    quote::quote!(inputs.push((#i, #layout));)
+                })
+                .collect::<TokenStream>();
+
+            let push_resolves = s
+                .resolves
+                .iter()
+                .map(
+                    |&r| quote::quote!(resolves.push((#r, ::sierra::Layout::ColorAttachmentOptimal));),
+                )
+                .collect::<TokenStream>();
+
             let color_count = s.colors.len();
+            let input_count = s.inputs.len();
+            let resolve_count = s.resolves.len();
 
-            match s.depth {
-                Some(depth) => {
-                    quote::quote!(
-                        subpasses.push(::sierra::Subpass {
-                            colors: {
-                                let mut colors = ::std::vec::Vec::with_capacity(#color_count);
-                                #push_colors
-                                colors
-                            },
-                            depth: Some((#depth, ::sierra::Layout::DepthStencilAttachmentOptimal)),
-                        });
-                    )
-                }
-                None => {
-                    quote::quote!(
-                        subpasses.push(::sierra::Subpass {
-                            colors: {
-                                let mut colors = ::std::vec::Vec::with_capacity(#color_count);
-                                #push_colors
-                                colors
-                            },
-                            depth: None,
-                        });
-                    )
-                }
-            }
+            let depth = match s.depth {
+                Some(depth) => quote::quote!(Some((#depth, ::sierra::Layout::DepthStencilAttachmentOptimal))),
+                None => quote::quote!(None),
+            };
+
+            quote::quote!(
+                subpasses.push(::sierra::Subpass {
+                    colors: {
+                        let mut colors = ::std::vec::Vec::with_capacity(#color_count);
+                        #push_colors
+                        colors
+                    },
+                    depth: #depth,
+                    inputs: {
+                        let mut inputs = ::std::vec::Vec::with_capacity(#input_count);
+                        #push_inputs
+                        inputs
+                    },
+                    resolves: {
+                        let mut resolves = ::std::vec::Vec::with_capacity(#resolve_count);
+                        #push_resolves
+                        resolves
+                    },
+                });
+            )
+        })
+        .collect::<TokenStream>();
+
+    let dependencies = subpass_dependencies(&input.subpasses, input.attachments.len());
+    let dependency_count = dependencies.len();
+    let push_dependencies = dependencies
+        .iter()
+        .map(|(src, dst, src_stages, dst_stages)| {
+            quote::quote!(
+                dependencies.push(::sierra::SubpassDependency {
+                    src: Some(#src),
+                    dst: Some(#dst),
+                    src_stages: #src_stages,
+                    dst_stages: #dst_stages,
+                });
+            )
         })
         .collect::<TokenStream>();
 
@@ -175,12 +211,14 @@ pub(super) fn generate(input: &Input) -> TokenStream {
 
             let member = &a.member;
             let usages = input.subpasses.iter().filter_map(|s| {
-                if s.colors.iter().any(|&c| c == index) {
+                if s.colors.iter().any(|&c| c == index) || s.resolves.contains(&index) {
                     Some(quote::quote!(::sierra::ImageUsage::COLOR_ATTACHMENT))
                 } else if s.depth == Some(index) {
                     Some(quote::quote!(
                         ::sierra::ImageUsage::DEPTH_STENCIL_ATTACHMENT
                     ))
+                } else if s.inputs.contains(&index) {
+                    Some(quote::quote!(::sierra::ImageUsage::INPUT_ATTACHMENT))
                 } else {
                     None
                 }
@@ -193,8 +231,6 @@ pub(super) fn generate(input: &Input) -> TokenStream {
         .collect::<TokenStream>();
 
     let subpass_count = input.subpasses.len();
-    // let dependency_count = input.dependencies.len();
-    let dependency_count = 0usize;
 
     let clear_values = input
         .attachments
@@ -245,6 +281,7 @@ pub(super) fn generate(input: &Input) -> TokenStream {
                     #push_subpass_infos
 
                     let mut dependencies = ::std::vec::Vec::with_capacity(#dependency_count);
+                    #push_dependencies
 
                     let render_pass = self.render_pass.get_or_insert(::sierra::Device::create_render_pass(
                         device,
@@ -368,3 +405,65 @@ fn final_layout(store_op: &StoreOp) -> Option<TokenStream> {
         StoreOp::Store(Layout::Member(layout)) => Some(quote::quote!(self.#layout)),
     }
 }
+
+fn input_layout(member: &syn::Member) -> TokenStream {
+    quote::quote!(
+        if ::sierra::Format::is_color(&::sierra::Attachment::format(&input.#member)) {
+            ::sierra::Layout::ShaderReadOnlyOptimal
+        } else {
+            ::sierra::Layout::DepthStencilReadOnlyOptimal
+        }
+    )
+}
+
+/// Stages at which subpass accesses attachment.
+fn attachment_stages(subpass: &Subpass, index: u32, stages: &mut BTreeSet<&'static str>) {
+    if subpass.colors.contains(&index) || subpass.resolves.contains(&index) {
+        stages.insert("COLOR_ATTACHMENT_OUTPUT");
+    }
+    if subpass.depth == Some(index) {
+        stages.insert("EARLY_FRAGMENT_TESTS");
+        stages.insert("LATE_FRAGMENT_TESTS");
+    }
+    if subpass.inputs.contains(&index) {
+        stages.insert("FRAGMENT_SHADER");
+    }
+}
+
+fn stage_flags(stages: &BTreeSet<&'static str>) -> TokenStream {
+    let stages = stages.iter().map(|stage| quote::format_ident!("{}", stage));
+    quote::quote!(#(::sierra::PipelineStageFlags::#stages)|*)
+}
+
+/// Generates dependency between each pair of subpasses that access same attachment.
+fn subpass_dependencies(
+    subpasses: &[Subpass],
+    attachment_count: usize,
+) -> Vec<(u32, u32, TokenStream, TokenStream)> {
+    let mut dependencies = Vec::new();
+
+    for (dst, dst_subpass) in subpasses.iter().enumerate() {
+        for (src, src_subpass) in subpasses[..dst].iter().enumerate() {
+            let mut src_stages = BTreeSet::new();
+            let mut dst_stages = BTreeSet::new();
+
+            for index in 0..u32::try_from(attachment_count).unwrap() {
+                if src_subpass.uses(index) && dst_subpass.uses(index) {
+                    attachment_stages(src_subpass, index, &mut src_stages);
+                    attachment_stages(dst_subpass, index, &mut dst_stages);
+                }
+            }
+
+            if !src_stages.is_empty() {
+                dependencies.push((
+                    u32::try_from(src).unwrap(),
+                    u32::try_from(dst).unwrap(),
+                    stage_flags(&src_stages),
+                    stage_flags(&dst_stages),
+                ));
+            }
+        }
+    }
+
+    dependencies
+}
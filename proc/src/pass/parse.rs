@@ -66,11 +66,25 @@ impl Attachment {
 pub struct Subpass {
     pub colors: Vec<u32>,
     pub depth: Option<u32>,
+    pub inputs: Vec<u32>,
+    pub resolves: Vec<u32>,
+}
+
+impl Subpass {
+    /// Returns `true` if attachment is referenced by this subpass.
+    pub fn uses(&self, index: u32) -> bool {
+        self.colors.contains(&index)
+            || self.depth == Some(index)
+            || self.inputs.contains(&index)
+            || self.resolves.contains(&index)
+    }
 }
 
 struct SubpassAttribute {
     pub colors: Vec<syn::Member>,
     pub depth: Option<syn::Member>,
+    pub inputs: Vec<syn::Member>,
+    pub resolves: Vec<syn::Member>,
 }
 
 impl SubpassAttribute {
@@ -79,61 +93,64 @@ impl SubpassAttribute {
         attachments: &[Attachment],
         item_struct: &syn::ItemStruct,
     ) -> syn::Result<Subpass> {
-        let mut unique = HashSet::with_capacity(self.colors.len() + self.depth.is_some() as usize);
-
-        let mut color_indices = Vec::with_capacity(self.colors.len());
-        let mut depth_index = None;
-
-        for color in &self.colors {
-            if !unique.insert(color) {
+        let mut unique = HashSet::with_capacity(
+            self.colors.len()
+                + self.depth.is_some() as usize
+                + self.inputs.len()
+                + self.resolves.len(),
+        );
+
+        let mut reference = |member: &syn::Member| -> syn::Result<u32> {
+            if !unique.insert(member.clone()) {
                 return Err(syn::Error::new_spanned(
-                    color,
+                    member,
                     "Duplicate attachment references are not allowed",
                 ));
             }
 
-            validate_member(color, item_struct)?;
-
-            match attachments.iter().position(|a| a.member == *color) {
-                Some(index) => {
-                    let index = u32::try_from(index).unwrap();
+            validate_member(member, item_struct)?;
 
-                    color_indices.push(index);
-                }
-                None => {
-                    return Err(syn::Error::new_spanned(
-                        color,
-                        "Member is not an attachment",
-                    ))
-                }
-            }
-        }
-        for depth in &self.depth {
-            if !unique.insert(depth) {
-                return Err(syn::Error::new_spanned(
-                    depth,
-                    "Duplicate attachment references are not allowed",
-                ));
+            match attachments.iter().position(|a| a.member == *member) {
+                Some(index) => Ok(u32::try_from(index).unwrap()),
+                None => Err(syn::Error::new_spanned(
+                    member,
+                    "Member is not an attachment",
+                )),
             }
-            validate_member(depth, item_struct)?;
+        };
 
-            match attachments.iter().position(|a| a.member == *depth) {
-                Some(index) => {
-                    let index = u32::try_from(index).unwrap();
-                    depth_index = Some(index);
-                }
-                None => {
-                    return Err(syn::Error::new_spanned(
-                        depth,
-                        "Member is not an attachment",
-                    ))
-                }
-            }
+        let colors = self
+            .colors
+            .iter()
+            .map(&mut reference)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let depth = self.depth.as_ref().map(&mut reference).transpose()?;
+
+        let inputs = self
+            .inputs
+            .iter()
+            .map(&mut reference)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let resolves = self
+            .resolves
+            .iter()
+            .map(&mut reference)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        if !resolves.is_empty() && resolves.len() != colors.len() {
+            return Err(syn::Error::new_spanned(
+                &self.resolves[0],
+                "Number of `resolve` arguments must match number of `color` arguments",
+            ));
         }
 
         Ok(Subpass {
-            colors: color_indices,
-            depth: depth_index,
+            colors,
+            depth,
+            inputs,
+            resolves,
         })
     }
 }
@@ -201,6 +218,20 @@ enum SubpassArg {
         assign: syn::Token![=],
         member: syn::Member,
     },
+    Input {
+        #[allow(dead_code)]
+        ident: syn::Ident,
+        #[allow(dead_code)]
+        assign: syn::Token![=],
+        member: syn::Member,
+    },
+    Resolve {
+        #[allow(dead_code)]
+        ident: syn::Ident,
+        #[allow(dead_code)]
+        assign: syn::Token![=],
+        member: syn::Member,
+    },
 }
 
 fn parse_subpass_attr(attr: &syn::Attribute) -> syn::Result<Option<SubpassAttribute>> {
@@ -209,37 +240,55 @@ fn parse_subpass_attr(attr: &syn::Attribute) -> syn::Result<Option<SubpassAttrib
         _ => return Ok(None),
     }
 
-    let args = attr
-        .parse_args_with(|stream: syn::parse::ParseStream<'_>| {
-            stream.parse_terminated::<_, syn::Token![,]>(|stream: syn::parse::ParseStream<'_>| {
-                let ident = stream.parse::<syn::Ident>()?;
-                match () {
-                    () if ident == "color" => {
-                        let assign = stream.parse::<syn::Token![=]>()?;
-                        let member = stream.parse::<syn::Member>()?;
-                        Ok(SubpassArg::Color {
-                            ident,
-                            assign,
-                            member,
-                        })
-                    }
-                    () if ident == "depth" => {
-                        let assign = stream.parse::<syn::Token![=]>()?;
-                        let member = stream.parse::<syn::Member>()?;
-                        Ok(SubpassArg::Depth {
-                            ident,
-                            assign,
-                            member,
-                        })
-                    }
-                    () => Err(stream.error(format!("Unrecognized subpass argument {}", ident))),
+    let args = attr.parse_args_with(|stream: syn::parse::ParseStream<'_>| {
+        stream.parse_terminated::<_, syn::Token![,]>(|stream: syn::parse::ParseStream<'_>| {
+            let ident = stream.parse::<syn::Ident>()?;
+            match () {
+                () if ident == "color" => {
+                    let assign = stream.parse::<syn::Token![=]>()?;
+                    let member = stream.parse::<syn::Member>()?;
+                    Ok(SubpassArg::Color {
+                        ident,
+                        assign,
+                        member,
+                    })
+                }
+                () if ident == "depth" => {
+                    let assign = stream.parse::<syn::Token![=]>()?;
+                    let member = stream.parse::<syn::Member>()?;
+                    Ok(SubpassArg::Depth {
+                        ident,
+                        assign,
+                        member,
+                    })
                 }
-            })
+                () if ident == "input" => {
+                    let assign = stream.parse::<syn::Token![=]>()?;
+                    let member = stream.parse::<syn::Member>()?;
+                    Ok(SubpassArg::Input {
+                        ident,
+                        assign,
+                        member,
+                    })
+                }
+                () if ident == "resolve" => {
+                    let assign = stream.parse::<syn::Token![=]>()?;
+                    let member = stream.parse::<syn::Member>()?;
+                    Ok(SubpassArg::Resolve {
+                        ident,
+                        assign,
+                        member,
+                    })
+                }
+                () => Err(stream.error(format!("Unrecognized subpass argument {}", ident))),
+            }
         })
-        .unwrap();
+    })?;
 
     let mut colors = Vec::new();
     let mut depth = None;
+    let mut inputs = Vec::new();
+    let mut resolves = Vec::new();
 
     for arg in args {
         match arg {
@@ -254,10 +303,17 @@ fn parse_subpass_attr(attr: &syn::Attribute) -> syn::Result<Option<SubpassAttrib
 
                 depth = Some(member);
             }
+            SubpassArg::Input { member, .. } => inputs.push(member),
+            SubpassArg::Resolve { member, .. } => resolves.push(member),
         }
     }
 
-    Ok(Some(SubpassAttribute { colors, depth }))
+    Ok(Some(SubpassAttribute {
+        colors,
+        depth,
+        inputs,
+        resolves,
+    }))
 }
 
 fn parse_attachment(field: &mut syn::Field, field_index: u32) -> syn::Result<Option<Attachment>> {
@@ -0,0 +1,53 @@
+use {
+    super::parse::Input, crate::stage::combined_stages_tokens_dedup, proc_macro2::TokenStream,
+    std::convert::TryFrom as _,
+};
+
+pub(super) fn generate(input: &Input) -> TokenStream {
+    let ident = &input.item_struct.ident;
+
+    let sets = input
+        .sets
+        .iter()
+        .enumerate()
+        .map(|(index, set)| {
+            let ty = &set.ty;
+            let index = u32::try_from(index).expect("Too many sets");
+            let name = syn::LitStr::new(&set.ident.to_string(), set.ident.span());
+            quote::quote!(
+                <#ty as ::sierra::glsl::GlslDescriptors>::glsl_declarations(stage, #index, #name, &mut ctx);
+            )
+        })
+        .collect::<TokenStream>();
+
+    let push_constants = match &input.push_constants {
+        Some(push_constants) => {
+            let ty = &push_constants.ty;
+            let stages = combined_stages_tokens_dedup(push_constants.stages.iter().copied());
+            let name = syn::LitStr::new(
+                &push_constants.ident.to_string(),
+                push_constants.ident.span(),
+            );
+            let block_name = syn::LitStr::new(&format!("{}PushConstants", ident), ident.span());
+
+            quote::quote!(
+                if (#stages).contains(::sierra::ShaderStageFlags::from(stage)) {
+                    let members = ctx.block_member::<#ty>(#name);
+                    ctx.declare_block("push_constant", "uniform", #block_name, &members);
+                }
+            )
+        }
+        None => TokenStream::new(),
+    };
+
+    quote::quote!(
+        impl ::sierra::glsl::ShaderInclude for #ident {
+            fn glsl(stage: ::sierra::ShaderStage) -> ::std::string::String {
+                let mut ctx = ::sierra::glsl::GlslTypeContext::new();
+                #sets
+                #push_constants
+                ctx.code()
+            }
+        }
+    )
+}
@@ -1,4 +1,7 @@
-use {super::parse::Input, proc_macro2::TokenStream, std::convert::TryFrom};
+use {
+    super::parse::Input, crate::stage::combined_stages_tokens_dedup, proc_macro2::TokenStream,
+    std::convert::TryFrom,
+};
 
 pub(super) fn layout_type_name(input: &Input) -> syn::Ident {
     quote::format_ident!("{}Layout", input.item_struct.ident)
@@ -68,6 +71,36 @@ pub(super) fn generate(input: &Input) -> TokenStream {
         })
         .collect::<TokenStream>();
 
+    let (push_constant_ranges, push_constants_method) = match &input.push_constants {
+        Some(push_constants) => {
+            let ty = &push_constants.ty;
+            let stages = combined_stages_tokens_dedup(push_constants.stages.iter().copied());
+
+            (
+                quote::quote!(::std::vec![::sierra::PushConstant {
+                    stages: #stages,
+                    offset: 0,
+                    size: ::std::mem::size_of::<<#ty as ::sierra::ShaderRepr<::sierra::Std430>>::Type>() as u32,
+                }]),
+                quote::quote!(
+                    /// Converts value into std430 layout and records push constants command.
+                    pub fn push_constants<'a>(&'a self, encoder: &mut ::sierra::EncoderCommon<'a>, value: &#ty) {
+                        let mut repr = <<#ty as ::sierra::ShaderRepr<::sierra::Std430>>::Type as ::sierra::Zeroable>::zeroed();
+                        ::sierra::ShaderRepr::<::sierra::Std430>::copy_to_repr(value, &mut repr);
+
+                        encoder.push_constants(
+                            &self.pipeline_layout,
+                            #stages,
+                            0,
+                            encoder.scope().to_scope([repr]),
+                        );
+                    }
+                ),
+            )
+        }
+        None => (quote::quote!(::std::vec::Vec::new()), TokenStream::new()),
+    };
+
     let vis = &input.item_struct.vis;
     let ident = &input.item_struct.ident;
 
@@ -96,7 +129,7 @@ pub(super) fn generate(input: &Input) -> TokenStream {
 
                 let pipeline_layout = device.create_pipeline_layout(::sierra::PipelineLayoutInfo {
                     sets: ::std::vec![#(#raw_set_layouts),*],
-                    push_constants: ::std::vec::Vec::new(),
+                    push_constants: #push_constant_ranges,
                 })?;
 
                 Ok(#layout_ident {
@@ -109,6 +142,8 @@ pub(super) fn generate(input: &Input) -> TokenStream {
                 &self.pipeline_layout
             }
 
+            #push_constants_method
+
             pub fn bind_graphics<'a, D>(&'a self, updated_descriptors: &'a D, encoder: &mut ::sierra::EncoderCommon<'a>)
             where
                 D: ::sierra::UpdatedPipelineDescriptors<Self>,
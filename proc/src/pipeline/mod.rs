@@ -1,7 +1,9 @@
+mod glsl;
 mod input;
 // mod instance;
 mod layout;
 mod parse;
+mod wgsl;
 
 pub fn pipeline(
     attr: proc_macro::TokenStream,
@@ -24,6 +26,8 @@ pub fn pipeline(
                 .chain(Some(input::generate(&input)))
                 // .chain(Some(instance::generate(&input)))
                 .chain(Some(layout::generate(&input)))
+                .chain(input.declarations.glsl.then(|| glsl::generate(&input)))
+                .chain(input.declarations.wgsl.then(|| wgsl::generate(&input)))
                 .collect::<proc_macro2::TokenStream>()
         }
         Err(err) => err.into_compile_error(),
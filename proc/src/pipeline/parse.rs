@@ -1,8 +1,14 @@
-use crate::find_unique_attribute;
+use crate::{
+    find_unique_attribute, parse_shader_declarations,
+    stage::{parse_stages, Stage},
+    ShaderDeclarations,
+};
 
 pub struct Input {
     pub item_struct: syn::ItemStruct,
     pub sets: Vec<Set>,
+    pub push_constants: Option<PushConstants>,
+    pub declarations: ShaderDeclarations,
 }
 
 pub struct Set {
@@ -10,27 +16,59 @@ pub struct Set {
     pub ty: syn::Type,
 }
 
+pub struct PushConstants {
+    pub ident: syn::Ident,
+    pub stages: Vec<Stage>,
+    pub ty: syn::Type,
+}
+
 pub fn parse(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> syn::Result<Input> {
-    if !attr.is_empty() {
-        return Err(syn::Error::new_spanned(
-            proc_macro2::TokenStream::from(attr),
-            "#[pipeline] attribute does not accept arguments",
-        ));
-    }
+    let declarations = parse_shader_declarations(attr, "pipeline")?;
 
     let mut item_struct =
         syn::parse::<syn::ItemStruct>(item).expect("`#[pipeline]` can be applied only to structs");
 
     let mut sets = Vec::new();
+    let mut push_constants = None;
 
     for field in item_struct.fields.iter_mut() {
+        if let Some(stages) = find_unique_attribute(
+            &mut field.attrs,
+            parse_push_constants_attr,
+            "At most one `push_constants` attribute",
+        )? {
+            if push_constants.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "At most one field can have `push_constants` attribute",
+                ));
+            }
+
+            let ident = field
+                .ident
+                .clone()
+                .expect("Only named struct are supported");
+
+            push_constants = Some(PushConstants {
+                ident,
+                stages,
+                ty: field.ty.clone(),
+            });
+            continue;
+        }
+
         match parse_set_field(field)? {
             None => {}
             Some(set) => sets.push(set),
         }
     }
 
-    Ok(Input { item_struct, sets })
+    Ok(Input {
+        item_struct,
+        sets,
+        push_constants,
+        declarations,
+    })
 }
 
 fn parse_set_field(field: &mut syn::Field) -> syn::Result<Option<Set>> {
@@ -72,3 +110,28 @@ fn parse_set_attr(attr: &syn::Attribute) -> syn::Result<Option<SetAttribute>> {
         _ => Ok(None),
     }
 }
+
+/// Parses `#[push_constants(stages = Vertex | Fragment)]` attribute.
+/// Stages may be separated with `,` as in `#[stages(Vertex, Fragment)]`.
+fn parse_push_constants_attr(attr: &syn::Attribute) -> syn::Result<Option<Vec<Stage>>> {
+    match attr.path.get_ident() {
+        Some(ident) if ident == "push_constants" => {
+            let stages = attr.parse_args_with(|stream: syn::parse::ParseStream<'_>| {
+                let ident = stream.parse::<syn::Ident>()?;
+                if ident != "stages" {
+                    return Err(syn::Error::new_spanned(ident, "Expected `stages` argument"));
+                }
+                let _eq = stream.parse::<syn::Token![=]>()?;
+
+                let stages = parse_stages(stream)?;
+                if stages.is_empty() {
+                    return Err(stream.error("Expected at least one stage"));
+                }
+                Ok(stages)
+            })?;
+
+            Ok(Some(stages))
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,51 @@
+use {super::parse::Input, proc_macro2::TokenStream, std::convert::TryFrom as _};
+
+pub(super) fn generate(input: &Input) -> TokenStream {
+    let ident = &input.item_struct.ident;
+
+    let sets = input
+        .sets
+        .iter()
+        .enumerate()
+        .map(|(index, set)| {
+            let ty = &set.ty;
+            let index = u32::try_from(index).expect("Too many sets");
+            let name = syn::LitStr::new(&set.ident.to_string(), set.ident.span());
+            quote::quote!(
+                <#ty as ::sierra::wgsl::WgslDescriptors>::wgsl_declarations(#index, #name, &mut ctx);
+            )
+        })
+        .collect::<TokenStream>();
+
+    let push_constants = match &input.push_constants {
+        Some(push_constants) => {
+            let ty = &push_constants.ty;
+            let name = syn::LitStr::new(
+                &push_constants.ident.to_string(),
+                push_constants.ident.span(),
+            );
+            let block_name = syn::LitStr::new(&format!("{}PushConstants", ident), ident.span());
+
+            quote::quote!(
+                ctx.add::<#ty, ::sierra::Std430>();
+                let members = ::sierra::wgsl::struct_member::<#ty, ::sierra::Std430>(
+                    "value",
+                    <#ty as ::sierra::ShaderRepr<::sierra::Std430>>::ALIGN_MASK,
+                );
+                ctx.declare_block("", "push_constant", #block_name, &members, #name);
+            )
+        }
+        None => TokenStream::new(),
+    };
+
+    quote::quote!(
+        impl ::sierra::wgsl::WgslInclude for #ident {
+            fn wgsl() -> ::std::string::String {
+                let mut ctx = ::sierra::wgsl::WgslTypeContext::new();
+                #sets
+                #push_constants
+                ctx.code()
+            }
+        }
+    )
+}
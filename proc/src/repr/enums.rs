@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+
+/// Generates representation of fieldless enum as `u32`
+/// and constants for its variants in shaders.
+pub fn generate_enum(
+    attr: proc_macro::TokenStream,
+    item_enum: &syn::ItemEnum,
+) -> syn::Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`shader_repr` for enums accepts no arguments",
+        ));
+    }
+
+    if !item_enum.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_enum.generics,
+            "Generic enums are not supported",
+        ));
+    }
+
+    if item_enum.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            item_enum,
+            "Enums without variants are not supported",
+        ));
+    }
+
+    for variant in &item_enum.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Only fieldless enums are supported",
+            ));
+        }
+    }
+
+    let ident = &item_enum.ident;
+    let variants = item_enum
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+
+    let reprs = ["Std140", "Std430"]
+        .iter()
+        .map(|layout| {
+            let layout = quote::format_ident!("{}", layout);
+
+            quote::quote!(
+                impl ::sierra::ShaderRepr<::sierra::#layout> for #ident {
+                    const ALIGN_MASK: usize = <u32 as ::sierra::ShaderRepr<::sierra::#layout>>::ALIGN_MASK;
+                    const ARRAY_PADDING: usize = <u32 as ::sierra::ShaderRepr<::sierra::#layout>>::ARRAY_PADDING;
+                    type Type = u32;
+                    type ArrayPadding = <u32 as ::sierra::ShaderRepr<::sierra::#layout>>::ArrayPadding;
+                    fn copy_to_repr(&self, repr: &mut u32) {
+                        *repr = match self {
+                            #(#ident::#variants => #ident::#variants as u32,)*
+                        };
+                    }
+                }
+            )
+        })
+        .collect::<TokenStream>();
+
+    let const_names = variants
+        .iter()
+        .map(|variant| format!("{}_{}", ident, variant))
+        .collect::<Vec<_>>();
+
+    Ok(quote::quote!(
+        #item_enum
+
+        #reprs
+
+        impl ::sierra::glsl::GlslType for #ident {
+            fn name() -> &'static str {
+                "uint"
+            }
+
+            fn def() -> ::std::string::String {
+                [#(
+                    ::std::format!("const uint {} = {}u;", #const_names, #ident::#variants as u32),
+                )*]
+                .join("\n")
+            }
+        }
+
+        impl ::sierra::wgsl::WgslType<::sierra::Std140> for #ident {
+            fn name() -> ::std::string::String {
+                ::std::string::String::from("u32")
+            }
+
+            fn def() -> ::std::string::String {
+                [#(
+                    ::std::format!("let {}: u32 = {}u;", #const_names, #ident::#variants as u32),
+                )*]
+                .join("\n")
+            }
+        }
+
+        impl ::sierra::wgsl::WgslType<::sierra::Std430> for #ident {
+            fn name() -> ::std::string::String {
+                ::std::string::String::from("u32")
+            }
+
+            // Constants are defined once for both layouts.
+            fn deps(ctx: &mut ::sierra::wgsl::WgslTypeContext) {
+                ctx.add::<Self, ::sierra::Std140>();
+            }
+        }
+    ))
+}
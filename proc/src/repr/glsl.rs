@@ -0,0 +1,71 @@
+use {
+    super::parse::{field_name, Input},
+    proc_macro2::TokenStream,
+};
+
+pub fn generate_glsl_type(input: &Input) -> TokenStream {
+    let ident = &input.name;
+    let self_ty = &input.self_ty;
+    let name = syn::LitStr::new(&ident.to_string(), ident.span());
+    let head = syn::LitStr::new(&format!("struct {} {{\n", ident), ident.span());
+
+    let fields = || {
+        input
+            .fields
+            .iter()
+            .chain(input.runtime_array.iter().map(|array| &array.field))
+    };
+
+    let deps = fields().map(|field| {
+        let ty = &field.ty;
+        quote::quote!(ctx.add::<#ty>();)
+    });
+
+    let members = fields().map(|field| {
+        let ty = &field.ty;
+        let field_name = syn::LitStr::new(&field_name(&field.ident), field.ident.span());
+
+        quote::quote!(
+            def.push_str(&::std::format!(
+                "    {} {}{};\n",
+                <#ty as ::sierra::glsl::GlslType>::name(),
+                #field_name,
+                <#ty as ::sierra::glsl::GlslType>::suffix(),
+            ));
+        )
+    });
+
+    // Structures with runtime-sized array are inlined into buffer block.
+    let def = if input.runtime_array.is_some() {
+        quote::quote!(
+            fn block_members() -> ::std::option::Option<::std::string::String> {
+                let mut def = ::std::string::String::new();
+                #(#members)*
+                ::std::option::Option::Some(def)
+            }
+        )
+    } else {
+        quote::quote!(
+            fn def() -> ::std::string::String {
+                let mut def = ::std::string::String::from(#head);
+                #(#members)*
+                def.push_str("};");
+                def
+            }
+        )
+    };
+
+    quote::quote!(
+        impl ::sierra::glsl::GlslType for #self_ty {
+            fn name() -> &'static str {
+                #name
+            }
+
+            fn deps(ctx: &mut ::sierra::glsl::GlslTypeContext) {
+                #(#deps)*
+            }
+
+            #def
+        }
+    )
+}
@@ -1,17 +1,53 @@
+mod enums;
+mod glsl;
 mod parse;
 mod repr;
+mod wgsl;
 
 use proc_macro2::TokenStream;
 
 pub fn shader_repr(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> TokenStream {
-    match parse::parse(attr, item) {
-        Ok(input) => {
-            let struct_item = &input.item_struct;
-            std::iter::once(quote::quote!(#struct_item))
-                .chain(Some(repr::generate_repr(&input)))
-                // .chain(Some(generate_glsl_type(&input)))
-                .collect::<TokenStream>()
+    let result = match syn::parse::<syn::Item>(item) {
+        Ok(syn::Item::Struct(item_struct)) => {
+            parse::parse(attr, item_struct).and_then(|input| generate(&input))
         }
+        Ok(syn::Item::Enum(item_enum)) => enums::generate_enum(attr, &item_enum),
+        Ok(item) => Err(syn::Error::new_spanned(
+            item,
+            "`shader_repr` is supported only for structs and enums",
+        )),
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(tokens) => tokens,
         Err(err) => err.into_compile_error(),
     }
 }
+
+fn generate(input: &parse::Input) -> syn::Result<TokenStream> {
+    let struct_item = &input.item_struct;
+    let mut tokens = quote::quote!(#struct_item);
+
+    if input.instances.is_empty() {
+        tokens.extend(generate_instance(input));
+    } else {
+        for instance in &input.instances {
+            tokens.extend(generate_instance(&input.instantiate(instance)?));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn generate_instance(input: &parse::Input) -> TokenStream {
+    let repr = match &input.runtime_array {
+        Some(array) => repr::generate_runtime_repr(input, array),
+        None => repr::generate_repr(input),
+    };
+
+    std::iter::once(repr)
+        .chain(Some(glsl::generate_glsl_type(input)))
+        .chain(Some(wgsl::generate_wgsl_type(input)))
+        .collect::<TokenStream>()
+}
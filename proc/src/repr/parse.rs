@@ -1,41 +1,443 @@
+use {
+    crate::find_unique_attribute,
+    std::collections::HashMap,
+    syn::{ext::IdentExt as _, spanned::Spanned as _, visit_mut::VisitMut},
+};
+
+#[derive(Clone)]
 pub struct Field {
+    pub member: syn::Member,
+    /// Name of the field. `_N` for fields of tuple structs.
     pub ident: syn::Ident,
     pub ty: syn::Type,
+    pub offset_140: Option<syn::LitInt>,
+    pub offset_430: Option<syn::LitInt>,
+}
+
+/// Trailing `RuntimeArray<T>` field.
+#[derive(Clone)]
+pub struct RuntimeArray {
+    pub field: Field,
+    pub elem: syn::Type,
 }
 
+#[derive(Clone)]
 pub struct Input {
     pub fields: Vec<Field>,
+    pub runtime_array: Option<RuntimeArray>,
     pub item_struct: syn::ItemStruct,
+    pub assert_size_140: Option<syn::LitInt>,
+    pub assert_size_430: Option<syn::LitInt>,
+
+    /// Instances of generic struct.
+    pub instances: Vec<syn::Type>,
+
+    /// Type for which representation is implemented.
+    pub self_ty: syn::Type,
+
+    /// Name of the type in shaders and prefix of generated representation types.
+    pub name: syn::Ident,
+}
+
+enum Argument {
+    AssertSize140(syn::LitInt),
+    AssertSize430(syn::LitInt),
+    Instance(syn::Type),
 }
 
-pub fn parse(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> syn::Result<Input> {
-    if !attr.is_empty() {
+pub fn parse(
+    attr: proc_macro::TokenStream,
+    mut item_struct: syn::ItemStruct,
+) -> syn::Result<Input> {
+    let args = syn::parse::Parser::parse(
+        |stream: syn::parse::ParseStream<'_>| {
+            stream.parse_terminated::<_, syn::Token![,]>(|stream| {
+                let ident = stream.parse::<syn::Ident>()?;
+                let _eq = stream.parse::<syn::Token![=]>()?;
+
+                match ident {
+                    ident if ident == "assert_size" => Ok(Argument::AssertSize140(stream.parse()?)),
+                    ident if ident == "assert_size_std430" => {
+                        Ok(Argument::AssertSize430(stream.parse()?))
+                    }
+                    ident if ident == "instance" => Ok(Argument::Instance(stream.parse()?)),
+                    ident => Err(syn::Error::new_spanned(
+                        &ident,
+                        format!(
+                            "Unrecognized argument `{}`. Expected `assert_size`, `assert_size_std430` or `instance`",
+                            ident
+                        ),
+                    )),
+                }
+            })
+        },
+        attr,
+    )?;
+
+    let mut assert_size_140 = None;
+    let mut assert_size_430 = None;
+    let mut instances = Vec::new();
+
+    for arg in args {
+        let (slot, value) = match arg {
+            Argument::AssertSize140(value) => (&mut assert_size_140, value),
+            Argument::AssertSize430(value) => (&mut assert_size_430, value),
+            Argument::Instance(ty) => {
+                instances.push(ty);
+                continue;
+            }
+        };
+
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(value, "Duplicate size assertion"));
+        }
+        *slot = Some(value);
+    }
+
+    if let Some(lifetime) = item_struct.generics.lifetimes().next() {
         return Err(syn::Error::new_spanned(
-            proc_macro2::TokenStream::from(attr),
-            "#[shader_repr] attribute does not accept arguments",
+            lifetime,
+            "Lifetime parameters are not supported",
         ));
     }
 
-    let item_struct = syn::parse::<syn::ItemStruct>(item)?;
+    if item_struct.generics.params.is_empty() {
+        if let Some(instance) = instances.first() {
+            return Err(syn::Error::new_spanned(
+                instance,
+                "`instance` argument is allowed only for generic structs",
+            ));
+        }
+    } else if instances.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_struct.generics,
+            "Generic structs require `instance = Type` argument for each instance to represent",
+        ));
+    }
 
-    let fields = item_struct
+    let mut fields = item_struct
         .fields
-        .iter()
-        .map(|field| {
-            let ident = field
-                .ident
-                .clone()
-                .ok_or_else(|| syn::Error::new_spanned(field, "Tuple structs are not supported"))?;
+        .iter_mut()
+        .enumerate()
+        .map(|(index, field)| {
+            let (member, ident) = match &field.ident {
+                Some(ident) => (syn::Member::Named(ident.clone()), ident.clone()),
+                None => (
+                    syn::Member::Unnamed(syn::Index {
+                        index: index as u32,
+                        span: field.ty.span(),
+                    }),
+                    quote::format_ident!("_{}", index, span = field.ty.span()),
+                ),
+            };
+
+            let offset_140 = find_unique_attribute(
+                &mut field.attrs,
+                |attr| parse_offset_attr(attr, "offset"),
+                "At most one `offset` attribute",
+            )?;
+
+            let offset_430 = find_unique_attribute(
+                &mut field.attrs,
+                |attr| parse_offset_attr(attr, "offset_std430"),
+                "At most one `offset_std430` attribute",
+            )?;
 
             Ok(Field {
                 ty: field.ty.clone(),
+                member,
                 ident,
+                offset_140,
+                offset_430,
             })
         })
         .collect::<Result<Vec<_>, syn::Error>>()?;
 
+    let runtime_array = match fields
+        .last()
+        .and_then(|field| runtime_array_elem(&field.ty))
+    {
+        Some(elem) => {
+            let field = fields.pop().unwrap();
+
+            if let Some(size) = assert_size_140.as_ref().or(assert_size_430.as_ref()) {
+                return Err(syn::Error::new_spanned(
+                    size,
+                    "Size assertions are not supported for structs with runtime-sized array",
+                ));
+            }
+
+            Some(RuntimeArray { field, elem })
+        }
+        None => None,
+    };
+
+    for field in &fields {
+        if runtime_array_elem(&field.ty).is_some() {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`RuntimeArray` must be the last field",
+            ));
+        }
+    }
+
+    if runtime_array.is_some() {
+        if let Some(offset) = fields
+            .iter()
+            .chain(runtime_array.iter().map(|array| &array.field))
+            .find_map(|field| field.offset_140.as_ref())
+        {
+            return Err(syn::Error::new_spanned(
+                offset,
+                "Structs with runtime-sized array have only std430 layout",
+            ));
+        }
+    }
+
+    let ident = &item_struct.ident;
+
     Ok(Input {
         fields,
+        runtime_array,
+        assert_size_140,
+        assert_size_430,
+        instances,
+        self_ty: syn::parse_quote!(#ident),
+        name: ident.clone(),
         item_struct,
     })
 }
+
+impl Input {
+    /// Returns input for instance of generic struct
+    /// with generic parameters substituted in field types.
+    pub fn instantiate(&self, instance: &syn::Type) -> syn::Result<Input> {
+        let ident = &self.item_struct.ident;
+
+        let args = match instance {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path
+                .segments
+                .last()
+                .filter(|segment| segment.ident == *ident)
+                .and_then(|segment| match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => Some(&args.args),
+                    _ => None,
+                }),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                instance,
+                format!("Expected instance of `{}` with generic arguments", ident),
+            )
+        })?;
+
+        let params = &self.item_struct.generics.params;
+        if args.len() != params.len() {
+            return Err(syn::Error::new_spanned(
+                args,
+                format!("Expected {} generic arguments", params.len()),
+            ));
+        }
+
+        let mut substitute = Substitute::default();
+        let mut name = ident.unraw().to_string();
+
+        for (param, arg) in params.iter().zip(args) {
+            let part = match (param, arg) {
+                (syn::GenericParam::Type(param), syn::GenericArgument::Type(ty)) => {
+                    substitute.types.insert(param.ident.clone(), ty.clone());
+                    instance_name_part(ty)
+                }
+                (syn::GenericParam::Const(param), syn::GenericArgument::Const(expr)) => {
+                    substitute.consts.insert(param.ident.clone(), expr.clone());
+                    const_name_part(expr)
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        arg,
+                        "Generic argument does not match parameter",
+                    ))
+                }
+            };
+
+            let part = part.ok_or_else(|| {
+                syn::Error::new_spanned(
+                    arg,
+                    "Only paths, arrays and integer literals are supported as generic arguments",
+                )
+            })?;
+
+            name.push_str(&part);
+        }
+
+        let mut input = self.clone();
+
+        for field in input
+            .fields
+            .iter_mut()
+            .chain(input.runtime_array.as_mut().map(|array| &mut array.field))
+        {
+            substitute.visit_type_mut(&mut field.ty);
+        }
+
+        if let Some(array) = &mut input.runtime_array {
+            substitute.visit_type_mut(&mut array.elem);
+        }
+
+        input.instances = Vec::new();
+        input.self_ty = instance.clone();
+        input.name = syn::Ident::new(&name, instance.span());
+
+        Ok(input)
+    }
+}
+
+/// Substitutes generic parameters with arguments of the instance.
+#[derive(Default)]
+struct Substitute {
+    types: HashMap<syn::Ident, syn::Type>,
+    consts: HashMap<syn::Ident, syn::Expr>,
+}
+
+impl VisitMut for Substitute {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let Some(arg) = type_ident(ty).and_then(|ident| self.types.get(ident)) {
+            *ty = arg.clone();
+        } else {
+            syn::visit_mut::visit_type_mut(self, ty);
+        }
+    }
+
+    fn visit_generic_argument_mut(&mut self, arg: &mut syn::GenericArgument) {
+        // Const parameters in generic arguments are parsed as types.
+        let value = match arg {
+            syn::GenericArgument::Type(ty) => {
+                type_ident(ty).and_then(|ident| self.consts.get(ident))
+            }
+            _ => None,
+        };
+
+        match value {
+            Some(value) => *arg = syn::GenericArgument::Const(value.clone()),
+            None => syn::visit_mut::visit_generic_argument_mut(self, arg),
+        }
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        let value = match expr {
+            syn::Expr::Path(syn::ExprPath {
+                qself: None, path, ..
+            }) => path.get_ident().and_then(|ident| self.consts.get(ident)),
+            _ => None,
+        };
+
+        match value {
+            Some(value) => *expr = value.clone(),
+            None => syn::visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+}
+
+fn type_ident(ty: &syn::Type) -> Option<&syn::Ident> {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident(),
+        _ => None,
+    }
+}
+
+/// Returns part of the instance name for type argument,
+/// e.g. `Vec3` for `sierra::vec3` and `F32x4` for `[f32; 4]`.
+fn instance_name_part(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let segment = path.segments.last()?;
+            let ident = segment.ident.unraw().to_string();
+
+            let mut chars = ident.chars();
+            let mut name = chars
+                .next()?
+                .to_uppercase()
+                .chain(chars)
+                .collect::<String>();
+
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                for arg in &args.args {
+                    let part = match arg {
+                        syn::GenericArgument::Type(ty) => instance_name_part(ty)?,
+                        syn::GenericArgument::Const(expr) => const_name_part(expr)?,
+                        _ => return None,
+                    };
+                    name.push_str(&part);
+                }
+            }
+
+            Some(name)
+        }
+        syn::Type::Array(array) => Some(format!(
+            "{}x{}",
+            instance_name_part(&array.elem)?,
+            const_name_part(&array.len)?
+        )),
+        _ => None,
+    }
+}
+
+/// Returns part of the instance name for const argument.
+fn const_name_part(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => Some(int.base10_digits().to_owned()),
+        syn::Expr::Block(block) => match block.block.stmts.as_slice() {
+            [syn::Stmt::Expr(expr)] => const_name_part(expr),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parses `#[offset = N]` attribute.
+fn parse_offset_attr(attr: &syn::Attribute, name: &str) -> syn::Result<Option<syn::LitInt>> {
+    if !attr.path.is_ident(name) {
+        return Ok(None);
+    }
+
+    match attr.parse_meta()? {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Int(offset),
+            ..
+        }) => Ok(Some(offset)),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            format!("Expected `#[{} = N]` attribute", name),
+        )),
+    }
+}
+
+/// Returns element type if `ty` is `RuntimeArray<T>`.
+fn runtime_array_elem(ty: &syn::Type) -> Option<syn::Type> {
+    let path = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last()?;
+    if segment.ident != "RuntimeArray" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(elem) => Some(elem.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns name of the field without raw identifier prefix.
+pub fn field_name(ident: &syn::Ident) -> String {
+    ident.unraw().to_string()
+}
@@ -1,9 +1,16 @@
-use {super::parse::Input, proc_macro2::TokenStream};
+use {
+    super::parse::{field_name, Input, RuntimeArray},
+    proc_macro2::TokenStream,
+};
 
 pub fn generate_repr(input: &Input) -> TokenStream {
     let vis = &input.item_struct.vis;
 
-    let mut last_offset = quote::quote!(0);
+    let mut last_offset_140 = quote::quote!(0);
+    let mut last_offset_430 = quote::quote!(0);
+
+    let mut offsets_140 = Vec::new();
+    let mut offsets_430 = Vec::new();
 
     let fields_140: TokenStream = input
         .fields
@@ -12,20 +19,18 @@ pub fn generate_repr(input: &Input) -> TokenStream {
             let field_type = &field.ty;
 
             let val_ident = quote::format_ident!("val_{}", field.ident);
-            // let off_ident = quote::format_ident!("off_{}", field.ident);
             let pad_ident = quote::format_ident!("pad_{}", field.ident);
 
             let field_align_mask = quote::quote!(<#field_type as ::sierra::ShaderRepr<::sierra::Std140>>::ALIGN_MASK);
-            let pad_size = quote::quote!(::sierra::pad_size(#field_align_mask, #last_offset));
+            let pad_size = quote::quote!(::sierra::pad_size(#field_align_mask, #last_offset_140));
             let field_repr = quote::quote!(<#field_type as ::sierra::ShaderRepr<::sierra::Std140>>::Type);
-            let next_offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset, ::std::mem::size_of::<#field_repr>()));
+            let next_offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset_140, ::std::mem::size_of::<#field_repr>()));
 
-            // let offset = last_offset.clone();
-            last_offset = next_offset;
+            offsets_140.push(quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset_140, 0)));
+            last_offset_140 = next_offset;
 
             quote::quote! {
                 pub #pad_ident: [u8; #pad_size],
-                // pub #off_ident: [(); #offset],
                 pub #val_ident: #field_repr,
             }
         })
@@ -38,20 +43,18 @@ pub fn generate_repr(input: &Input) -> TokenStream {
             let field_type = &field.ty;
 
             let val_ident = quote::format_ident!("val_{}", field.ident);
-            // let off_ident = quote::format_ident!("off_{}", field.ident);
             let pad_ident = quote::format_ident!("pad_{}", field.ident);
 
             let field_align_mask = quote::quote!(<#field_type as ::sierra::ShaderRepr<::sierra::Std430>>::ALIGN_MASK);
-            let pad_size = quote::quote!(::sierra::pad_size(#field_align_mask, #last_offset));
+            let pad_size = quote::quote!(::sierra::pad_size(#field_align_mask, #last_offset_430));
             let field_repr = quote::quote!(<#field_type as ::sierra::ShaderRepr<::sierra::Std430>>::Type);
-            let next_offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset, ::std::mem::size_of::<#field_repr>()));
+            let next_offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset_430, ::std::mem::size_of::<#field_repr>()));
 
-            // let offset = last_offset.clone();
-            last_offset = next_offset;
+            offsets_430.push(quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset_430, 0)));
+            last_offset_430 = next_offset;
 
             quote::quote! {
                 pub #pad_ident: [u8; #pad_size],
-                // pub #off_ident: [(); #offset],
                 pub #val_ident: #field_repr,
             }
         })
@@ -61,7 +64,7 @@ pub fn generate_repr(input: &Input) -> TokenStream {
         .fields
         .iter()
         .map(|field| {
-            let field_ident = &field.ident;
+            let field_ident = &field.member;
             let val_ident = quote::format_ident!("val_{}", field.ident);
 
             quote::quote! {
@@ -74,7 +77,7 @@ pub fn generate_repr(input: &Input) -> TokenStream {
         .fields
         .iter()
         .map(|field| {
-            let field_ident = &field.ident;
+            let field_ident = &field.member;
             let val_ident = quote::format_ident!("val_{}", field.ident);
 
             quote::quote! {
@@ -107,12 +110,13 @@ pub fn generate_repr(input: &Input) -> TokenStream {
             tokens
         });
 
-    let pad_size_140 = quote::quote!(::sierra::pad_size(#align_mask_140, #last_offset));
-    let pad_size_430 = quote::quote!(::sierra::pad_size(#align_mask_430, #last_offset));
+    let pad_size_140 = quote::quote!(::sierra::pad_size(#align_mask_140, #last_offset_140));
+    let pad_size_430 = quote::quote!(::sierra::pad_size(#align_mask_430, #last_offset_430));
 
     let ident = &input.item_struct.ident;
-    let std140_ident = quote::format_ident!("{}ReprStd140", input.item_struct.ident);
-    let std430_ident = quote::format_ident!("{}ReprStd430", input.item_struct.ident);
+    let self_ty = &input.self_ty;
+    let std140_ident = quote::format_ident!("{}ReprStd140", input.name);
+    let std430_ident = quote::format_ident!("{}ReprStd430", input.name);
 
     let doc_attr_140 = if cfg!(feature = "verbose-docs") {
         format!(
@@ -136,6 +140,32 @@ pub fn generate_repr(input: &Input) -> TokenStream {
         quote::quote!(#[doc(hidden)])
     };
 
+    let layout_140 = generate_layout(
+        input,
+        "Std140",
+        &std140_ident,
+        &offsets_140,
+        input.assert_size_140.as_ref(),
+        &input
+            .fields
+            .iter()
+            .map(|field| field.offset_140.as_ref())
+            .collect::<Vec<_>>(),
+    );
+
+    let layout_430 = generate_layout(
+        input,
+        "Std430",
+        &std430_ident,
+        &offsets_430,
+        input.assert_size_430.as_ref(),
+        &input
+            .fields
+            .iter()
+            .map(|field| field.offset_430.as_ref())
+            .collect::<Vec<_>>(),
+    );
+
     quote::quote! {
         #[repr(C)]
         #[derive(Clone, Copy)]
@@ -148,7 +178,7 @@ pub fn generate_repr(input: &Input) -> TokenStream {
         unsafe impl ::sierra::Zeroable for #std140_ident {}
         unsafe impl ::sierra::Pod for #std140_ident {}
 
-        impl ::sierra::ShaderRepr<::sierra::Std140> for #ident {
+        impl ::sierra::ShaderRepr<::sierra::Std140> for #self_ty {
             const ALIGN_MASK: usize = #align_mask_140;
             const ARRAY_PADDING: usize = 0;
             type Type = #std140_ident;
@@ -169,7 +199,7 @@ pub fn generate_repr(input: &Input) -> TokenStream {
         unsafe impl ::sierra::Zeroable for #std430_ident {}
         unsafe impl ::sierra::Pod for #std430_ident {}
 
-        impl ::sierra::ShaderRepr<::sierra::Std430> for #ident {
+        impl ::sierra::ShaderRepr<::sierra::Std430> for #self_ty {
             const ALIGN_MASK: usize = #align_mask_430;
             const ARRAY_PADDING: usize = 0;
             type Type = #std430_ident;
@@ -179,5 +209,222 @@ pub fn generate_repr(input: &Input) -> TokenStream {
             }
         }
 
+        #layout_140
+        #layout_430
     }
 }
+
+/// Generates offset constants, fields description and layout assertions
+/// for representation of the struct in specified layout.
+fn generate_layout(
+    input: &Input,
+    layout: &str,
+    repr_ident: &syn::Ident,
+    offsets: &[TokenStream],
+    assert_size: Option<&syn::LitInt>,
+    assert_offsets: &[Option<&syn::LitInt>],
+) -> TokenStream {
+    let ident = &input.name;
+    let self_ty = &input.self_ty;
+    let vis = &input.item_struct.vis;
+    let layout_ident = quote::format_ident!("{}", layout);
+
+    let const_idents = input
+        .fields
+        .iter()
+        .map(|field| match &field.member {
+            syn::Member::Named(_) => {
+                quote::format_ident!("OFFSET_{}", field_name(&field.ident).to_uppercase())
+            }
+            syn::Member::Unnamed(index) => quote::format_ident!("OFFSET_{}", index.index),
+        })
+        .collect::<Vec<_>>();
+
+    let offset_consts = input
+        .fields
+        .iter()
+        .zip(&const_idents)
+        .zip(offsets)
+        .map(|((field, const_ident), offset)| {
+            let doc = format!("Offset of `{}` field in {} layout.", field.ident, layout);
+            quote::quote!(
+                #[doc = #doc]
+                #vis const #const_ident: usize = #offset;
+            )
+        })
+        .collect::<TokenStream>();
+
+    let fields_desc = input
+        .fields
+        .iter()
+        .zip(&const_idents)
+        .map(|(field, const_ident)| {
+            let ty = &field.ty;
+            let name = field_name(&field.ident);
+            quote::quote!(
+                ::sierra::ShaderReprField {
+                    name: #name,
+                    offset: #repr_ident::#const_ident,
+                    size: ::std::mem::size_of::<<#ty as ::sierra::ShaderRepr<::sierra::#layout_ident>>::Type>(),
+                },
+            )
+        })
+        .collect::<TokenStream>();
+
+    let size_assert = assert_size.map(|size| {
+        let msg = format!(
+            "{} size of `{}` does not match asserted size {}",
+            layout, ident, size
+        );
+        quote::quote_spanned!(size.span() =>
+            const _: () = ::std::assert!(#repr_ident::SIZE == #size, #msg);
+        )
+    });
+
+    let offset_asserts = input
+        .fields
+        .iter()
+        .zip(&const_idents)
+        .zip(assert_offsets)
+        .filter_map(|((field, const_ident), assert_offset)| {
+            let assert_offset = (*assert_offset)?;
+            let msg = format!(
+                "{} offset of `{}::{}` does not match asserted offset {}",
+                layout, ident, field.ident, assert_offset
+            );
+            Some(quote::quote_spanned!(assert_offset.span() =>
+                const _: () = ::std::assert!(#repr_ident::#const_ident == #assert_offset, #msg);
+            ))
+        })
+        .collect::<TokenStream>();
+
+    quote::quote!(
+        impl #repr_ident {
+            #offset_consts
+
+            /// Size of the representation.
+            #vis const SIZE: usize = ::std::mem::size_of::<Self>();
+        }
+
+        impl ::sierra::ShaderReprStruct<::sierra::#layout_ident> for #self_ty {
+            const FIELDS: &'static [::sierra::ShaderReprField] = &[#fields_desc];
+        }
+
+        #size_assert
+        #offset_asserts
+    )
+}
+
+/// Generates `ShaderReprRuntime` implementation for struct
+/// with trailing runtime-sized array.
+/// Such structs are represented only in std430 layout.
+pub fn generate_runtime_repr(input: &Input, array: &RuntimeArray) -> TokenStream {
+    let ident = &input.name;
+    let self_ty = &input.self_ty;
+    let elem = &array.elem;
+    let array_ident = &array.field.member;
+
+    let mut last_offset = quote::quote!(0);
+
+    let fields_desc = input
+        .fields
+        .iter()
+        .map(|field| {
+            let field_type = &field.ty;
+            let name = field_name(&field.ident);
+
+            let field_align_mask = quote::quote!(<#field_type as ::sierra::ShaderRepr<::sierra::Std430>>::ALIGN_MASK);
+            let field_size = quote::quote!(::std::mem::size_of::<<#field_type as ::sierra::ShaderRepr<::sierra::Std430>>::Type>());
+            let offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset, 0));
+            last_offset = quote::quote!(::sierra::next_offset(#field_align_mask, #last_offset, #field_size));
+
+            quote::quote!(
+                ::sierra::ShaderReprField {
+                    name: #name,
+                    offset: #offset,
+                    size: #field_size,
+                },
+            )
+        })
+        .collect::<TokenStream>();
+
+    let array_offset = quote::quote!(::sierra::next_offset(<#elem as ::sierra::ShaderRepr<::sierra::Std430>>::ALIGN_MASK, #last_offset, 0));
+
+    let write_fields = input
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let field_type = &field.ty;
+            let field_ident = &field.member;
+
+            quote::quote!(
+                ::sierra::write_repr_at::<#field_type, ::sierra::Std430>(
+                    &self.#field_ident,
+                    bytes,
+                    <Self as ::sierra::ShaderReprRuntime<::sierra::Std430>>::FIELDS[#index].offset,
+                );
+            )
+        })
+        .collect::<TokenStream>();
+
+    let offset_asserts = input
+        .fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| {
+            let assert_offset = field.offset_430.as_ref()?;
+            let msg = format!(
+                "Std430 offset of `{}::{}` does not match asserted offset {}",
+                ident, field.ident, assert_offset
+            );
+            Some(quote::quote_spanned!(assert_offset.span() =>
+                const _: () = ::std::assert!(
+                    <#self_ty as ::sierra::ShaderReprRuntime<::sierra::Std430>>::FIELDS[#index].offset == #assert_offset,
+                    #msg,
+                );
+            ))
+        })
+        .collect::<TokenStream>();
+
+    let array_offset_assert = array.field.offset_430.as_ref().map(|assert_offset| {
+        let msg = format!(
+            "Std430 offset of `{}::{}` does not match asserted offset {}",
+            ident, array.field.ident, assert_offset
+        );
+        quote::quote_spanned!(assert_offset.span() =>
+            const _: () = ::std::assert!(
+                <#self_ty as ::sierra::ShaderReprRuntime<::sierra::Std430>>::ARRAY_OFFSET == #assert_offset,
+                #msg,
+            );
+        )
+    });
+
+    quote::quote!(
+        impl ::sierra::ShaderReprRuntime<::sierra::Std430> for #self_ty {
+            const FIELDS: &'static [::sierra::ShaderReprField] = &[#fields_desc];
+
+            const ARRAY_OFFSET: usize = #array_offset;
+
+            const ARRAY_STRIDE: usize = ::std::mem::size_of::<::sierra::Padded<
+                <#elem as ::sierra::ShaderRepr<::sierra::Std430>>::Type,
+                <#elem as ::sierra::ShaderRepr<::sierra::Std430>>::ArrayPadding,
+            >>();
+
+            fn array_len(&self) -> usize {
+                self.#array_ident.len()
+            }
+
+            fn write_repr(&self, bytes: &mut [u8]) {
+                #write_fields
+                self.#array_ident.write_repr::<::sierra::Std430>(
+                    bytes,
+                    <Self as ::sierra::ShaderReprRuntime<::sierra::Std430>>::ARRAY_OFFSET,
+                );
+            }
+        }
+
+        #offset_asserts
+        #array_offset_assert
+    )
+}
@@ -0,0 +1,83 @@
+use {
+    super::parse::{field_name, Input},
+    proc_macro2::TokenStream,
+};
+
+pub fn generate_wgsl_type(input: &Input) -> TokenStream {
+    let ident = &input.name;
+    let self_ty = &input.self_ty;
+
+    ["Std140", "Std430"]
+        .iter()
+        .map(|layout| {
+            let layout = quote::format_ident!("{}", layout);
+            let name = syn::LitStr::new(&format!("{}{}", ident, layout), ident.span());
+            let head = syn::LitStr::new(&format!("struct {}{} {{\n", ident, layout), ident.span());
+
+            let deps = input
+                .fields
+                .iter()
+                .chain(input.runtime_array.iter().map(|array| &array.field))
+                .map(|field| {
+                let ty = &field.ty;
+                quote::quote!(ctx.add::<#ty, ::sierra::#layout>();)
+            });
+
+            let members = input.fields.iter().enumerate().map(|(index, field)| {
+                let ty = &field.ty;
+                let field_name = syn::LitStr::new(&field_name(&field.ident), field.ident.span());
+
+                // First member is aligned as whole structure to get the same size.
+                // Structures with runtime-sized array have no size.
+                let align_mask = if index == 0 && input.runtime_array.is_none() {
+                    quote::quote!(<#self_ty as ::sierra::ShaderRepr<::sierra::#layout>>::ALIGN_MASK)
+                } else {
+                    quote::quote!(<#ty as ::sierra::ShaderRepr<::sierra::#layout>>::ALIGN_MASK)
+                };
+
+                quote::quote!(
+                    def.push_str(&::sierra::wgsl::struct_member::<#ty, ::sierra::#layout>(#field_name, #align_mask));
+                )
+            });
+
+            // Structures with runtime-sized array are inlined into buffer block.
+            let def = match &input.runtime_array {
+                Some(array) => {
+                    let elem = &array.elem;
+                    let array_name = syn::LitStr::new(&field_name(&array.field.ident), array.field.ident.span());
+
+                    quote::quote!(
+                        fn block_members() -> ::std::option::Option<::std::string::String> {
+                            let mut def = ::std::string::String::new();
+                            #(#members)*
+                            def.push_str(&::sierra::wgsl::runtime_array_member::<#elem, ::sierra::#layout>(#array_name));
+                            ::std::option::Option::Some(def)
+                        }
+                    )
+                }
+                None => quote::quote!(
+                    fn def() -> ::std::string::String {
+                        let mut def = ::std::string::String::from(#head);
+                        #(#members)*
+                        def.push_str("};");
+                        def
+                    }
+                ),
+            };
+
+            quote::quote!(
+                impl ::sierra::wgsl::WgslType<::sierra::#layout> for #self_ty {
+                    fn name() -> ::std::string::String {
+                        ::std::string::String::from(#name)
+                    }
+
+                    fn deps(ctx: &mut ::sierra::wgsl::WgslTypeContext) {
+                        #(#deps)*
+                    }
+
+                    #def
+                }
+            )
+        })
+        .collect()
+}
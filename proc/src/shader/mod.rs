@@ -1,3 +0,0 @@
-mod glsl;
-
-pub use self::glsl::*;
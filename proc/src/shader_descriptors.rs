@@ -0,0 +1,355 @@
+use {
+    crate::include_shader::{load_shader, shader_path, track_files},
+    proc_macro2::TokenStream,
+    std::collections::BTreeMap,
+    syn::parse::{Parse, ParseStream},
+};
+
+struct Args {
+    path: syn::LitStr,
+    set: u32,
+    count: Option<u32>,
+    defines: Vec<(syn::Ident, syn::LitStr)>,
+}
+
+impl Parse for Args {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let path = stream.parse::<syn::LitStr>()?;
+
+        let mut set = None;
+        let mut count = None;
+        let mut defines = Vec::new();
+
+        while !stream.is_empty() {
+            let _ = stream.parse::<syn::Token![,]>()?;
+            if stream.is_empty() {
+                break;
+            }
+
+            let name = stream.parse::<syn::Ident>()?;
+            let _ = stream.parse::<syn::Token![=]>()?;
+
+            if name == "set" {
+                if set.is_some() {
+                    return Err(syn::Error::new_spanned(name, "Duplicate `set` argument"));
+                }
+                set = Some(stream.parse::<syn::LitInt>()?.base10_parse()?);
+            } else if name == "count" {
+                if count.is_some() {
+                    return Err(syn::Error::new_spanned(name, "Duplicate `count` argument"));
+                }
+                count = Some(stream.parse::<syn::LitInt>()?.base10_parse()?);
+            } else {
+                defines.push((name, stream.parse()?));
+            }
+        }
+
+        Ok(Args {
+            path,
+            set: set.unwrap_or(0),
+            count,
+            defines,
+        })
+    }
+}
+
+/// Descriptor binding collected from all entry points.
+struct Binding {
+    name: Option<String>,
+    ty: naga::Handle<naga::Type>,
+    class: naga::StorageClass,
+    stages: Vec<&'static str>,
+}
+
+pub fn shader_descriptors(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> TokenStream {
+    match syn::parse(attr).and_then(|args| generate(&args, syn::parse(item)?)) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn generate(args: &Args, item_struct: syn::ItemStruct) -> syn::Result<TokenStream> {
+    if !matches!(item_struct.fields, syn::Fields::Unit) {
+        return Err(syn::Error::new_spanned(
+            &item_struct.fields,
+            "Fields are generated from shader. Expected unit struct",
+        ));
+    }
+
+    let span = args.path.span();
+    let path = shader_path(&args.path);
+    let shader = load_shader(&path, &args.defines, span)?;
+    let module = &shader.module;
+
+    let mut bindings = BTreeMap::new();
+
+    for (index, entry_point) in module.entry_points.iter().enumerate() {
+        let function_info = shader.info.get_entry_point(index);
+        let stage = match entry_point.stage {
+            naga::ShaderStage::Vertex => "Vertex",
+            naga::ShaderStage::Fragment => "Fragment",
+            naga::ShaderStage::Compute => "Compute",
+        };
+
+        for (handle, var) in module.global_variables.iter() {
+            if function_info[handle].is_empty() {
+                continue;
+            }
+
+            match &var.binding {
+                Some(binding) if binding.group == args.set => {
+                    let binding = bindings.entry(binding.binding).or_insert_with(|| Binding {
+                        name: var.name.clone(),
+                        ty: var.ty,
+                        class: var.class,
+                        stages: Vec::new(),
+                    });
+
+                    if !binding.stages.contains(&stage) {
+                        binding.stages.push(stage);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut fields = Vec::with_capacity(bindings.len());
+
+    for (index, (binding, reflected)) in bindings.iter().enumerate() {
+        if *binding as usize != index {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "Bindings of set {} must be contiguous starting from 0, binding {} is missing",
+                    args.set, index
+                ),
+            ));
+        }
+
+        let ident = match &reflected.name {
+            Some(name) => syn::parse_str::<syn::Ident>(name)
+                .unwrap_or_else(|_| quote::format_ident!("binding_{}", binding)),
+            None => quote::format_ident!("binding_{}", binding),
+        };
+
+        let (attr, ty) = match field_type(module, reflected, args.count) {
+            Ok(field) => field,
+            Err(FieldTypeError::Unsupported) => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Binding {} of set {} has type unsupported by `#[descriptors]`",
+                        binding, args.set
+                    ),
+                ))
+            }
+            Err(FieldTypeError::RuntimeSized) => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Binding {} of set {} is runtime-sized array. \
+                         Number of descriptors in the binding must be known to create layout, \
+                         specify it with `count = N` argument",
+                        binding, args.set
+                    ),
+                ))
+            }
+        };
+
+        let stages = reflected
+            .stages
+            .iter()
+            .map(|stage| quote::format_ident!("{}", stage));
+
+        fields.push(quote::quote!(
+            #attr
+            #[stages(#(#stages),*)]
+            pub #ident: #ty
+        ));
+    }
+
+    let syn::ItemStruct {
+        attrs,
+        vis,
+        struct_token,
+        ident,
+        generics,
+        ..
+    } = &item_struct;
+
+    let track = track_files(&shader.files);
+
+    Ok(quote::quote!(
+        #track
+
+        #[::sierra::descriptors]
+        #(#attrs)*
+        #vis #struct_token #ident #generics {
+            #(#fields,)*
+        }
+    ))
+}
+
+enum FieldTypeError {
+    Unsupported,
+    RuntimeSized,
+}
+
+enum ArrayCount {
+    Constant(usize),
+    Runtime(u32),
+}
+
+/// Returns field attribute and type for reflected binding.
+///
+/// Buffer contents are declared as bytes, so generated struct
+/// must not request shader declarations that require buffer types.
+/// Runtime-sized arrays become `Vec` fields with `runtime_count` descriptors.
+fn field_type(
+    module: &naga::Module,
+    binding: &Binding,
+    runtime_count: Option<u32>,
+) -> Result<(TokenStream, TokenStream), FieldTypeError> {
+    match binding.class {
+        naga::StorageClass::Uniform => Ok((
+            quote::quote!(#[buffer(uniform, ty = [u8])]),
+            quote::quote!(::sierra::BufferRange),
+        )),
+        naga::StorageClass::Storage { .. } => Ok((
+            quote::quote!(#[buffer(storage, ty = [u8])]),
+            quote::quote!(::sierra::BufferRange),
+        )),
+        naga::StorageClass::Handle => {
+            let (ty, count) = match &module.types[binding.ty].inner {
+                naga::TypeInner::Array {
+                    base,
+                    size: naga::ArraySize::Constant(constant),
+                    ..
+                } => {
+                    let count = match module.constants[*constant].inner {
+                        naga::ConstantInner::Scalar {
+                            value: naga::ScalarValue::Uint(value),
+                            ..
+                        } => value as usize,
+                        naga::ConstantInner::Scalar {
+                            value: naga::ScalarValue::Sint(value),
+                            ..
+                        } => value as usize,
+                        _ => return Err(FieldTypeError::Unsupported),
+                    };
+                    (*base, Some(ArrayCount::Constant(count)))
+                }
+                naga::TypeInner::Array {
+                    base,
+                    size: naga::ArraySize::Dynamic,
+                    ..
+                } => match runtime_count {
+                    Some(count) => (*base, Some(ArrayCount::Runtime(count))),
+                    None => return Err(FieldTypeError::RuntimeSized),
+                },
+                _ => (binding.ty, None),
+            };
+
+            let (attr, ty) = match &module.types[ty].inner {
+                naga::TypeInner::Image {
+                    class: naga::ImageClass::Storage { .. },
+                    ..
+                } => (
+                    quote::quote!(storage_image),
+                    quote::quote!(::sierra::ImageView),
+                ),
+                naga::TypeInner::Image { .. } => (
+                    quote::quote!(sampled_image),
+                    quote::quote!(::sierra::ImageView),
+                ),
+                naga::TypeInner::Sampler { .. } => {
+                    (quote::quote!(sampler), quote::quote!(::sierra::Sampler))
+                }
+                _ => return Err(FieldTypeError::Unsupported),
+            };
+
+            match count {
+                Some(ArrayCount::Constant(count)) => {
+                    Ok((quote::quote!(#[#attr]), quote::quote!([#ty; #count])))
+                }
+                Some(ArrayCount::Runtime(count)) => Ok((
+                    quote::quote!(#[#attr(count = #count)]),
+                    quote::quote!(::std::vec::Vec<#ty>),
+                )),
+                None => Ok((quote::quote!(#[#attr]), ty)),
+            }
+        }
+        _ => Err(FieldTypeError::Unsupported),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_type, Binding, FieldTypeError};
+
+    /// Returns module with runtime-sized array of sampled images and binding of it.
+    fn runtime_array() -> (naga::Module, Binding) {
+        let mut module = naga::Module::default();
+        let image = module.types.append(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Image {
+                    dim: naga::ImageDimension::D2,
+                    arrayed: false,
+                    class: naga::ImageClass::Sampled {
+                        kind: naga::ScalarKind::Float,
+                        multi: false,
+                    },
+                },
+            },
+            naga::Span::default(),
+        );
+        let array = module.types.append(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Array {
+                    base: image,
+                    size: naga::ArraySize::Dynamic,
+                    stride: 0,
+                },
+            },
+            naga::Span::default(),
+        );
+
+        let binding = Binding {
+            name: Some("textures".to_owned()),
+            ty: array,
+            class: naga::StorageClass::Handle,
+            stages: vec!["Fragment"],
+        };
+
+        (module, binding)
+    }
+
+    #[test]
+    fn runtime_array_is_vec_with_count() {
+        let (module, binding) = runtime_array();
+        let (attr, ty) = field_type(&module, &binding, Some(64)).ok().unwrap();
+        assert_eq!(
+            attr.to_string(),
+            quote::quote!(#[sampled_image(count = 64u32)]).to_string()
+        );
+        assert_eq!(
+            ty.to_string(),
+            quote::quote!(::std::vec::Vec<::sierra::ImageView>).to_string()
+        );
+    }
+
+    #[test]
+    fn runtime_array_requires_count() {
+        let (module, binding) = runtime_array();
+        assert!(matches!(
+            field_type(&module, &binding, None),
+            Err(FieldTypeError::RuntimeSized)
+        ));
+    }
+}
@@ -15,6 +15,52 @@ pub enum Stage {
     Intersection,
 }
 
+/// Parses stage from its name.
+pub fn parse_stage(ident: &syn::Ident) -> Option<Stage> {
+    match ident {
+        i if i == "Vertex" => Some(Stage::Vertex),
+        i if i == "TessellationControl" => Some(Stage::TessellationControl),
+        i if i == "TessellationEvaluation" => Some(Stage::TessellationEvaluation),
+        i if i == "Geometry" => Some(Stage::Geometry),
+        i if i == "Fragment" => Some(Stage::Fragment),
+        i if i == "Compute" => Some(Stage::Compute),
+        i if i == "Raygen" => Some(Stage::Raygen),
+        i if i == "AnyHit" => Some(Stage::AnyHit),
+        i if i == "ClosestHit" => Some(Stage::ClosestHit),
+        i if i == "Miss" => Some(Stage::Miss),
+        i if i == "Intersection" => Some(Stage::Intersection),
+        _ => None,
+    }
+}
+
+/// Parses stage names separated by `,` or `|`.
+pub fn parse_stages(stream: syn::parse::ParseStream<'_>) -> syn::Result<Vec<Stage>> {
+    let mut stages = Vec::new();
+
+    while !stream.is_empty() {
+        let ident = stream.parse::<syn::Ident>()?;
+        let stage = parse_stage(&ident).ok_or_else(|| {
+            syn::Error::new_spanned(&ident, format!("Unrecognized stage `{}`", ident))
+        })?;
+        stages.push(stage);
+
+        if stream.is_empty() {
+            break;
+        }
+
+        let lookahead = stream.lookahead1();
+        if lookahead.peek(syn::Token![,]) {
+            stream.parse::<syn::Token![,]>()?;
+        } else if lookahead.peek(syn::Token![|]) {
+            stream.parse::<syn::Token![|]>()?;
+        } else {
+            return Err(lookahead.error());
+        }
+    }
+
+    Ok(stages)
+}
+
 pub fn stage_flag_tokens(stage: Stage) -> TokenStream {
     match stage {
         Stage::Vertex => quote::quote!(::sierra::ShaderStageFlags::VERTEX),
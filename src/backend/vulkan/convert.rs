@@ -3,9 +3,10 @@ use crate::{
     AspectFlags, BlendFactor, BlendOp, BorderColor, BufferCopy, BufferImageCopy, BufferUsage,
     CompareOp, ComponentMask, CompositeAlphaFlags, Culling, DescriptorBindingFlags,
     DescriptorSetLayoutFlags, DescriptorType, DeviceAddress, Extent2d, Extent3d, Filter, Format,
-    FrontFace, GeometryFlags, ImageBlit, ImageCopy, ImageExtent, ImageUsage, ImageViewKind,
-    IndexType, Layout, LoadOp, LogicOp, MemoryUsage, MipmapMode, Offset2d, Offset3d, OutOfMemory,
-    PipelineStageFlags, PolygonMode, PresentMode, PrimitiveTopology, QueueCapabilityFlags, Rect2d,
+    FrontFace, GeometryFlags, ImageBlit, ImageCopy, ImageExtent, ImageResolve, ImageUsage,
+    ImageViewKind, IndexType, Layout, LoadOp, LogicOp, MemoryUsage, MipmapMode, Offset2d, Offset3d,
+    OutOfMemory, PipelineStageFlags, PipelineStatisticFlags, PolygonMode, PresentMode,
+    PrimitiveTopology, QueryResultFlags, QueryType, QueueCapabilityFlags, Rect2d,
     SamplerAddressMode, Samples, ShaderStage, ShaderStageFlags, StencilOp, StoreOp, Subresource,
     SubresourceLayers, SubresourceRange, SurfaceTransformFlags, VertexInputRate, Viewport,
 };
@@ -1153,8 +1154,8 @@ impl ToErupt<vk1_0::DescriptorType> for DescriptorType {
             Self::CombinedImageSampler => vk1_0::DescriptorType::COMBINED_IMAGE_SAMPLER,
             Self::SampledImage => vk1_0::DescriptorType::SAMPLED_IMAGE,
             Self::StorageImage => vk1_0::DescriptorType::STORAGE_IMAGE,
-            // Self::UniformTexelBuffer => vk1_0::DescriptorType::UNIFORM_TEXEL_BUFFER,
-            // Self::StorageTexelBuffer => vk1_0::DescriptorType::STORAGE_TEXEL_BUFFER,
+            Self::UniformTexelBuffer => vk1_0::DescriptorType::UNIFORM_TEXEL_BUFFER,
+            Self::StorageTexelBuffer => vk1_0::DescriptorType::STORAGE_TEXEL_BUFFER,
             Self::UniformBuffer => vk1_0::DescriptorType::UNIFORM_BUFFER,
             Self::StorageBuffer => vk1_0::DescriptorType::STORAGE_BUFFER,
             Self::UniformBufferDynamic => vk1_0::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
@@ -1254,6 +1255,18 @@ impl ToErupt<vk1_0::ImageCopy> for ImageCopy {
     }
 }
 
+impl ToErupt<vk1_0::ImageResolve> for ImageResolve {
+    fn to_erupt(self) -> vk1_0::ImageResolve {
+        vk1_0::ImageResolve {
+            src_subresource: self.src_subresource.to_erupt(),
+            src_offset: self.src_offset.to_erupt(),
+            dst_subresource: self.dst_subresource.to_erupt(),
+            dst_offset: self.dst_offset.to_erupt(),
+            extent: self.extent.to_erupt(),
+        }
+    }
+}
+
 impl ToErupt<vk1_0::BufferCopy> for BufferCopy {
     fn to_erupt(self) -> vk1_0::BufferCopy {
         vk1_0::BufferCopy {
@@ -1526,3 +1539,87 @@ impl ToErupt<SurfaceTransformFlagsKHR> for SurfaceTransformFlags {
         result
     }
 }
+
+impl ToErupt<vk1_0::QueryType> for QueryType {
+    fn to_erupt(self) -> vk1_0::QueryType {
+        match self {
+            QueryType::Occlusion => vk1_0::QueryType::OCCLUSION,
+            QueryType::PipelineStatistics(_) => vk1_0::QueryType::PIPELINE_STATISTICS,
+            QueryType::Timestamp => vk1_0::QueryType::TIMESTAMP,
+        }
+    }
+}
+
+impl ToErupt<vk1_0::QueryPipelineStatisticFlags> for PipelineStatisticFlags {
+    fn to_erupt(self) -> vk1_0::QueryPipelineStatisticFlags {
+        let mut result = vk1_0::QueryPipelineStatisticFlags::empty();
+
+        if self.contains(PipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES) {
+            result |= vk1_0::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES;
+        }
+
+        if self.contains(PipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES) {
+            result |= vk1_0::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES;
+        }
+
+        if self.contains(PipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS) {
+            result |= vk1_0::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS;
+        }
+
+        if self.contains(PipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS) {
+            result |= vk1_0::QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS;
+        }
+
+        if self.contains(PipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES) {
+            result |= vk1_0::QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES;
+        }
+
+        if self.contains(PipelineStatisticFlags::CLIPPING_INVOCATIONS) {
+            result |= vk1_0::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS;
+        }
+
+        if self.contains(PipelineStatisticFlags::CLIPPING_PRIMITIVES) {
+            result |= vk1_0::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES;
+        }
+
+        if self.contains(PipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS) {
+            result |= vk1_0::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS;
+        }
+
+        if self.contains(PipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES) {
+            result |= vk1_0::QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES;
+        }
+
+        if self.contains(PipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS) {
+            result |=
+                vk1_0::QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS;
+        }
+
+        if self.contains(PipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS) {
+            result |= vk1_0::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS;
+        }
+
+        result
+    }
+}
+
+impl ToErupt<vk1_0::QueryResultFlags> for QueryResultFlags {
+    fn to_erupt(self) -> vk1_0::QueryResultFlags {
+        // Results are always fetched as 64-bit values.
+        let mut result = vk1_0::QueryResultFlags::_64;
+
+        if self.contains(QueryResultFlags::WAIT) {
+            result |= vk1_0::QueryResultFlags::WAIT;
+        }
+
+        if self.contains(QueryResultFlags::WITH_AVAILABILITY) {
+            result |= vk1_0::QueryResultFlags::WITH_AVAILABILITY;
+        }
+
+        if self.contains(QueryResultFlags::PARTIAL) {
+            result |= vk1_0::QueryResultFlags::PARTIAL;
+        }
+
+        result
+    }
+}
@@ -8,6 +8,8 @@ use {
         epochs::Epochs,
         graphics::Graphics,
         physical::{Features, Properties},
+        queue::Queue,
+        resources::NamedObject,
         unexpected_result,
     },
     crate::{
@@ -16,7 +18,8 @@ use {
             AccelerationStructureBuildSizesInfo, AccelerationStructureGeometryInfo,
             AccelerationStructureInfo, AccelerationStructureLevel,
         },
-        align_up, arith_eq, arith_le, arith_ne, assert_object,
+        access::AccessFlags,
+        align_up, arith_eq, arith_lt, arith_ne, assert_object,
         buffer::{
             Buffer, BufferInfo, BufferRange, BufferUsage, MappableBuffer, StridedBufferRange,
         },
@@ -26,36 +29,44 @@ use {
             DescriptorSetLayoutInfo, DescriptorType, Descriptors, DescriptorsAllocationError,
             WriteDescriptorSet,
         },
+        encode::{BufferCopy, BufferImageCopy, CommandBuffer},
         fence::Fence,
+        format::{AspectFlags, Format, FormatDescription},
         framebuffer::{Framebuffer, FramebufferInfo},
         host_memory_space_overlow,
-        image::{Image, ImageInfo},
+        image::{
+            Image, ImageInfo, ImageMemoryBarrier, ImageUsage, Layout, SubresourceLayers,
+            SubresourceRange,
+        },
         memory::MemoryUsage,
         out_of_host_memory,
         pipeline::{
-            ColorBlend, ComputePipeline, ComputePipelineInfo, GraphicsPipeline,
-            GraphicsPipelineInfo, PipelineLayout, PipelineLayoutInfo, RayTracingPipeline,
-            RayTracingPipelineInfo, RayTracingShaderGroupInfo, ShaderBindingTable,
-            ShaderBindingTableInfo, State,
+            ColorBlend, ComputePipeline, ComputePipelineInfo, CreatePipelineError,
+            GraphicsPipeline, GraphicsPipelineInfo, PipelineLayout, PipelineLayoutInfo,
+            RayTracingPipeline, RayTracingPipelineInfo, RayTracingShaderGroupInfo,
+            ShaderBindingTable, ShaderBindingTableInfo, State,
         },
+        query::{QueryPool, QueryPoolInfo, QueryResultFlags, QueryType},
         queue::QueueId,
+        reflect::{EntryPointReflection, ShaderInterfaceMismatch, ShaderReflection},
         render_pass::{CreateRenderPassError, RenderPass, RenderPassInfo},
         sampler::{Sampler, SamplerInfo},
         semaphore::Semaphore,
         shader::{
-            CreateShaderModuleError, InvalidShader, ShaderLanguage, ShaderModule, ShaderModuleInfo,
-            ShaderStage,
+            naga_to_spirv, parse_with_naga, CreateShaderModuleError, InvalidShader, ShaderLanguage,
+            ShaderModule, ShaderModuleInfo, ShaderStage,
         },
+        stage::PipelineStageFlags,
         surface::{Surface, SurfaceError},
         swapchain::Swapchain,
-        view::{ImageView, ImageViewInfo, ImageViewKind},
-        CreateImageError, DeviceAddress, IndexType, MapError, OutOfMemory,
+        view::{BufferView, BufferViewInfo, ImageView, ImageViewInfo, ImageViewKind},
+        CreateImageError, DeviceAddress, Extent3d, IndexType, MapError, Offset3d, OutOfMemory,
     },
     bytemuck::Pod,
     erupt::{
         extensions::{
-            khr_acceleration_structure as vkacc, khr_ray_tracing_pipeline as vkrt,
-            khr_swapchain as vksw,
+            ext_debug_utils as vkdu, khr_acceleration_structure as vkacc,
+            khr_ray_tracing_pipeline as vkrt, khr_swapchain as vksw,
         },
         vk1_0, vk1_2, DeviceLoader, ExtendableFromConst as _,
     },
@@ -64,6 +75,7 @@ use {
     gpu_descriptor::{DescriptorAllocator, DescriptorSetLayoutCreateFlags, DescriptorTotalCount},
     gpu_descriptor_erupt::EruptDescriptorDevice,
     parking_lot::Mutex,
+    scoped_arena::Scope,
     slab::Slab,
     smallvec::SmallVec,
     std::{
@@ -71,9 +83,8 @@ use {
         convert::{TryFrom as _, TryInto as _},
         ffi::CString,
         fmt::{self, Debug},
-        mem::{size_of_val, MaybeUninit},
+        mem::{size_of, size_of_val, MaybeUninit},
         ops::Range,
-        str::from_utf8,
         sync::{Arc, Weak},
     },
 };
@@ -100,6 +111,7 @@ pub(crate) struct Inner {
     allocator: Mutex<GpuAllocator<vk1_0::DeviceMemory>>,
     version: u32,
     buffers: Mutex<Slab<vk1_0::Buffer>>,
+    buffer_views: Mutex<Slab<vk1_0::BufferView>>,
     descriptor_allocator: Mutex<DescriptorAllocator<vk1_0::DescriptorPool, vk1_0::DescriptorSet>>,
     descriptor_set_layouts: Mutex<Slab<vk1_0::DescriptorSetLayout>>,
     fences: Mutex<Slab<vk1_0::Fence>>,
@@ -113,6 +125,7 @@ pub(crate) struct Inner {
     shaders: Mutex<Slab<vk1_0::ShaderModule>>,
     acceleration_strucutres: Mutex<Slab<vkacc::AccelerationStructureKHR>>,
     samplers: Mutex<Slab<vk1_0::Sampler>>,
+    query_pools: Mutex<Slab<vk1_0::QueryPool>>,
     swapchains: Mutex<Slab<vksw::SwapchainKHR>>,
 
     samplers_cache: Mutex<HashMap<SamplerInfo, Sampler>>,
@@ -233,6 +246,21 @@ impl Device {
         &self.inner.properties
     }
 
+    pub(super) fn features(&self) -> &Features {
+        &self.inner.features
+    }
+
+    /// Returns epochs of all queues at this moment.
+    /// Work submitted so far is complete once these epochs are closed.
+    pub(crate) fn current_epochs(&self) -> Vec<(QueueId, u64)> {
+        self.inner.epochs.current_all_queues()
+    }
+
+    /// Checks if all work submitted before epochs were taken is complete.
+    pub(crate) fn epochs_closed(&self, epochs: &[(QueueId, u64)]) -> bool {
+        self.inner.epochs.are_closed(epochs)
+    }
+
     pub(super) fn epochs(&self) -> &Epochs {
         &self.inner.epochs
     }
@@ -260,6 +288,7 @@ impl Device {
 
                 // Numbers here are hints so no strong reasoning is required.
                 buffers: Mutex::new(Slab::with_capacity(4096)),
+                buffer_views: Mutex::new(Slab::with_capacity(128)),
                 descriptor_set_layouts: Mutex::new(Slab::with_capacity(64)),
                 fences: Mutex::new(Slab::with_capacity(128)),
                 framebuffers: Mutex::new(Slab::with_capacity(128)),
@@ -273,6 +302,7 @@ impl Device {
                 swapchains: Mutex::new(Slab::with_capacity(32)),
                 acceleration_strucutres: Mutex::new(Slab::with_capacity(1024)),
                 samplers: Mutex::new(Slab::with_capacity(128)),
+                query_pools: Mutex::new(Slab::with_capacity(64)),
 
                 logical,
                 physical,
@@ -302,6 +332,37 @@ impl Device {
         }
     }
 
+    /// Attaches debug name to the device object.
+    /// Debugging tools and validation messages refer to the object by this name.
+    ///
+    /// Does nothing if `VK_EXT_debug_utils` is not enabled.
+    #[tracing::instrument(skip(object))]
+    pub fn set_object_name(&self, object: &impl NamedObject, name: &str) {
+        assert!(object.is_owned_by(&self.downgrade()));
+
+        if !self.graphics().instance.enabled().ext_debug_utils {
+            return;
+        }
+
+        let name = CString::new(name).expect("Object name must not contain nul bytes");
+
+        let result = unsafe {
+            self.inner.logical.set_debug_utils_object_name_ext(
+                &vkdu::DebugUtilsObjectNameInfoEXTBuilder::new()
+                    .object_type(object.object_type())
+                    .object_handle(object.object_handle())
+                    .object_name(&name),
+            )
+        }
+        .result();
+
+        match result {
+            Ok(()) => {}
+            Err(vk1_0::Result::ERROR_OUT_OF_HOST_MEMORY) => out_of_host_memory(),
+            Err(result) => unexpected_result(result),
+        }
+    }
+
     /// Creates buffer with uninitialized content.
     #[tracing::instrument]
     pub fn create_buffer(&self, info: BufferInfo) -> Result<Buffer, OutOfMemory> {
@@ -477,6 +538,48 @@ impl Device {
         self.inner.logical.destroy_buffer(Some(handle), None);
     }
 
+    /// Creates formatted view to a buffer region.
+    /// Buffer must be created with `UNIFORM_TEXEL` or `STORAGE_TEXEL` usage.
+    #[tracing::instrument]
+    pub fn create_buffer_view(&self, info: BufferViewInfo) -> Result<BufferView, OutOfMemory> {
+        assert_owner!(info.buffer, self);
+        assert!(
+            info.buffer
+                .info()
+                .usage
+                .intersects(BufferUsage::UNIFORM_TEXEL | BufferUsage::STORAGE_TEXEL),
+            "Buffer must be created with `UNIFORM_TEXEL` or `STORAGE_TEXEL` usage to create views"
+        );
+        assert!(
+            info.offset <= info.buffer.info().size
+                && info.size <= info.buffer.info().size - info.offset,
+            "Buffer view range is out of bounds"
+        );
+
+        let view = unsafe {
+            self.inner.logical.create_buffer_view(
+                &vk1_0::BufferViewCreateInfoBuilder::new()
+                    .buffer(info.buffer.handle())
+                    .format(info.format.to_erupt())
+                    .offset(info.offset)
+                    .range(info.size),
+                None,
+            )
+        }
+        .result()
+        .map_err(oom_error_from_erupt)?;
+
+        let index = self.inner.buffer_views.lock().insert(view);
+
+        tracing::debug!("BufferView created {:p}", view);
+        Ok(BufferView::new(info, self.downgrade(), view, index))
+    }
+
+    pub(super) unsafe fn destroy_buffer_view(&self, index: usize) {
+        let handle = self.inner.buffer_views.lock().remove(index);
+        self.inner.logical.destroy_buffer_view(Some(handle), None);
+    }
+
     /// Returns handle to newly created [`Fence`].
     /// Fences are create in un-signaled state.
     #[tracing::instrument]
@@ -557,11 +660,15 @@ impl Device {
     }
 
     /// Creates graphics pipeline.
+    ///
+    /// Shader interfaces are validated against pipeline layout
+    /// and vertex input only if shader modules were reflected.
+    /// Validation of modules without reflection is skipped.
     #[tracing::instrument]
     pub fn create_graphics_pipeline(
         &self,
         info: GraphicsPipelineInfo,
-    ) -> Result<GraphicsPipeline, OutOfMemory> {
+    ) -> Result<GraphicsPipeline, CreatePipelineError> {
         assert_owner!(info.layout, self);
         assert_owner!(info.render_pass, self);
         assert_owner!(info.vertex_shader.module(), self);
@@ -573,6 +680,29 @@ impl Device {
             assert_owner!(fragment_shader.module(), self);
         }
 
+        if let Some(entry_point) = reflect_entry_point(
+            info.vertex_shader.module(),
+            info.vertex_shader.entry(),
+            ShaderStage::Vertex,
+        )? {
+            entry_point.validate_layout(info.layout.info())?;
+            entry_point.validate_vertex_input(&info.vertex_attributes)?;
+        }
+
+        if let Some(fragment_shader) = info
+            .rasterizer
+            .as_ref()
+            .and_then(|r| r.fragment_shader.as_ref())
+        {
+            if let Some(entry_point) = reflect_entry_point(
+                fragment_shader.module(),
+                fragment_shader.entry(),
+                ShaderStage::Fragment,
+            )? {
+                entry_point.validate_layout(info.layout.info())?;
+            }
+        }
+
         let vertex_shader_entry: CString;
         let fragment_shader_entry: CString;
         let mut shader_stages = Vec::with_capacity(2);
@@ -672,10 +802,19 @@ impl Device {
                 .front_face(rasterizer.front_face.to_erupt())
                 .line_width(1.0);
 
-            multisample_state = Some(
-                vk1_0::PipelineMultisampleStateCreateInfoBuilder::new()
-                    .rasterization_samples(vk1_0::SampleCountFlagBits::_1),
-            );
+            let multisample = &rasterizer.multisample;
+            let mut builder = vk1_0::PipelineMultisampleStateCreateInfoBuilder::new()
+                .rasterization_samples(multisample.samples.to_erupt())
+                .alpha_to_coverage_enable(multisample.alpha_to_coverage)
+                .alpha_to_one_enable(multisample.alpha_to_one);
+
+            if let Some(min_sample_shading) = multisample.sample_shading {
+                builder = builder
+                    .sample_shading_enable(true)
+                    .min_sample_shading(min_sample_shading.into_inner());
+            }
+
+            multisample_state = Some(builder);
 
             let mut builder = vk1_0::PipelineDepthStencilStateCreateInfoBuilder::new();
 
@@ -897,14 +1036,26 @@ impl Device {
     }
 
     /// Creates compute pipeline.
+    ///
+    /// Shader interface is validated against pipeline layout
+    /// only if shader module was reflected.
+    /// Validation of module without reflection is skipped.
     #[tracing::instrument]
     pub fn create_compute_pipeline(
         &self,
         info: ComputePipelineInfo,
-    ) -> Result<ComputePipeline, OutOfMemory> {
+    ) -> Result<ComputePipeline, CreatePipelineError> {
         assert_owner!(info.shader.module(), self);
         assert_owner!(info.layout, self);
 
+        if let Some(entry_point) = reflect_entry_point(
+            info.shader.module(),
+            info.shader.entry(),
+            ShaderStage::Compute,
+        )? {
+            entry_point.validate_layout(info.layout.info())?;
+        }
+
         let shader_entry = entry_name_to_cstr(info.shader.entry());
 
         let pipelines = unsafe {
@@ -1263,76 +1414,141 @@ impl Device {
     ) -> Result<RenderPass, CreateRenderPassError> {
         let mut subpass_attachments = Vec::new();
 
-        let subpasses =
-            info.subpasses
-                .iter()
-                .enumerate()
-                .map(|(si, s)| -> Result<_, CreateRenderPassError> {
-                    let color_offset = subpass_attachments.len();
-                    subpass_attachments.extend(
-                        s.colors
-                            .iter()
-                            .enumerate()
-                            .map(|(ci, &(c, cl))| -> Result<_, CreateRenderPassError> {
-                                Ok(vk1_0::AttachmentReferenceBuilder::new()
-                                .attachment(if arith_le(c, info.attachments.len()) {
-                                    Some(c)
-                                } else {
-                                    None
-                                }
-                                .and_then(|c| c.try_into().ok())
-                                .ok_or_else(|| {
-                                    CreateRenderPassError::ColorAttachmentReferenceOutOfBound {
-                                        subpass: si,
-                                        index: ci,
-                                        attachment: c,
-                                    }
-                                })?)
-                                .layout(cl.to_erupt())
-                            )
-                            })
-                            .collect::<Result<SmallVec<[_; 16]>, _>>()?,
-                    );
-
-                    let depth_offset = subpass_attachments.len();
-                    if let Some((d, dl)) = s.depth {
-                        subpass_attachments.push(
-                            vk1_0::AttachmentReferenceBuilder::new()
+        let subpasses = info
+            .subpasses
+            .iter()
+            .enumerate()
+            .map(|(si, s)| -> Result<_, CreateRenderPassError> {
+                let color_offset = subpass_attachments.len();
+                subpass_attachments.extend(
+                    s.colors
+                        .iter()
+                        .enumerate()
+                        .map(|(ci, &(c, cl))| -> Result<_, CreateRenderPassError> {
+                            Ok(vk1_0::AttachmentReferenceBuilder::new()
                                 .attachment(
-                                    if arith_le(d, info.attachments.len()) {
-                                        Some(d)
+                                    if arith_lt(c, info.attachments.len()) {
+                                        Some(c)
                                     } else {
                                         None
                                     }
-                                    .and_then(|d| d.try_into().ok())
+                                    .and_then(|c| c.try_into().ok())
                                     .ok_or_else(|| {
-                                        CreateRenderPassError::DepthAttachmentReferenceOutOfBound {
+                                        CreateRenderPassError::ColorAttachmentReferenceOutOfBound {
                                             subpass: si,
-                                            attachment: d,
+                                            index: ci,
+                                            attachment: c,
                                         }
                                     })?,
                                 )
-                                .layout(dl.to_erupt()),
-                        );
-                    }
-                    Ok((color_offset, depth_offset))
-                })
-                .collect::<Result<SmallVec<[_; 16]>, _>>()?;
+                                .layout(cl.to_erupt()))
+                        })
+                        .collect::<Result<SmallVec<[_; 16]>, _>>()?,
+                );
+
+                let depth_offset = subpass_attachments.len();
+                if let Some((d, dl)) = s.depth {
+                    subpass_attachments.push(
+                        vk1_0::AttachmentReferenceBuilder::new()
+                            .attachment(
+                                if arith_lt(d, info.attachments.len()) {
+                                    Some(d)
+                                } else {
+                                    None
+                                }
+                                .and_then(|d| d.try_into().ok())
+                                .ok_or_else(|| {
+                                    CreateRenderPassError::DepthAttachmentReferenceOutOfBound {
+                                        subpass: si,
+                                        attachment: d,
+                                    }
+                                })?,
+                            )
+                            .layout(dl.to_erupt()),
+                    );
+                }
+
+                let input_offset = subpass_attachments.len();
+                subpass_attachments.extend(
+                    s.inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(ii, &(i, il))| -> Result<_, CreateRenderPassError> {
+                            if !arith_lt(i, info.attachments.len()) {
+                                return Err(
+                                    CreateRenderPassError::InputAttachmentReferenceOutOfBound {
+                                        subpass: si,
+                                        index: ii,
+                                        attachment: i,
+                                    },
+                                );
+                            }
+
+                            Ok(vk1_0::AttachmentReferenceBuilder::new()
+                                .attachment(i)
+                                .layout(il.to_erupt()))
+                        })
+                        .collect::<Result<SmallVec<[_; 16]>, _>>()?,
+                );
+
+                if !s.resolves.is_empty() && s.resolves.len() != s.colors.len() {
+                    return Err(CreateRenderPassError::ResolveAttachmentCountMismatch {
+                        subpass: si,
+                        colors: s.colors.len(),
+                        resolves: s.resolves.len(),
+                    });
+                }
+
+                let resolve_offset = subpass_attachments.len();
+                subpass_attachments.extend(
+                    s.resolves
+                        .iter()
+                        .enumerate()
+                        .map(|(ri, &(r, rl))| -> Result<_, CreateRenderPassError> {
+                            if !arith_lt(r, info.attachments.len()) {
+                                return Err(
+                                    CreateRenderPassError::ResolveAttachmentReferenceOutOfBound {
+                                        subpass: si,
+                                        index: ri,
+                                        attachment: r,
+                                    },
+                                );
+                            }
+
+                            Ok(vk1_0::AttachmentReferenceBuilder::new()
+                                .attachment(r)
+                                .layout(rl.to_erupt()))
+                        })
+                        .collect::<Result<SmallVec<[_; 16]>, _>>()?,
+                );
+
+                Ok((color_offset, depth_offset, input_offset, resolve_offset))
+            })
+            .collect::<Result<SmallVec<[_; 16]>, _>>()?;
 
         let subpasses = info
             .subpasses
             .iter()
             .zip(subpasses)
-            .map(|(s, (color_offset, depth_offset))| {
-                let builder = vk1_0::SubpassDescriptionBuilder::new()
-                    .color_attachments(&subpass_attachments[color_offset..depth_offset]);
+            .map(
+                |(s, (color_offset, depth_offset, input_offset, resolve_offset))| {
+                    let mut builder = vk1_0::SubpassDescriptionBuilder::new()
+                        .color_attachments(&subpass_attachments[color_offset..depth_offset])
+                        .input_attachments(&subpass_attachments[input_offset..resolve_offset]);
+
+                    if !s.resolves.is_empty() {
+                        builder = builder.resolve_attachments(
+                            &subpass_attachments[resolve_offset..resolve_offset + s.resolves.len()],
+                        );
+                    }
 
-                if s.depth.is_some() {
-                    builder.depth_stencil_attachment(&subpass_attachments[depth_offset])
-                } else {
-                    builder
-                }
-            })
+                    if s.depth.is_some() {
+                        builder.depth_stencil_attachment(&subpass_attachments[depth_offset])
+                    } else {
+                        builder
+                    }
+                },
+            )
             .collect::<Vec<_>>();
 
         let attachments = info
@@ -1345,7 +1561,7 @@ impl Device {
                     .store_op(a.store_op.to_erupt())
                     .initial_layout(a.initial_layout.to_erupt())
                     .final_layout(a.final_layout.to_erupt())
-                    .samples(vk1_0::SampleCountFlagBits::_1)
+                    .samples(a.samples.to_erupt())
             })
             .collect::<SmallVec<[_; 16]>>();
 
@@ -1422,80 +1638,85 @@ impl Device {
         self.inner.logical.destroy_semaphore(Some(handle), None);
     }
 
+    /// Compiles shader into SPIR-V or loads it from the shader cache
+    /// configured in `GraphicsConfig`.
+    /// Returns reflection if it was extracted during compilation.
+    fn compile_cached(
+        &self,
+        info: &ShaderModuleInfo,
+    ) -> Result<(Box<[u8]>, Option<ShaderReflection>), CreateShaderModuleError> {
+        let cached = match &self.graphics().shader_cache {
+            Some(dir) => Some(dir.join(format!("{:032x}.spv", info.cache_key()?))),
+            None => None,
+        };
+
+        if let Some(path) = &cached {
+            match std::fs::read(path) {
+                Ok(code) => {
+                    tracing::trace!("Shader loaded from cache {}", path.display());
+                    return Ok((code.into_boxed_slice(), None));
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    tracing::warn!("Failed to read cached shader {}: {}", path.display(), err)
+                }
+            }
+        }
+
+        let (code, reflection) = match parse_with_naga(info) {
+            Ok((module, module_info)) => (
+                naga_to_spirv(&module, &module_info)?,
+                ShaderReflection::from_naga(&module, &module_info),
+            ),
+            Err(CreateShaderModuleError::UnsupportedShaderLanguage { .. }) => {
+                if info.language == ShaderLanguage::HLSL && info.hlsl.is_none() {
+                    return Err(CreateShaderModuleError::MissingHlslOptions);
+                }
+                (self.compile_shader(info)?, None)
+            }
+            Err(err) => return Err(err),
+        };
+
+        if let Some(path) = &cached {
+            if let Err(err) = write_shader_cache(path, &code) {
+                tracing::warn!("Failed to cache shader {}: {}", path.display(), err);
+            }
+        }
+
+        Ok((code, reflection))
+    }
+
+    /// Compiles shader with compiler provided in `GraphicsConfig`.
+    fn compile_shader(
+        &self,
+        info: &ShaderModuleInfo,
+    ) -> Result<Box<[u8]>, CreateShaderModuleError> {
+        match &self.graphics().shader_compiler {
+            Some(compiler) if compiler.supports(info.language) => compiler
+                .compile(info)
+                .map_err(|source| CreateShaderModuleError::CompileError { source }),
+            _ => Err(CreateShaderModuleError::UnsupportedShaderLanguage {
+                language: info.language,
+            }),
+        }
+    }
+
     /// Creates new shader module from shader's code.
     #[tracing::instrument]
     pub fn create_shader_module(
         &self,
         info: ShaderModuleInfo,
     ) -> Result<ShaderModule, CreateShaderModuleError> {
-        let spv;
+        let compiled;
+        let mut reflection = None;
 
         let code = match info.language {
             ShaderLanguage::SPIRV => &*info.code,
-            ShaderLanguage::GLSL { stage } => {
-                let stage = match stage {
-                    ShaderStage::Vertex => naga::ShaderStage::Vertex,
-                    ShaderStage::Fragment => naga::ShaderStage::Fragment,
-                    ShaderStage::Compute => naga::ShaderStage::Compute,
-                    _ => {
-                        return Err(CreateShaderModuleError::UnsupportedShaderLanguage {
-                            language: info.language,
-                        })
-                    }
-                };
-
-                let code = from_utf8(&info.code)?;
-
-                let module = naga::front::glsl::Parser::default()
-                    .parse(
-                        &naga::front::glsl::Options {
-                            stage,
-                            defines: Default::default(),
-                        },
-                        code,
-                    )
-                    .map_err(|errors| CreateShaderModuleError::NagaGlslParseError { errors })?;
-
-                let info = naga::valid::Validator::new(
-                    naga::valid::ValidationFlags::all(),
-                    Default::default(),
-                )
-                .validate(&module)?;
-
-                spv = naga::back::spv::write_vec(
-                    &module,
-                    &info,
-                    &naga::back::spv::Options::default(),
-                )?;
-
-                bytemuck::cast_slice(&spv)
-            }
-
-            ShaderLanguage::WGSL => {
-                let code = from_utf8(&info.code)?;
-                let module = naga::front::wgsl::parse_str(code).map_err(|err| {
-                    CreateShaderModuleError::NagaWgslParseError {
-                        source: Box::from(err.emit_to_string(".")),
-                    }
-                })?;
-                let info = naga::valid::Validator::new(
-                    naga::valid::ValidationFlags::all(),
-                    Default::default(),
-                )
-                .validate(&module)?;
-
-                spv = naga::back::spv::write_vec(
-                    &module,
-                    &info,
-                    &naga::back::spv::Options::default(),
-                )?;
-
-                bytemuck::cast_slice(&spv)
-            }
             _ => {
-                return Err(CreateShaderModuleError::UnsupportedShaderLanguage {
-                    language: info.language,
-                })
+                let (code, reflected) = self.compile_cached(&info)?;
+                compiled = code;
+                reflection = reflected;
+                &*compiled
             }
         };
 
@@ -1522,6 +1743,15 @@ impl Device {
             });
         }
 
+        // Code compiled from other languages is already validated by the compiler.
+        let reflection = match reflection {
+            Some(reflection) => Some(reflection),
+            None if info.language == ShaderLanguage::SPIRV && !info.skip_validation => {
+                ShaderReflection::from_spirv(code)?
+            }
+            None => ShaderReflection::from_spirv_unchecked(code),
+        };
+
         let mut aligned_code;
 
         let is_aligned = code.as_ptr() as usize & 3 == 0;
@@ -1555,7 +1785,8 @@ impl Device {
         };
 
         let module = unsafe {
-            // FIXME: It is still required to validate SPIR-V.
+            // SPIR-V is validated above unless validation is skipped
+            // or naga doesn't support it.
             // Othewise adheres to valid usage described in spec.
             self.inner.logical.create_shader_module(
                 &vk1_0::ShaderModuleCreateInfoBuilder::new().code(code_slice),
@@ -1570,7 +1801,20 @@ impl Device {
         let index = self.inner.shaders.lock().insert(module);
 
         tracing::debug!("Shader module created: {:p}", module);
-        Ok(ShaderModule::new(info, self.downgrade(), module, index))
+        if reflection.is_none() {
+            tracing::warn!(
+                "Failed to reflect shader module {:p}, pipelines using it won't be validated",
+                module
+            );
+        }
+
+        Ok(ShaderModule::new(
+            info,
+            reflection,
+            self.downgrade(),
+            module,
+            index,
+        ))
     }
 
     pub(super) unsafe fn destroy_shader_module(&self, index: usize) {
@@ -2281,13 +2525,38 @@ impl Device {
                         assert_owner!(combo.sampler, self);
                     }
                 }
-                Descriptors::SampledImage(views)
-                | Descriptors::StorageImage(views)
-                | Descriptors::InputAttachment(views) => {
+                Descriptors::SampledImage(views) | Descriptors::InputAttachment(views) => {
                     for view in views {
                         assert_owner!(view.view, self);
                     }
                 }
+                Descriptors::StorageImage(views) => {
+                    for view in views {
+                        assert_owner!(view.view, self);
+                        assert!(
+                            view.view.info().image.info().usage.contains(ImageUsage::STORAGE),
+                            "Image must be created with `STORAGE` usage to be bound as storage image"
+                        );
+                    }
+                }
+                Descriptors::UniformTexelBuffer(views) => {
+                    for view in views {
+                        assert_owner!(view, self);
+                        assert!(
+                            view.info().buffer.info().usage.contains(BufferUsage::UNIFORM_TEXEL),
+                            "Buffer must be created with `UNIFORM_TEXEL` usage to be bound as uniform texel buffer"
+                        );
+                    }
+                }
+                Descriptors::StorageTexelBuffer(views) => {
+                    for view in views {
+                        assert_owner!(view, self);
+                        assert!(
+                            view.info().buffer.info().usage.contains(BufferUsage::STORAGE_TEXEL),
+                            "Buffer must be created with `STORAGE_TEXEL` usage to be bound as storage texel buffer"
+                        );
+                    }
+                }
                 Descriptors::UniformBuffer(regions)
                 | Descriptors::StorageBuffer(regions)
                 | Descriptors::UniformBufferDynamic(regions)
@@ -2334,7 +2603,8 @@ impl Device {
 
         let mut buffers = SmallVec::<[_; 16]>::new();
 
-        // let mut buffer_views = SmallVec::<[_; 16]
+        let mut buffer_views = SmallVec::<[_; 16]>::new();
+
         let mut acceleration_structures = SmallVec::<[_; 64]>::new();
 
         let mut write_descriptor_acceleration_structures = SmallVec::<[_; 16]>::new();
@@ -2384,6 +2654,13 @@ impl Device {
 
                     ranges.push(start..images.len());
                 }
+                Descriptors::UniformTexelBuffer(slice) | Descriptors::StorageTexelBuffer(slice) => {
+                    let start = buffer_views.len();
+
+                    buffer_views.extend(slice.iter().map(|view| view.handle()));
+
+                    ranges.push(start..buffer_views.len());
+                }
                 Descriptors::UniformBuffer(slice) => {
                     let start = buffers.len();
 
@@ -2482,8 +2759,12 @@ impl Device {
                     Descriptors::StorageImage(_) => builder
                         .descriptor_type(vk1_0::DescriptorType::STORAGE_IMAGE)
                         .image_info(&images[ranges.next().unwrap()]),
-                    // Descriptors::UniformTexelBuffer(_) => todo!(),
-                    // Descriptors::StorageTexelBuffer(_) => todo!(),
+                    Descriptors::UniformTexelBuffer(_) => builder
+                        .descriptor_type(vk1_0::DescriptorType::UNIFORM_TEXEL_BUFFER)
+                        .texel_buffer_view(&buffer_views[ranges.next().unwrap()]),
+                    Descriptors::StorageTexelBuffer(_) => builder
+                        .descriptor_type(vk1_0::DescriptorType::STORAGE_TEXEL_BUFFER)
+                        .texel_buffer_view(&buffer_views[ranges.next().unwrap()]),
                     Descriptors::UniformBuffer(_) => builder
                         .descriptor_type(vk1_0::DescriptorType::UNIFORM_BUFFER)
                         .buffer_info(&buffers[ranges.next().unwrap()]),
@@ -2566,6 +2847,131 @@ impl Device {
         self.inner.logical.destroy_sampler(Some(handle), None);
     }
 
+    #[tracing::instrument]
+    pub fn create_query_pool(&self, info: QueryPoolInfo) -> Result<QueryPool, OutOfMemory> {
+        assert_ne!(info.count, 0, "Query pool must contain at least one query");
+
+        let mut builder = vk1_0::QueryPoolCreateInfoBuilder::new()
+            .query_type(info.ty.to_erupt())
+            .query_count(info.count);
+
+        if let QueryType::PipelineStatistics(flags) = info.ty {
+            assert_ne!(
+                self.inner.features.v10.pipeline_statistics_query, 0,
+                "`PipelineStatisticsQuery` feature must be enabled to create pipeline statistics query pool"
+            );
+            assert!(
+                !flags.is_empty(),
+                "At least one pipeline statistic must be queried"
+            );
+
+            builder = builder.pipeline_statistics(flags.to_erupt());
+        }
+
+        let handle = unsafe { self.inner.logical.create_query_pool(&builder, None) }
+            .result()
+            .map_err(oom_error_from_erupt)?;
+
+        let index = self.inner.query_pools.lock().insert(handle);
+
+        tracing::debug!("QueryPool created {:p}", handle);
+        Ok(QueryPool::new(info, self.downgrade(), handle, index))
+    }
+
+    pub(super) unsafe fn destroy_query_pool(&self, index: usize) {
+        let handle = self.inner.query_pools.lock().remove(index);
+        self.inner.logical.destroy_query_pool(Some(handle), None);
+    }
+
+    /// Returns number of nanoseconds required for timestamp query value to be incremented by 1.
+    pub fn timestamp_period(&self) -> f32 {
+        self.inner.properties.v10.limits.timestamp_period
+    }
+
+    /// Fetches results of queries in range into host memory.
+    ///
+    /// Each query yields `QueryType::values_per_query` values,
+    /// followed by availability value if `QueryResultFlags::WITH_AVAILABILITY` is set.
+    /// Values of available timestamp queries are masked to valid timestamp bits
+    /// of queue families that wrote them and converted from ticks to nanoseconds
+    /// using device's timestamp period.
+    ///
+    /// Returns `None` if results are not yet available
+    /// and none of `WAIT`, `PARTIAL` and `WITH_AVAILABILITY` flags is set.
+    #[tracing::instrument]
+    pub fn get_query_results(
+        &self,
+        pool: &QueryPool,
+        queries: Range<u32>,
+        flags: QueryResultFlags,
+    ) -> Result<Option<Vec<u64>>, OutOfMemory> {
+        assert_owner!(pool, self);
+        assert!(
+            queries.start <= queries.end && queries.end <= pool.info().count,
+            "Query range is out of pool bounds"
+        );
+
+        let count = queries.end - queries.start;
+        if count == 0 {
+            return Ok(Some(Vec::new()));
+        }
+
+        let values = pool.info().ty.values_per_query() as usize;
+        let stride = values + flags.contains(QueryResultFlags::WITH_AVAILABILITY) as usize;
+        let mut data = vec![0u64; stride * count as usize];
+
+        let result = unsafe {
+            self.inner.logical.get_query_pool_results(
+                pool.handle(),
+                queries.start,
+                count,
+                size_of_val(&data[..]),
+                data.as_mut_ptr() as _,
+                (stride * size_of::<u64>()) as u64,
+                Some(flags.to_erupt()),
+            )
+        }
+        .raw;
+
+        let with_availability = flags.contains(QueryResultFlags::WITH_AVAILABILITY);
+
+        let ready = match result {
+            vk1_0::Result::SUCCESS => true,
+            vk1_0::Result::NOT_READY => {
+                if !flags
+                    .intersects(QueryResultFlags::PARTIAL | QueryResultFlags::WITH_AVAILABILITY)
+                {
+                    return Ok(None);
+                }
+                false
+            }
+            vk1_0::Result::ERROR_OUT_OF_HOST_MEMORY => out_of_host_memory(),
+            vk1_0::Result::ERROR_OUT_OF_DEVICE_MEMORY => return Err(OutOfMemory),
+            vk1_0::Result::ERROR_DEVICE_LOST => device_lost(),
+            result => unexpected_result(result),
+        };
+
+        if let QueryType::Timestamp = pool.info().ty {
+            let period = f64::from(self.timestamp_period());
+            let mask = pool.timestamp_mask();
+
+            for query in data.chunks_mut(stride) {
+                // Values of unavailable queries are left as is.
+                let available = if with_availability {
+                    query[values] != 0
+                } else {
+                    ready
+                };
+
+                if available {
+                    query[0] = ((query[0] & mask) as f64 * period) as u64;
+                }
+            }
+        }
+
+        Ok(Some(data))
+    }
+
     #[tracing::instrument]
     pub fn create_shader_binding_table(
         &self,
@@ -2734,6 +3140,310 @@ impl Device {
         }
         .map_err(Into::into)
     }
+
+    /// Reads content of the buffer range back into host memory.
+    ///
+    /// Copies the range into temporary host-visible buffer using `queue`
+    /// and blocks until device finishes the copy.
+    /// All device writes to the range must be submitted before this call.
+    ///
+    /// # Panics
+    ///
+    /// This method may panic if `buffer` was not created with `TRANSFER_SRC`
+    /// usage or if range is out of buffer bounds.
+    #[tracing::instrument(skip(queue))]
+    pub fn read_buffer(
+        &self,
+        queue: &mut Queue,
+        buffer: &Buffer,
+        offset: u64,
+        size: u64,
+    ) -> Result<Vec<u8>, MapError> {
+        assert_owner!(buffer, self);
+        assert!(
+            buffer.info().usage.contains(BufferUsage::TRANSFER_SRC),
+            "Buffer must be created with `TRANSFER_SRC` usage to be read back"
+        );
+        assert!(
+            matches!(offset.checked_add(size), Some(end) if end <= buffer.info().size),
+            "Range is out of buffer bounds"
+        );
+
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let staging = self.create_mappable_buffer(
+            BufferInfo {
+                align: 3,
+                size,
+                usage: BufferUsage::TRANSFER_DST,
+            },
+            MemoryUsage::DOWNLOAD,
+        )?;
+
+        let scope = Scope::new();
+        let mut encoder = queue.create_encoder(&scope)?;
+
+        encoder.memory_barrier(
+            PipelineStageFlags::ALL_COMMANDS,
+            AccessFlags::MEMORY_WRITE,
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_READ,
+        );
+
+        encoder.copy_buffer(
+            buffer,
+            &staging,
+            scope.to_scope([BufferCopy {
+                src_offset: offset,
+                dst_offset: 0,
+                size,
+            }]),
+        );
+
+        encoder.memory_barrier(
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+            PipelineStageFlags::HOST,
+            AccessFlags::HOST_READ,
+        );
+
+        let cbuf = encoder.finish();
+        self.download(queue, cbuf, staging, size)
+    }
+
+    /// Reads texels of the image region back into host memory.
+    ///
+    /// `layout` is the layout image subresource is in when commands
+    /// submitted before this call are complete.
+    /// It is never undefined since `Layout` has no such variant,
+    /// so image can always be transitioned back to it.
+    /// Image is transitioned to `TransferSrcOptimal` for the copy
+    /// and back to `layout` after.
+    ///
+    /// Texels are returned tightly packed, row by row, layer by layer.
+    /// Only one aspect of depth-stencil images can be read at a time.
+    ///
+    /// # Panics
+    ///
+    /// This method may panic if `image` was not created with `TRANSFER_SRC`
+    /// usage, if `subresource` specifies more than one aspect
+    /// or if region specified by `subresource`, `offset` and `extent`
+    /// is out of image bounds.
+    #[tracing::instrument(skip(queue))]
+    pub fn read_image(
+        &self,
+        queue: &mut Queue,
+        image: &Image,
+        layout: Layout,
+        subresource: SubresourceLayers,
+        offset: Offset3d,
+        extent: Extent3d,
+    ) -> Result<Vec<u8>, MapError> {
+        assert_owner!(image, self);
+        assert!(
+            image.info().usage.contains(ImageUsage::TRANSFER_SRC),
+            "Image must be created with `TRANSFER_SRC` usage to be read back"
+        );
+
+        let info = image.info();
+        assert!(
+            subresource.level < info.levels,
+            "Mip level {} is out of bounds, image has {} levels",
+            subresource.level,
+            info.levels,
+        );
+        assert!(
+            matches!(
+                subresource.first_layer.checked_add(subresource.layer_count),
+                Some(end) if end <= info.layers
+            ),
+            "Layers {}..+{} are out of bounds, image has {} layers",
+            subresource.first_layer,
+            subresource.layer_count,
+            info.layers,
+        );
+
+        let level_extent = info.extent.into_3d();
+        let fits = |offset: i32, extent: u32, size: u32| {
+            let size = (size >> subresource.level).max(1);
+            matches!(
+                u32::try_from(offset).ok().and_then(|offset| offset.checked_add(extent)),
+                Some(end) if end <= size
+            )
+        };
+        assert!(
+            fits(offset.x, extent.width, level_extent.width)
+                && fits(offset.y, extent.height, level_extent.height)
+                && fits(offset.z, extent.depth, level_extent.depth),
+            "Region {:?}..+{:?} is out of bounds of mip level {}",
+            offset,
+            extent,
+            subresource.level,
+        );
+
+        let texel_size = aspect_texel_size(image.info().format, subresource.aspect);
+
+        let size = u64::from(texel_size)
+            .checked_mul(u64::from(extent.width))
+            .and_then(|size| size.checked_mul(u64::from(extent.height)))
+            .and_then(|size| size.checked_mul(u64::from(extent.depth)))
+            .and_then(|size| size.checked_mul(u64::from(subresource.layer_count)))
+            .unwrap_or_else(|| host_memory_space_overlow());
+
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let staging = self.create_mappable_buffer(
+            BufferInfo {
+                align: 3,
+                size,
+                usage: BufferUsage::TRANSFER_DST,
+            },
+            MemoryUsage::DOWNLOAD,
+        )?;
+
+        let scope = Scope::new();
+        let mut encoder = queue.create_encoder(&scope)?;
+
+        let range = SubresourceRange {
+            aspect: subresource.aspect,
+            first_level: subresource.level,
+            level_count: 1,
+            first_layer: subresource.first_layer,
+            layer_count: subresource.layer_count,
+        };
+
+        let src_layout = match layout {
+            Layout::General | Layout::TransferSrcOptimal => layout,
+            _ => Layout::TransferSrcOptimal,
+        };
+
+        encoder.image_barriers(
+            PipelineStageFlags::ALL_COMMANDS,
+            PipelineStageFlags::TRANSFER,
+            scope.to_scope([ImageMemoryBarrier {
+                image,
+                old_access: AccessFlags::MEMORY_WRITE,
+                old_layout: Some(layout),
+                new_access: AccessFlags::TRANSFER_READ,
+                new_layout: src_layout,
+                family_transfer: None,
+                range,
+            }]),
+        );
+
+        encoder.copy_image_to_buffer(
+            image,
+            src_layout,
+            &staging,
+            scope.to_scope([BufferImageCopy {
+                buffer_offset: 0,
+                buffer_row_length: 0,
+                buffer_image_height: 0,
+                image_subresource: subresource,
+                image_offset: offset,
+                image_extent: extent,
+            }]),
+        );
+
+        if src_layout != layout {
+            encoder.image_barriers(
+                PipelineStageFlags::TRANSFER,
+                PipelineStageFlags::ALL_COMMANDS,
+                scope.to_scope([ImageMemoryBarrier {
+                    image,
+                    old_access: AccessFlags::empty(),
+                    old_layout: Some(src_layout),
+                    new_access: AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+                    new_layout: layout,
+                    family_transfer: None,
+                    range,
+                }]),
+            );
+        }
+
+        encoder.memory_barrier(
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+            PipelineStageFlags::HOST,
+            AccessFlags::HOST_READ,
+        );
+
+        let cbuf = encoder.finish();
+        self.download(queue, cbuf, staging, size)
+    }
+
+    /// Submits command buffer that fills `staging`, waits for it
+    /// and copies content of `staging` into `Vec`.
+    fn download(
+        &self,
+        queue: &mut Queue,
+        cbuf: CommandBuffer,
+        mut staging: MappableBuffer,
+        size: u64,
+    ) -> Result<Vec<u8>, MapError> {
+        let size = usize::try_from(size).unwrap_or_else(|_| host_memory_space_overlow());
+
+        let mut fence = self.create_fence()?;
+        queue.submit(
+            &mut [],
+            Some(cbuf),
+            &mut [],
+            Some(&mut fence),
+            &Scope::new(),
+        );
+        self.wait_fences(&mut [&mut fence], true);
+
+        let mut data = vec![0; size];
+
+        unsafe {
+            staging.memory_block().read_bytes(
+                EruptMemoryDevice::wrap(&self.inner.logical),
+                0,
+                &mut data,
+            )
+        }?;
+
+        Ok(data)
+    }
+}
+
+/// Returns size of one texel of the format's `aspect`
+/// as it is laid out in buffer for copy commands.
+fn aspect_texel_size(format: Format, aspect: AspectFlags) -> u32 {
+    assert_eq!(
+        aspect.bits().count_ones(),
+        1,
+        "Exactly one aspect must be copied at a time"
+    );
+    assert!(
+        format.aspect_flags().contains(aspect),
+        "Format {:?} does not have aspect {:?}",
+        format,
+        aspect
+    );
+
+    match format.description() {
+        FormatDescription::R(repr) => u32::from(repr.bits) / 8,
+        FormatDescription::RG(repr) => 2 * u32::from(repr.bits) / 8,
+        FormatDescription::RGB(repr) | FormatDescription::BGR(repr) => 3 * u32::from(repr.bits) / 8,
+        FormatDescription::RGBA(repr) | FormatDescription::BGRA(repr) => {
+            4 * u32::from(repr.bits) / 8
+        }
+        FormatDescription::Stencil(_) => 1,
+        FormatDescription::DepthStencil { .. } if aspect == AspectFlags::STENCIL => 1,
+        // 24 bit depth is copied as 32 bit value.
+        FormatDescription::Depth(depth) | FormatDescription::DepthStencil { depth, .. } => {
+            if depth.bits > 16 {
+                4
+            } else {
+                2
+            }
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -2835,10 +3545,51 @@ pub(super) fn descriptor_count_from_bindings(
             DescriptorType::StorageBuffer => result.storage_buffer += binding.count,
             DescriptorType::StorageBufferDynamic => result.storage_buffer_dynamic += binding.count,
             DescriptorType::StorageImage => result.storage_image += binding.count,
+            DescriptorType::StorageTexelBuffer => result.storage_texel_buffer += binding.count,
             DescriptorType::UniformBuffer => result.uniform_buffer += binding.count,
             DescriptorType::UniformBufferDynamic => result.uniform_buffer_dynamic += binding.count,
+            DescriptorType::UniformTexelBuffer => result.uniform_texel_buffer += binding.count,
         }
     }
 
     result
 }
+
+/// Returns reflection of the shader entry point.
+/// Returns `None` and logs warning if shader module could not be reflected.
+fn reflect_entry_point<'a>(
+    module: &'a ShaderModule,
+    entry: &str,
+    stage: ShaderStage,
+) -> Result<Option<&'a EntryPointReflection>, ShaderInterfaceMismatch> {
+    match module.reflection() {
+        Some(reflection) => match reflection.entry_point(entry, stage) {
+            Some(entry_point) => Ok(Some(entry_point)),
+            None => Err(ShaderInterfaceMismatch::MissingEntryPoint {
+                entry: entry.to_owned(),
+                stage,
+            }),
+        },
+        None => {
+            tracing::warn!(
+                "Shader module {:p} has no reflection, `{}` entry point won't be validated",
+                module.handle(),
+                entry
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Writes compiled shader into cache file.
+/// File is written under temporary name and renamed
+/// so that concurrent readers never observe partial content.
+fn write_shader_cache(path: &std::path::Path, code: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, code)?;
+    std::fs::rename(&tmp, path)
+}
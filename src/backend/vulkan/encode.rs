@@ -4,24 +4,32 @@ use {
         convert::{oom_error_from_erupt, ToErupt},
         device::WeakDevice,
         epochs::References,
+        pool::CommandPoolInner,
+        resources::Framebuffer,
     },
     crate::{
         accel::{AccelerationStructureGeometry, AccelerationStructureLevel, IndexData},
         buffer::{BufferRange, BufferUsage, StridedBufferRange},
         encode::*,
-        format::{FormatDescription, FormatRepr, FormatType},
+        format::{Format, FormatDescription, FormatRepr, FormatType},
         queue::QueueId,
-        render_pass::{ClearValue, LoadOp},
+        render_pass::{
+            ClearColor, ClearDepth, ClearDepthStencil, ClearStencil, ClearValue, LoadOp,
+        },
         IndexType, OutOfMemory,
     },
     erupt::{
-        extensions::{khr_acceleration_structure as vkacc, khr_ray_tracing_pipeline as vkrt},
+        extensions::{
+            ext_debug_utils as vkdu, khr_acceleration_structure as vkacc,
+            khr_ray_tracing_pipeline as vkrt,
+        },
         vk1_0,
     },
     scoped_arena::Scope,
     std::{
         convert::TryFrom as _,
         fmt::{self, Debug},
+        sync::Arc,
     },
 };
 
@@ -36,6 +44,12 @@ pub struct CommandBuffer {
     queue: QueueId,
     owner: WeakDevice,
     references: References,
+
+    // Pool to return command buffer into. `None` for queue's own pool.
+    pool: Option<Arc<CommandPoolInner>>,
+
+    // Framebuffer and subpass inherited by secondary command buffer.
+    inheritance: Option<(Framebuffer, u32)>,
 }
 
 impl Debug for CommandBuffer {
@@ -52,10 +66,14 @@ impl Debug for CommandBuffer {
     }
 }
 
-#[cfg(feature = "leak-detection")]
 impl Drop for CommandBuffer {
     fn drop(&mut self) {
+        #[cfg(feature = "leak-detection")]
         COMMAND_BUFFER_ALLOCATED.fetch_sub(1, Relaxed);
+
+        if let Some(pool) = &self.pool {
+            pool.release(self.handle, self.inheritance.is_some());
+        }
     }
 }
 
@@ -74,9 +92,34 @@ impl CommandBuffer {
             queue,
             owner,
             references: References::new(),
+            pool: None,
+            inheritance: None,
         }
     }
 
+    pub(super) fn from_pool(
+        handle: vk1_0::CommandBuffer,
+        queue: QueueId,
+        owner: WeakDevice,
+        pool: Arc<CommandPoolInner>,
+        inheritance: Option<(Framebuffer, u32)>,
+    ) -> Self {
+        let mut cbuf = CommandBuffer::new(handle, queue, owner);
+        cbuf.pool = Some(pool);
+        cbuf.inheritance = inheritance;
+        cbuf
+    }
+
+    /// Returns `true` if command buffer is returned to `CommandPool` rather than queue.
+    pub(super) fn is_pooled(&self) -> bool {
+        self.pool.is_some()
+    }
+
+    /// Returns `true` if this is secondary command buffer.
+    pub fn is_secondary(&self) -> bool {
+        self.inheritance.is_some()
+    }
+
     pub(super) fn handle(&self) -> vk1_0::CommandBuffer {
         self.handle
     }
@@ -103,28 +146,63 @@ impl CommandBuffer {
             None => return Ok(()),
         };
 
+        let pool = self.pool.clone();
+        let _guard = pool.as_ref().map(|pool| pool.lock());
+
+        let inheritance = self.inheritance.clone();
+        let inheritance_info = inheritance.as_ref().map(|(framebuffer, subpass)| {
+            vk1_0::CommandBufferInheritanceInfoBuilder::new()
+                .render_pass(framebuffer.info().render_pass.handle())
+                .subpass(*subpass)
+                .framebuffer(framebuffer.handle())
+        });
+
+        let begin_info = match &inheritance_info {
+            Some(inheritance_info) => vk1_0::CommandBufferBeginInfoBuilder::new()
+                .flags(
+                    vk1_0::CommandBufferUsageFlags::ONE_TIME_SUBMIT
+                        | vk1_0::CommandBufferUsageFlags::RENDER_PASS_CONTINUE,
+                )
+                .inheritance_info(inheritance_info),
+            None => vk1_0::CommandBufferBeginInfoBuilder::new()
+                .flags(vk1_0::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+        };
+
         unsafe {
-            device.logical().begin_command_buffer(
-                self.handle,
-                &vk1_0::CommandBufferBeginInfoBuilder::new()
-                    .flags(vk1_0::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
-            )
+            device
+                .logical()
+                .begin_command_buffer(self.handle, &begin_info)
         }
         .result()
         .map_err(oom_error_from_erupt)?;
 
         let logical = &device.logical();
 
+        // Debug labels are silently dropped without `VK_EXT_debug_utils`.
+        let debug_utils = device.graphics().instance.enabled().ext_debug_utils;
+
+        // Render pass and subpass index commands are currently recorded in.
+        // Secondary command buffer is recorded entirely inside inherited subpass.
+        let mut render_pass = inheritance
+            .as_ref()
+            .map(|(framebuffer, _)| &framebuffer.info().render_pass);
+        let mut subpass = inheritance
+            .as_ref()
+            .map_or(0, |(_, subpass)| *subpass as usize);
+
         for command in commands {
             match command {
                 Command::BeginRenderPass {
                     framebuffer,
                     clears,
+                    secondary,
                 } => {
                     assert_owner!(framebuffer, device);
                     self.references.add_framebuffer(framebuffer.clone());
 
                     let pass = &framebuffer.info().render_pass;
+                    render_pass = Some(pass);
+                    subpass = 0;
 
                     let mut clears = clears.into_iter();
                     let clear_values = scope.to_scope_from_iter(
@@ -180,11 +258,18 @@ impl CommandBuffer {
                                     extent: framebuffer.info().extent.to_erupt(),
                                 })
                                 .clear_values(clear_values),
-                            vk1_0::SubpassContents::INLINE,
+                            subpass_contents(secondary),
                         )
                     }
                 }
-                Command::EndRenderPass => unsafe { logical.cmd_end_render_pass(self.handle) },
+                Command::NextSubpass { secondary } => unsafe {
+                    subpass += 1;
+                    logical.cmd_next_subpass(self.handle, subpass_contents(secondary))
+                },
+                Command::EndRenderPass => unsafe {
+                    render_pass = None;
+                    logical.cmd_end_render_pass(self.handle)
+                },
                 Command::BindGraphicsPipeline { pipeline } => unsafe {
                     assert_owner!(pipeline, device);
                     self.references.add_graphics_pipeline(pipeline.clone());
@@ -231,6 +316,132 @@ impl CommandBuffer {
                         instances.start,
                     )
                 },
+                Command::DrawIndirect {
+                    buffer,
+                    offset,
+                    draw_count,
+                    stride,
+                } => unsafe {
+                    assert_owner!(buffer, device);
+                    self.references.add_buffer(buffer.clone());
+
+                    assert!(
+                        draw_count <= 1 || device.features().v10.multi_draw_indirect != 0,
+                        "`MultiDrawIndirect` feature is not enabled"
+                    );
+
+                    logical.cmd_draw_indirect(
+                        self.handle,
+                        buffer.handle(),
+                        offset,
+                        draw_count,
+                        stride,
+                    )
+                },
+                Command::DrawIndexedIndirect {
+                    buffer,
+                    offset,
+                    draw_count,
+                    stride,
+                } => unsafe {
+                    assert_owner!(buffer, device);
+                    self.references.add_buffer(buffer.clone());
+
+                    assert!(
+                        draw_count <= 1 || device.features().v10.multi_draw_indirect != 0,
+                        "`MultiDrawIndirect` feature is not enabled"
+                    );
+
+                    logical.cmd_draw_indexed_indirect(
+                        self.handle,
+                        buffer.handle(),
+                        offset,
+                        draw_count,
+                        stride,
+                    )
+                },
+                Command::DrawIndirectCount {
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                } => unsafe {
+                    assert_owner!(buffer, device);
+                    assert_owner!(count_buffer, device);
+                    self.references.add_buffer(buffer.clone());
+                    self.references.add_buffer(count_buffer.clone());
+
+                    if logical.enabled().khr_draw_indirect_count {
+                        logical.cmd_draw_indirect_count_khr(
+                            self.handle,
+                            buffer.handle(),
+                            offset,
+                            count_buffer.handle(),
+                            count_buffer_offset,
+                            max_draw_count,
+                            stride,
+                        )
+                    } else {
+                        assert_ne!(
+                            device.features().v12.draw_indirect_count,
+                            0,
+                            "`DrawIndirectCount` feature is not enabled"
+                        );
+
+                        logical.cmd_draw_indirect_count(
+                            self.handle,
+                            buffer.handle(),
+                            offset,
+                            count_buffer.handle(),
+                            count_buffer_offset,
+                            max_draw_count,
+                            stride,
+                        )
+                    }
+                },
+                Command::DrawIndexedIndirectCount {
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                } => unsafe {
+                    assert_owner!(buffer, device);
+                    assert_owner!(count_buffer, device);
+                    self.references.add_buffer(buffer.clone());
+                    self.references.add_buffer(count_buffer.clone());
+
+                    if logical.enabled().khr_draw_indirect_count {
+                        logical.cmd_draw_indexed_indirect_count_khr(
+                            self.handle,
+                            buffer.handle(),
+                            offset,
+                            count_buffer.handle(),
+                            count_buffer_offset,
+                            max_draw_count,
+                            stride,
+                        )
+                    } else {
+                        assert_ne!(
+                            device.features().v12.draw_indirect_count,
+                            0,
+                            "`DrawIndirectCount` feature is not enabled"
+                        );
+
+                        logical.cmd_draw_indexed_indirect_count(
+                            self.handle,
+                            buffer.handle(),
+                            offset,
+                            count_buffer.handle(),
+                            count_buffer_offset,
+                            max_draw_count,
+                            stride,
+                        )
+                    }
+                },
                 Command::SetViewport { viewport } => unsafe {
                     // FIXME: Check that bound pipeline has dynamic viewport
                     // state.
@@ -259,6 +470,118 @@ impl CommandBuffer {
                         data.as_ptr() as _,
                     );
                 },
+                Command::FillBuffer {
+                    buffer,
+                    offset,
+                    size,
+                    data,
+                } => unsafe {
+                    assert_owner!(buffer, device);
+                    self.references.add_buffer(buffer.clone());
+
+                    logical.cmd_fill_buffer(self.handle, buffer.handle(), offset, size, data);
+                },
+                Command::ClearColorImage {
+                    image,
+                    layout,
+                    color: ClearColor(r, g, b, a),
+                    ranges,
+                } => unsafe {
+                    assert_owner!(image, device);
+                    self.references.add_image(image.clone());
+
+                    logical.cmd_clear_color_image(
+                        self.handle,
+                        image.handle(),
+                        layout.to_erupt(),
+                        &format_clear_color(image.info().format, r, g, b, a),
+                        scope.to_scope_from_iter(
+                            ranges.iter().map(|range| range.to_erupt().into_builder()),
+                        ),
+                    );
+                },
+                Command::ClearDepthStencilImage {
+                    image,
+                    layout,
+                    depth_stencil: ClearDepthStencil(depth, stencil),
+                    ranges,
+                } => unsafe {
+                    assert_owner!(image, device);
+                    self.references.add_image(image.clone());
+
+                    logical.cmd_clear_depth_stencil_image(
+                        self.handle,
+                        image.handle(),
+                        layout.to_erupt(),
+                        &vk1_0::ClearDepthStencilValue { depth, stencil },
+                        scope.to_scope_from_iter(
+                            ranges.iter().map(|range| range.to_erupt().into_builder()),
+                        ),
+                    );
+                },
+                Command::ClearAttachments { attachments, rects } => unsafe {
+                    let pass = render_pass
+                        .expect("`ClearAttachments` must be recorded inside render pass");
+                    let info = pass.info();
+                    let subpass = &info.subpasses[subpass];
+
+                    let attachments =
+                        scope.to_scope_from_iter(attachments.iter().map(|attachment| {
+                            let depth_stencil = |aspect_mask, depth, stencil| {
+                                assert!(
+                                    subpass.depth.is_some(),
+                                    "Subpass has no depth-stencil attachment"
+                                );
+
+                                vk1_0::ClearAttachmentBuilder::new()
+                                    .aspect_mask(aspect_mask)
+                                    .clear_value(vk1_0::ClearValue {
+                                        depth_stencil: vk1_0::ClearDepthStencilValue {
+                                            depth,
+                                            stencil,
+                                        },
+                                    })
+                            };
+
+                            match *attachment {
+                                ClearAttachment::Color(index, ClearColor(r, g, b, a)) => {
+                                    let (attachment, _) = subpass.colors[index as usize];
+                                    let format = info.attachments[attachment as usize].format;
+
+                                    vk1_0::ClearAttachmentBuilder::new()
+                                        .aspect_mask(vk1_0::ImageAspectFlags::COLOR)
+                                        .color_attachment(index)
+                                        .clear_value(vk1_0::ClearValue {
+                                            color: format_clear_color(format, r, g, b, a),
+                                        })
+                                }
+                                ClearAttachment::Depth(ClearDepth(depth)) => {
+                                    depth_stencil(vk1_0::ImageAspectFlags::DEPTH, depth, 0)
+                                }
+                                ClearAttachment::Stencil(ClearStencil(stencil)) => {
+                                    depth_stencil(vk1_0::ImageAspectFlags::STENCIL, 0.0, stencil)
+                                }
+                                ClearAttachment::DepthStencil(ClearDepthStencil(
+                                    depth,
+                                    stencil,
+                                )) => depth_stencil(
+                                    vk1_0::ImageAspectFlags::DEPTH
+                                        | vk1_0::ImageAspectFlags::STENCIL,
+                                    depth,
+                                    stencil,
+                                ),
+                            }
+                        }));
+
+                    let rects = scope.to_scope_from_iter(rects.iter().map(|rect| {
+                        vk1_0::ClearRectBuilder::new()
+                            .rect(rect.rect.to_erupt())
+                            .base_array_layer(rect.first_layer)
+                            .layer_count(rect.layer_count)
+                    }));
+
+                    logical.cmd_clear_attachments(self.handle, attachments, rects);
+                },
                 Command::BindVertexBuffers { first, buffers } => unsafe {
                     for &(buffer, _) in buffers {
                         assert_owner!(buffer, device);
@@ -608,6 +931,33 @@ impl CommandBuffer {
                     );
                 },
 
+                Command::ResolveImage {
+                    src_image,
+                    src_layout,
+                    dst_image,
+                    dst_layout,
+                    regions,
+                } => unsafe {
+                    assert_owner!(src_image, device);
+                    assert_owner!(dst_image, device);
+
+                    self.references.add_image(src_image.clone());
+                    self.references.add_image(dst_image.clone());
+
+                    logical.cmd_resolve_image(
+                        self.handle,
+                        src_image.handle(),
+                        src_layout.to_erupt(),
+                        dst_image.handle(),
+                        dst_layout.to_erupt(),
+                        scope.to_scope_from_iter(
+                            regions
+                                .iter()
+                                .map(|region| region.to_erupt().into_builder()),
+                        ),
+                    );
+                },
+
                 Command::CopyBuffer {
                     src_buffer,
                     dst_buffer,
@@ -655,6 +1005,31 @@ impl CommandBuffer {
                     );
                 },
 
+                Command::CopyImageBuffer {
+                    src_image,
+                    src_layout,
+                    dst_buffer,
+                    regions,
+                } => unsafe {
+                    assert_owner!(src_image, device);
+                    assert_owner!(dst_buffer, device);
+
+                    self.references.add_image(src_image.clone());
+                    self.references.add_buffer(dst_buffer.clone());
+
+                    logical.cmd_copy_image_to_buffer(
+                        self.handle,
+                        src_image.handle(),
+                        src_layout.to_erupt(),
+                        dst_buffer.handle(),
+                        scope.to_scope_from_iter(
+                            regions
+                                .iter()
+                                .map(|region| region.to_erupt().into_builder()),
+                        ),
+                    );
+                },
+
                 Command::BlitImage {
                     src_image,
                     src_layout,
@@ -784,6 +1159,129 @@ impl CommandBuffer {
                 Command::Dispatch { x, y, z } => unsafe {
                     logical.cmd_dispatch(self.handle, x, y, z)
                 },
+                Command::DispatchIndirect { buffer, offset } => unsafe {
+                    assert_owner!(buffer, device);
+                    self.references.add_buffer(buffer.clone());
+
+                    logical.cmd_dispatch_indirect(self.handle, buffer.handle(), offset)
+                },
+                Command::ResetQueryPool { pool, queries } => unsafe {
+                    assert_owner!(pool, device);
+                    self.references.add_query_pool(pool.clone());
+
+                    logical.cmd_reset_query_pool(
+                        self.handle,
+                        pool.handle(),
+                        queries.start,
+                        queries.end - queries.start,
+                    )
+                },
+                Command::BeginQuery {
+                    pool,
+                    query,
+                    precise,
+                } => unsafe {
+                    assert_owner!(pool, device);
+                    self.references.add_query_pool(pool.clone());
+
+                    let flags = if precise {
+                        vk1_0::QueryControlFlags::PRECISE
+                    } else {
+                        vk1_0::QueryControlFlags::empty()
+                    };
+
+                    logical.cmd_begin_query(self.handle, pool.handle(), query, Some(flags))
+                },
+                Command::EndQuery { pool, query } => unsafe {
+                    assert_owner!(pool, device);
+                    self.references.add_query_pool(pool.clone());
+
+                    logical.cmd_end_query(self.handle, pool.handle(), query)
+                },
+                Command::WriteTimestamp { pool, query, stage } => unsafe {
+                    assert_owner!(pool, device);
+                    self.references.add_query_pool(pool.clone());
+
+                    let valid_bits =
+                        device.properties().family[self.queue.family as usize].timestamp_valid_bits;
+                    assert_ne!(valid_bits, 0, "Queue family doesn't support timestamps");
+                    pool.add_timestamp_valid_bits(valid_bits);
+
+                    logical.cmd_write_timestamp(
+                        self.handle,
+                        vk1_0::PipelineStageFlagBits(stage.to_erupt().bits()),
+                        pool.handle(),
+                        query,
+                    )
+                },
+                Command::ExecuteCommands { cbufs } => unsafe {
+                    let handles = scope.to_scope_from_iter(cbufs.iter().map(|cbuf| {
+                        let cbuf = cbuf.as_ref().expect("Command buffer is already executed");
+                        assert_owner!(cbuf, device);
+                        assert_eq!(self.queue, cbuf.queue());
+                        assert!(
+                            cbuf.is_secondary(),
+                            "Only secondary command buffers can be executed"
+                        );
+                        cbuf.handle()
+                    }));
+
+                    logical.cmd_execute_commands(self.handle, handles);
+
+                    // Secondary command buffers are kept alive until this one finishes execution.
+                    for cbuf in cbufs.iter_mut() {
+                        self.references.add_command_buffer(cbuf.take().unwrap());
+                    }
+                },
+                Command::PushDebugGroup { name, color } => unsafe {
+                    if debug_utils {
+                        logical.cmd_begin_debug_utils_label_ext(
+                            self.handle,
+                            &vkdu::DebugUtilsLabelEXTBuilder::new()
+                                .label_name(name)
+                                .color(color),
+                        )
+                    }
+                },
+                Command::PopDebugGroup => unsafe {
+                    if debug_utils {
+                        logical.cmd_end_debug_utils_label_ext(self.handle)
+                    }
+                },
+                Command::InsertDebugLabel { name, color } => unsafe {
+                    if debug_utils {
+                        logical.cmd_insert_debug_utils_label_ext(
+                            self.handle,
+                            &vkdu::DebugUtilsLabelEXTBuilder::new()
+                                .label_name(name)
+                                .color(color),
+                        )
+                    }
+                },
+                Command::CopyQueryPoolResults {
+                    pool,
+                    queries,
+                    buffer,
+                    offset,
+                    stride,
+                    flags,
+                } => unsafe {
+                    assert_owner!(pool, device);
+                    assert_owner!(buffer, device);
+                    self.references.add_query_pool(pool.clone());
+                    self.references.add_buffer(buffer.clone());
+
+                    logical.cmd_copy_query_pool_results(
+                        self.handle,
+                        pool.handle(),
+                        queries.start,
+                        queries.end - queries.start,
+                        buffer.handle(),
+                        offset,
+                        stride,
+                        Some(flags.to_erupt()),
+                    )
+                },
             }
         }
 
@@ -795,6 +1293,14 @@ impl CommandBuffer {
     }
 }
 
+fn subpass_contents(secondary: bool) -> vk1_0::SubpassContents {
+    if secondary {
+        vk1_0::SubpassContents::SECONDARY_COMMAND_BUFFERS
+    } else {
+        vk1_0::SubpassContents::INLINE
+    }
+}
+
 fn color_f32_to_uint64(color: f32) -> u64 {
     color.min(0f32).max(u64::max_value() as f32) as u64
 }
@@ -931,6 +1437,17 @@ fn colors_f32_to_value(r: f32, g: f32, b: f32, a: f32, repr: FormatRepr) -> vk1_
     }
 }
 
+fn format_clear_color(format: Format, r: f32, g: f32, b: f32, a: f32) -> vk1_0::ClearColorValue {
+    use FormatDescription::*;
+
+    match format.description() {
+        R(repr) | RG(repr) | RGB(repr) | RGBA(repr) | BGR(repr) | BGRA(repr) => {
+            colors_f32_to_value(r, g, b, a, repr)
+        }
+        _ => panic!("Attempt to clear depth-stencil image with color value"),
+    }
+}
+
 fn buffer_range_to_device_address(
     range: &BufferRange,
     references: &mut References,
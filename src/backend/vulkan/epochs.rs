@@ -3,7 +3,8 @@ use {
         encode::CommandBuffer,
         resources::{
             AccelerationStructure, Buffer, ComputePipeline, DescriptorSet, Fence, Framebuffer,
-            GraphicsPipeline, Image, ImageView, PipelineLayout, RayTracingPipeline, Sampler,
+            GraphicsPipeline, Image, ImageView, PipelineLayout, QueryPool, RayTracingPipeline,
+            Sampler,
         },
     },
     crate::queue::QueueId,
@@ -49,6 +50,7 @@ impl Epochs {
     pub fn close_epoch(&self, queue: QueueId, epoch: u64) {
         let mut queue = self.queues[&queue].lock();
         debug_assert!(queue.current > epoch);
+        queue.closed = queue.closed.max(epoch + 1);
         if let Ok(len) = usize::try_from(queue.current - epoch) {
             if len < queue.epochs.len() {
                 let epochs = queue.epochs.drain(len..).collect::<SmallVec<[_; 16]>>();
@@ -56,7 +58,11 @@ impl Epochs {
                 for mut epoch in epochs {
                     for mut cbuf in epoch.cbufs.drain(..) {
                         cbuf.references().clear();
-                        queue.cbufs.push(cbuf);
+
+                        // Pooled command buffers return to their pool on drop.
+                        if !cbuf.is_pooled() {
+                            queue.cbufs.push(cbuf);
+                        }
                     }
                     queue.cache.push_back(epoch);
                 }
@@ -85,6 +91,24 @@ impl Epochs {
         result
     }
 
+    /// Returns current epoch of each queue.
+    pub fn current_all_queues(&self) -> Vec<(QueueId, u64)> {
+        self.queues
+            .iter()
+            .map(|(id, queue)| (*id, queue.lock().current))
+            .collect()
+    }
+
+    /// Checks that specified epochs and all epochs before them are closed.
+    ///
+    /// Epoch returned by `current_all_queues` still collects submitted work,
+    /// so it must be closed as well.
+    pub fn are_closed(&self, epochs: &[(QueueId, u64)]) -> bool {
+        epochs
+            .iter()
+            .all(|(queue, epoch)| self.queues[queue].lock().closed > *epoch)
+    }
+
     pub fn drain_cbuf(&self, queue: QueueId, cbufs: &mut Vec<CommandBuffer>) {
         debug_assert!(cbufs.is_empty());
         let mut queue = self.queues[&queue].lock();
@@ -103,6 +127,9 @@ impl Epochs {
 
 struct QueueEpochs {
     current: u64,
+
+    // All epochs before this one are closed.
+    closed: u64,
     cbufs: Vec<CommandBuffer>,
     cache: VecDeque<Epoch>,
     epochs: VecDeque<Epoch>,
@@ -146,6 +173,8 @@ pub(super) struct References {
     samplers: Vec<Sampler>,
     descriptor_sets: Vec<DescriptorSet>,
     fences: Vec<Fence>,
+    query_pools: Vec<QueryPool>,
+    command_buffers: Vec<CommandBuffer>,
 }
 
 impl References {
@@ -163,6 +192,8 @@ impl References {
             samplers: Vec::new(),
             descriptor_sets: Vec::new(),
             fences: Vec::new(),
+            query_pools: Vec::new(),
+            command_buffers: Vec::new(),
         }
     }
 
@@ -214,6 +245,14 @@ impl References {
     //     self.fences.push(fence);
     // }
 
+    pub fn add_query_pool(&mut self, query_pool: QueryPool) {
+        self.query_pools.push(query_pool);
+    }
+
+    pub fn add_command_buffer(&mut self, command_buffer: CommandBuffer) {
+        self.command_buffers.push(command_buffer);
+    }
+
     pub fn is_empty(&self) -> bool {
         self.buffers.is_empty()
             && self.images.is_empty()
@@ -227,6 +266,8 @@ impl References {
             && self.samplers.is_empty()
             && self.descriptor_sets.is_empty()
             && self.fences.is_empty()
+            && self.query_pools.is_empty()
+            && self.command_buffers.is_empty()
     }
 
     pub fn clear(&mut self) {
@@ -242,6 +283,8 @@ impl References {
         self.samplers.clear();
         self.descriptor_sets.clear();
         self.fences.clear();
+        self.query_pools.clear();
+        self.command_buffers.clear();
     }
 }
 
@@ -249,9 +292,57 @@ impl QueueEpochs {
     fn new() -> Self {
         QueueEpochs {
             current: 0,
+            closed: 0,
             cbufs: Vec::new(),
             cache: VecDeque::new(),
             epochs: std::iter::once(Epoch::new()).collect(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::Epochs, crate::queue::QueueId};
+
+    const QUEUE: QueueId = QueueId {
+        family: 0,
+        index: 0,
+    };
+
+    #[test]
+    fn epoch_is_closed_by_close_epoch() {
+        let epochs = Epochs::new(std::iter::once(QUEUE));
+
+        // Resource removed while epoch 0 collects submitted work.
+        let removed = epochs.current_all_queues();
+        assert_eq!(removed, [(QUEUE, 0)]);
+        assert!(!epochs.are_closed(&removed));
+
+        assert_eq!(epochs.next_epoch(QUEUE), 0);
+        assert!(!epochs.are_closed(&removed));
+
+        assert_eq!(epochs.next_epoch(QUEUE), 1);
+        epochs.close_epoch(QUEUE, 0);
+        assert!(epochs.are_closed(&removed));
+        assert!(!epochs.are_closed(&epochs.current_all_queues()));
+    }
+
+    #[test]
+    fn closing_later_epoch_closes_earlier() {
+        let epochs = Epochs::new(std::iter::once(QUEUE));
+        let removed = epochs.current_all_queues();
+
+        assert_eq!(epochs.next_epoch(QUEUE), 0);
+        assert_eq!(epochs.next_epoch(QUEUE), 1);
+        let later = epochs.current_all_queues();
+        assert_eq!(epochs.next_epoch(QUEUE), 2);
+
+        epochs.close_epoch(QUEUE, 2);
+        assert!(epochs.are_closed(&removed));
+        assert!(epochs.are_closed(&later));
+
+        // Closing earlier epoch after later one doesn't reopen it.
+        epochs.close_epoch(QUEUE, 1);
+        assert!(epochs.are_closed(&later));
+    }
+}
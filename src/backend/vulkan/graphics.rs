@@ -3,6 +3,7 @@ use {
     crate::{
         out_of_host_memory,
         physical::EnumerateDeviceError,
+        shader::ShaderCompiler,
         surface::{CreateSurfaceError, RawWindowHandleKind, Surface, SurfaceInfo},
         OutOfMemory,
     },
@@ -12,7 +13,11 @@ use {
                 DebugReportCallbackCreateInfoEXTBuilder, DebugReportFlagsEXT,
                 DebugReportObjectTypeEXT, EXT_DEBUG_REPORT_EXTENSION_NAME,
             },
-            ext_debug_utils::EXT_DEBUG_UTILS_EXTENSION_NAME,
+            ext_debug_utils::{
+                DebugUtilsMessageSeverityFlagBitsEXT, DebugUtilsMessageSeverityFlagsEXT,
+                DebugUtilsMessageTypeFlagsEXT, DebugUtilsMessengerCallbackDataEXT,
+                DebugUtilsMessengerCreateInfoEXTBuilder, EXT_DEBUG_UTILS_EXTENSION_NAME,
+            },
             khr_get_physical_device_properties2::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2_EXTENSION_NAME,
             khr_surface::KHR_SURFACE_EXTENSION_NAME,
         },
@@ -23,9 +28,11 @@ use {
     raw_window_handle::{HasRawWindowHandle, RawWindowHandle},
     smallvec::SmallVec,
     std::{
-        ffi::{c_void, CStr},
+        ffi::{c_void, CStr, CString},
         fmt::{self, Debug},
         os::raw::c_char,
+        panic::{catch_unwind, AssertUnwindSafe},
+        path::PathBuf,
         sync::atomic::AtomicBool,
     },
 };
@@ -69,6 +76,12 @@ pub struct Graphics {
     pub(crate) instance: InstanceLoader,
     pub(crate) version: u32,
     _entry: EntryLoader,
+
+    // Referenced by debug messenger as user data. Never moved out of the box.
+    _callback: Box<DebugMessageCallback>,
+
+    pub(crate) shader_compiler: Option<Box<dyn ShaderCompiler>>,
+    pub(crate) shader_cache: Option<PathBuf>,
 }
 
 static GLOBAL_GRAPHICS: OnceCell<Graphics> = OnceCell::new();
@@ -86,6 +99,159 @@ impl Debug for Graphics {
     }
 }
 
+bitflags::bitflags! {
+    /// Severities of debug messages produced by validation layers and drivers.
+    pub struct DebugMessageSeverity: u32 {
+        /// Diagnostic messages from loader, layers and drivers.
+        const VERBOSE = 0x00000001;
+
+        /// Informational messages like resource details.
+        const INFO = 0x00000010;
+
+        /// Use of API that is not an error but is likely a bug.
+        const WARNING = 0x00000100;
+
+        /// Invalid use of API.
+        const ERROR = 0x00001000;
+    }
+}
+
+bitflags::bitflags! {
+    /// Kinds of debug messages produced by validation layers and drivers.
+    pub struct DebugMessageType: u32 {
+        /// Event unrelated to specification or performance.
+        const GENERAL = 0x00000001;
+
+        /// Violation of specification.
+        const VALIDATION = 0x00000002;
+
+        /// Potentially non-optimal use of API.
+        const PERFORMANCE = 0x00000004;
+    }
+}
+
+/// Debug message produced by validation layers and drivers.
+#[derive(Clone, Copy, Debug)]
+pub struct DebugMessage<'a> {
+    /// Severity of the message. Exactly one flag is set.
+    pub severity: DebugMessageSeverity,
+
+    /// Kinds of the message.
+    pub ty: DebugMessageType,
+
+    /// Name of the message identifier, e.g. validation rule VUID.
+    pub id: &'a str,
+
+    /// Message text.
+    pub message: &'a str,
+}
+
+/// Callback that receives debug messages.
+/// It may be called from any thread that uses the API.
+pub type DebugMessageCallback = Box<dyn Fn(&DebugMessage<'_>) + Send + Sync>;
+
+/// Configuration for graphics initialization.
+pub struct GraphicsConfig {
+    /// Application name reported to the driver.
+    pub application_name: String,
+
+    /// Application version as major, minor and patch numbers.
+    pub application_version: (u32, u32, u32),
+
+    /// Engine name reported to the driver.
+    pub engine_name: String,
+
+    /// Engine version as major, minor and patch numbers.
+    pub engine_version: (u32, u32, u32),
+
+    /// Highest API version as major and minor numbers the application is going to use.
+    /// Clamped to version supported by the implementation.
+    ///
+    /// Defaults to latest version supported by the implementation.
+    pub api_version: Option<(u32, u32)>,
+
+    /// Additional instance extensions to enable.
+    /// Initialization fails if any of them is not available.
+    pub extensions: Vec<String>,
+
+    /// Additional instance layers to enable.
+    /// Initialization fails if any of them is not available.
+    pub layers: Vec<String>,
+
+    /// Skips surface extensions.
+    /// Surfaces cannot be created but instance can be created without window system.
+    pub headless: bool,
+
+    /// Enables validation layers if available.
+    ///
+    /// Defaults to `true` in debug builds and `false` in release builds.
+    pub validation: bool,
+
+    /// Severities of debug messages to report.
+    ///
+    /// Defaults to warnings and errors.
+    pub message_severity: DebugMessageSeverity,
+
+    /// Callback to receive debug messages.
+    /// Messages are emitted as `tracing` events if none is provided.
+    ///
+    /// Panics in callback abort the process as they cannot unwind through the driver.
+    pub message_callback: Option<DebugMessageCallback>,
+
+    /// Compiler for shader languages and stages that built-in front-end
+    /// doesn't support.
+    /// With `shader-compiler` feature enabled defaults to shaderc-based compiler.
+    pub shader_compiler: Option<Box<dyn ShaderCompiler>>,
+
+    /// Directory where compiled SPIR-V is cached.
+    /// Shaders are looked up by `ShaderModuleInfo::cache_key`
+    /// and compiled only if missing in the cache.
+    pub shader_cache: Option<PathBuf>,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        GraphicsConfig {
+            application_name: String::from("IllumeApp"),
+            application_version: (0, 0, 1),
+            engine_name: String::from("Illume"),
+            engine_version: (0, 0, 1),
+            api_version: None,
+            extensions: Vec::new(),
+            layers: Vec::new(),
+            headless: false,
+            validation: cfg!(debug_assertions),
+            message_severity: DebugMessageSeverity::WARNING | DebugMessageSeverity::ERROR,
+            message_callback: None,
+            #[cfg(feature = "shader-compiler")]
+            shader_compiler: Some(Box::new(crate::shader::shader_compiler::Shaderc)),
+            #[cfg(not(feature = "shader-compiler"))]
+            shader_compiler: None,
+            shader_cache: None,
+        }
+    }
+}
+
+impl Debug for GraphicsConfig {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("GraphicsConfig")
+            .field("application_name", &self.application_name)
+            .field("application_version", &self.application_version)
+            .field("engine_name", &self.engine_name)
+            .field("engine_version", &self.engine_version)
+            .field("api_version", &self.api_version)
+            .field("extensions", &self.extensions)
+            .field("layers", &self.layers)
+            .field("headless", &self.headless)
+            .field("validation", &self.validation)
+            .field("message_severity", &self.message_severity)
+            .field("message_callback", &self.message_callback.is_some())
+            .field("shader_compiler", &self.shader_compiler.is_some())
+            .field("shader_cache", &self.shader_cache)
+            .finish()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum InitError {
     #[error(transparent)]
@@ -102,11 +268,41 @@ pub enum InitError {
         #[from]
         source: vk1_0::Result,
     },
+
+    #[error("Graphics is already initialized")]
+    AlreadyInitialized,
+
+    #[error("Requested instance layer `{name}` is not available")]
+    LayerNotAvailable { name: String },
+
+    #[error("Requested instance extension `{name}` is not available")]
+    ExtensionNotAvailable { name: String },
 }
 
 impl Graphics {
+    /// Returns global graphics instance,
+    /// initializing it with default config on first call.
     pub fn get_or_init() -> Result<&'static Graphics, InitError> {
-        GLOBAL_GRAPHICS.get_or_try_init(Self::new)
+        GLOBAL_GRAPHICS.get_or_try_init(|| Self::new(GraphicsConfig::default()))
+    }
+
+    /// Initializes global graphics instance with specified config.
+    ///
+    /// Fails with `InitError::AlreadyInitialized` if graphics was already
+    /// initialized, either by this function or by `get_or_init`.
+    pub fn init_with(config: GraphicsConfig) -> Result<&'static Graphics, InitError> {
+        let mut initialized = false;
+
+        let graphics = GLOBAL_GRAPHICS.get_or_try_init(|| {
+            initialized = true;
+            Self::new(config)
+        })?;
+
+        if initialized {
+            Ok(graphics)
+        } else {
+            Err(InitError::AlreadyInitialized)
+        }
     }
 
     pub(crate) unsafe fn get_unchecked() -> &'static Graphics {
@@ -114,12 +310,34 @@ impl Graphics {
     }
 
     #[tracing::instrument]
-    fn new() -> Result<Self, InitError> {
+    fn new(config: GraphicsConfig) -> Result<Self, InitError> {
         tracing::trace!("Init erupt graphisc implementation");
 
         let entry = EntryLoader::new()?;
 
-        let version = entry.instance_version();
+        let version = match config.api_version {
+            Some((major, minor)) => {
+                vk1_0::make_api_version(0, major, minor, 0).min(entry.instance_version())
+            }
+            None => entry.instance_version(),
+        };
+
+        let application_name = CString::new(config.application_name)
+            .expect("Application name must not contain nul bytes");
+        let engine_name =
+            CString::new(config.engine_name).expect("Engine name must not contain nul bytes");
+
+        let layers = config
+            .layers
+            .into_iter()
+            .map(|name| CString::new(name).expect("Layer name must not contain nul bytes"))
+            .collect::<Vec<_>>();
+
+        let extensions = config
+            .extensions
+            .into_iter()
+            .map(|name| CString::new(name).expect("Extension name must not contain nul bytes"))
+            .collect::<Vec<_>>();
 
         let layer_properties =
             unsafe { entry.enumerate_instance_layer_properties(None) }.result()?;
@@ -127,7 +345,7 @@ impl Graphics {
         let mut enable_layers = SmallVec::<[_; 1]>::new();
 
         // Pushes layer if it's avalable and returns if it was pushed.
-        let mut push_layer = |name: &'static CStr| -> bool {
+        let mut push_layer = |name: &CStr| -> bool {
             if layer_properties
                 .iter()
                 .any(|p| unsafe { CStr::from_ptr(&p.layer_name[0]) } == name)
@@ -139,12 +357,21 @@ impl Graphics {
             }
         };
 
-        if cfg!(debug_assertions) {
-            // Enable layers in debug mode.
-            if !push_layer(unsafe {
+        for name in &layers {
+            if !push_layer(name) {
+                return Err(InitError::LayerNotAvailable {
+                    name: name.to_string_lossy().into_owned(),
+                });
+            }
+        }
+
+        if config.validation {
+            let khronos_validation = push_layer(unsafe {
                 // Safe because literal has nul-byte.
                 CStr::from_bytes_with_nul_unchecked(b"VK_LAYER_KHRONOS_validation\0")
-            }) {
+            });
+
+            if !khronos_validation {
                 push_layer(unsafe {
                     // Safe because literal has nul-byte.
                     CStr::from_bytes_with_nul_unchecked(b"VK_LAYER_LUNARG_standard_validation\0")
@@ -201,15 +428,26 @@ impl Graphics {
             }
         };
 
+        for name in &extensions {
+            if !push_ext(name.as_ptr()) {
+                return Err(InitError::ExtensionNotAvailable {
+                    name: name.to_string_lossy().into_owned(),
+                });
+            }
+        }
+
         push_ext(KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2_EXTENSION_NAME);
 
-        if cfg!(debug_assertions) {
-            // Enable debug utils and report extensions in debug build.
-            push_ext(EXT_DEBUG_UTILS_EXTENSION_NAME);
+        // Debug utils provide object names and labels even without validation.
+        // Deprecated debug report is used for messages only when debug utils are missing.
+        let debug_utils = (config.validation || cfg!(debug_assertions))
+            && push_ext(EXT_DEBUG_UTILS_EXTENSION_NAME);
+
+        if config.validation && !debug_utils {
             push_ext(EXT_DEBUG_REPORT_EXTENSION_NAME);
         }
 
-        if push_ext(KHR_SURFACE_EXTENSION_NAME) {
+        if !config.headless && push_ext(KHR_SURFACE_EXTENSION_NAME) {
             #[cfg(target_os = "android")]
             {
                 push_ext(KHR_ANDROID_SURFACE_EXTENSION_NAME);
@@ -250,10 +488,10 @@ impl Graphics {
                 &vk1_0::InstanceCreateInfoBuilder::new()
                     .application_info(
                         &vk1_0::ApplicationInfoBuilder::new()
-                            .engine_name(CStr::from_bytes_with_nul(b"Illume\0").unwrap())
-                            .engine_version(1)
-                            .application_name(CStr::from_bytes_with_nul(b"IllumeApp\0").unwrap())
-                            .application_version(1)
+                            .engine_name(&engine_name)
+                            .engine_version(make_version(config.engine_version))
+                            .application_name(&application_name)
+                            .application_version(make_version(config.application_version))
                             .api_version(version),
                     )
                     .enabled_layer_names(&enable_layers)
@@ -272,12 +510,37 @@ impl Graphics {
             Ok(ok) => ok,
         };
 
-        if instance.enabled().ext_debug_report {
+        let callback: Box<DebugMessageCallback> = Box::new(
+            config
+                .message_callback
+                .unwrap_or_else(|| Box::new(trace_debug_message)),
+        );
+
+        let user_data = &*callback as *const DebugMessageCallback as *mut c_void;
+
+        if instance.enabled().ext_debug_utils {
+            let _ = unsafe {
+                instance.create_debug_utils_messenger_ext(
+                    &DebugUtilsMessengerCreateInfoEXTBuilder::new()
+                        .message_severity(DebugUtilsMessageSeverityFlagsEXT::from_bits_truncate(
+                            config.message_severity.bits(),
+                        ))
+                        .message_type(DebugUtilsMessageTypeFlagsEXT::from_bits_truncate(
+                            DebugMessageType::all().bits(),
+                        ))
+                        .pfn_user_callback(Some(debug_utils_messenger_callback))
+                        .user_data(user_data),
+                    None,
+                )
+            }
+            .result()?;
+        } else if instance.enabled().ext_debug_report {
             let _ = unsafe {
                 instance.create_debug_report_callback_ext(
                     &DebugReportCallbackCreateInfoEXTBuilder::new()
-                        .flags(DebugReportFlagsEXT::all())
-                        .pfn_callback(Some(debug_report_callback)),
+                        .flags(debug_report_flags(config.message_severity))
+                        .pfn_callback(Some(debug_report_callback))
+                        .user_data(user_data),
                     None,
                 )
             }
@@ -290,6 +553,9 @@ impl Graphics {
             instance,
             version,
             _entry: entry,
+            _callback: callback,
+            shader_compiler: config.shader_compiler,
+            shader_cache: config.shader_cache,
         };
 
         Ok(graphics)
@@ -599,34 +865,120 @@ impl fmt::Display for RequiredExtensionIsNotAvailable {
 
 impl std::error::Error for RequiredExtensionIsNotAvailable {}
 
+fn make_version((major, minor, patch): (u32, u32, u32)) -> u32 {
+    vk1_0::make_api_version(0, major, minor, patch)
+}
+
+fn debug_report_flags(severity: DebugMessageSeverity) -> DebugReportFlagsEXT {
+    let mut flags = DebugReportFlagsEXT::empty();
+
+    if severity.contains(DebugMessageSeverity::VERBOSE) {
+        flags |= DebugReportFlagsEXT::DEBUG_EXT;
+    }
+
+    if severity.contains(DebugMessageSeverity::INFO) {
+        flags |= DebugReportFlagsEXT::INFORMATION_EXT;
+    }
+
+    if severity.contains(DebugMessageSeverity::WARNING) {
+        flags |= DebugReportFlagsEXT::WARNING_EXT | DebugReportFlagsEXT::PERFORMANCE_WARNING_EXT;
+    }
+
+    if severity.contains(DebugMessageSeverity::ERROR) {
+        flags |= DebugReportFlagsEXT::ERROR_EXT;
+    }
+
+    flags
+}
+
+/// Default debug message callback that emits `tracing` events.
+fn trace_debug_message(message: &DebugMessage<'_>) {
+    if message.severity.contains(DebugMessageSeverity::ERROR) {
+        tracing::error!("{:?}: {} | {}", message.ty, message.id, message.message);
+    } else if message.severity.contains(DebugMessageSeverity::WARNING) {
+        tracing::warn!("{:?}: {} | {}", message.ty, message.id, message.message);
+    } else if message.severity.contains(DebugMessageSeverity::INFO) {
+        tracing::info!("{:?}: {} | {}", message.ty, message.id, message.message);
+    } else {
+        tracing::debug!("{:?}: {} | {}", message.ty, message.id, message.message);
+    }
+}
+
+/// Invokes callback passed as user data.
+/// Aborts if callback panics as unwinding into the driver is not allowed.
+unsafe fn invoke_callback(user_data: *mut c_void, message: &DebugMessage<'_>) {
+    let callback = &*(user_data as *const DebugMessageCallback);
+
+    if catch_unwind(AssertUnwindSafe(|| callback(message))).is_err() {
+        std::process::abort();
+    }
+}
+
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> std::borrow::Cow<'a, str> {
+    if ptr.is_null() {
+        "".into()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy()
+    }
+}
+
+unsafe extern "system" fn debug_utils_messenger_callback(
+    message_severity: DebugUtilsMessageSeverityFlagBitsEXT,
+    message_types: DebugUtilsMessageTypeFlagsEXT,
+    p_callback_data: *const DebugUtilsMessengerCallbackDataEXT,
+    p_user_data: *mut c_void,
+) -> vk1_0::Bool32 {
+    let data = &*p_callback_data;
+    let id = str_from_ptr(data.p_message_id_name);
+    let message = str_from_ptr(data.p_message);
+
+    invoke_callback(
+        p_user_data,
+        &DebugMessage {
+            severity: DebugMessageSeverity::from_bits_truncate(message_severity.0),
+            ty: DebugMessageType::from_bits_truncate(message_types.bits()),
+            id: &id,
+            message: &message,
+        },
+    );
+
+    0
+}
+
 unsafe extern "system" fn debug_report_callback(
     flags: DebugReportFlagsEXT,
-    object_type: DebugReportObjectTypeEXT,
+    _object_type: DebugReportObjectTypeEXT,
     _object: u64,
     _location: usize,
     _message_code: i32,
     p_layer_prefix: *const c_char,
     p_message: *const c_char,
-    _p_user_data: *mut c_void,
+    p_user_data: *mut c_void,
 ) -> vk1_0::Bool32 {
-    let layer_prefix = CStr::from_ptr(p_layer_prefix);
+    let layer_prefix = str_from_ptr(p_layer_prefix);
+    let message = str_from_ptr(p_message);
 
-    let message = CStr::from_ptr(p_message);
-
-    if flags.contains(DebugReportFlagsEXT::ERROR_EXT) {
-        tracing::error!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
-        // panic!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
+    let (severity, ty) = if flags.contains(DebugReportFlagsEXT::ERROR_EXT) {
+        (DebugMessageSeverity::ERROR, DebugMessageType::VALIDATION)
     } else if flags.contains(DebugReportFlagsEXT::PERFORMANCE_WARNING_EXT) {
-        tracing::warn!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
+        (DebugMessageSeverity::WARNING, DebugMessageType::PERFORMANCE)
     } else if flags.contains(DebugReportFlagsEXT::WARNING_EXT) {
-        tracing::warn!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
+        (DebugMessageSeverity::WARNING, DebugMessageType::VALIDATION)
     } else if flags.contains(DebugReportFlagsEXT::INFORMATION_EXT) {
-        tracing::info!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
-    } else if flags.contains(DebugReportFlagsEXT::DEBUG_EXT) {
-        tracing::debug!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
+        (DebugMessageSeverity::INFO, DebugMessageType::GENERAL)
     } else {
-        tracing::trace!("{:?}: {:?} | {:?}", layer_prefix, object_type, message);
-    }
+        (DebugMessageSeverity::VERBOSE, DebugMessageType::GENERAL)
+    };
+
+    invoke_callback(
+        p_user_data,
+        &DebugMessage {
+            severity,
+            ty,
+            id: &layer_prefix,
+            message: &message,
+        },
+    );
 
     0
 }
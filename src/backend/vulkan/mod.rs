@@ -11,13 +11,14 @@ mod encode;
 mod epochs;
 mod graphics;
 mod physical;
+mod pool;
 mod queue;
 mod resources;
 mod surface;
 mod swapchain;
 
 pub use self::{
-    device::*, encode::*, graphics::*, physical::*, queue::*, resources::*, surface::*,
+    device::*, encode::*, graphics::*, physical::*, pool::*, queue::*, resources::*, surface::*,
     swapchain::*,
 };
 
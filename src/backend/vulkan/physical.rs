@@ -19,6 +19,7 @@ use {
             khr_acceleration_structure::{self as acc, KHR_ACCELERATION_STRUCTURE_EXTENSION_NAME},
             khr_buffer_device_address::{self as bda, KHR_BUFFER_DEVICE_ADDRESS_EXTENSION_NAME},
             khr_deferred_host_operations::KHR_DEFERRED_HOST_OPERATIONS_EXTENSION_NAME,
+            khr_draw_indirect_count::KHR_DRAW_INDIRECT_COUNT_EXTENSION_NAME,
             khr_get_physical_device_properties2::{
                 self as pdp, KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2_EXTENSION_NAME,
             },
@@ -431,6 +432,34 @@ impl PhysicalDevice {
             features.push(Feature::SurfacePresentation);
         }
 
+        if self
+            .properties
+            .has_extension(unsafe { CStr::from_ptr(KHR_DRAW_INDIRECT_COUNT_EXTENSION_NAME) })
+            || self.features.v12.draw_indirect_count > 0
+        {
+            features.push(Feature::DrawIndirectCount);
+        }
+
+        if self.features.v10.multi_draw_indirect > 0 {
+            features.push(Feature::MultiDrawIndirect);
+        }
+
+        if self.features.v10.pipeline_statistics_query > 0 {
+            features.push(Feature::PipelineStatisticsQuery);
+        }
+
+        if self.features.v10.occlusion_query_precise > 0 {
+            features.push(Feature::OcclusionQueryPrecise);
+        }
+
+        if self.features.v10.sample_rate_shading > 0 {
+            features.push(Feature::SampleRateShading);
+        }
+
+        if self.features.v10.alpha_to_one > 0 {
+            features.push(Feature::AlphaToOne);
+        }
+
         DeviceInfo {
             kind: match self.properties.v10.device_type {
                 vk1_0::PhysicalDeviceType::INTEGRATED_GPU => Some(DeviceKind::Integrated),
@@ -817,6 +846,60 @@ impl PhysicalDevice {
             include_features12 = true;
         }
 
+        if requested_features.take(Feature::DrawIndirectCount) {
+            if self.features.v12.draw_indirect_count > 0 {
+                features12.draw_indirect_count = 1;
+                include_features12 = true;
+            } else if self
+                .properties
+                .has_extension(unsafe { CStr::from_ptr(KHR_DRAW_INDIRECT_COUNT_EXTENSION_NAME) })
+            {
+                push_ext(KHR_DRAW_INDIRECT_COUNT_EXTENSION_NAME);
+            } else {
+                panic!("Attempt to enable unsupported feature `DrawIndirectCount`");
+            }
+        }
+
+        if requested_features.take(Feature::MultiDrawIndirect) {
+            assert_ne!(
+                self.features.v10.multi_draw_indirect, 0,
+                "Attempt to enable unsupported feature `MultiDrawIndirect`"
+            );
+            features2.features.multi_draw_indirect = 1;
+        }
+
+        if requested_features.take(Feature::PipelineStatisticsQuery) {
+            assert_ne!(
+                self.features.v10.pipeline_statistics_query, 0,
+                "Attempt to enable unsupported feature `PipelineStatisticsQuery`"
+            );
+            features2.features.pipeline_statistics_query = 1;
+        }
+
+        if requested_features.take(Feature::OcclusionQueryPrecise) {
+            assert_ne!(
+                self.features.v10.occlusion_query_precise, 0,
+                "Attempt to enable unsupported feature `OcclusionQueryPrecise`"
+            );
+            features2.features.occlusion_query_precise = 1;
+        }
+
+        if requested_features.take(Feature::SampleRateShading) {
+            assert_ne!(
+                self.features.v10.sample_rate_shading, 0,
+                "Attempt to enable unsupported feature `SampleRateShading`"
+            );
+            features2.features.sample_rate_shading = 1;
+        }
+
+        if requested_features.take(Feature::AlphaToOne) {
+            assert_ne!(
+                self.features.v10.alpha_to_one, 0,
+                "Attempt to enable unsupported feature `AlphaToOne`"
+            );
+            features2.features.alpha_to_one = 1;
+        }
+
         if requested_features.take(Feature::ShaderSampledImageNonUniformIndexing) {
             assert!(requested_features.check(Feature::ShaderSampledImageDynamicIndexing));
             if self
@@ -0,0 +1,230 @@
+use {
+    super::{
+        convert::oom_error_from_erupt,
+        device::{Device, WeakDevice},
+        encode::CommandBuffer,
+    },
+    crate::{
+        encode::{Encoder, SecondaryEncoder},
+        framebuffer::Framebuffer,
+        queue::{QueueCapabilityFlags, QueueId},
+        OutOfMemory,
+    },
+    erupt::vk1_0,
+    parking_lot::{Mutex, MutexGuard},
+    scoped_arena::Scope,
+    std::{
+        fmt::{self, Debug},
+        sync::Arc,
+    },
+};
+
+/// Pool of command buffers for single queue family.
+///
+/// Unlike `Queue::create_encoder` it doesn't require exclusive access to the
+/// queue, so each recording thread can own a pool to record in parallel.
+/// Command buffers are returned to the pool when dropped,
+/// which happens after they finish execution if submitted.
+pub struct CommandPool {
+    inner: Arc<CommandPoolInner>,
+    device: Device,
+    queue: QueueId,
+    capabilities: QueueCapabilityFlags,
+}
+
+pub(super) struct CommandPoolInner {
+    handle: vk1_0::CommandPool,
+    owner: WeakDevice,
+
+    // Pool and its command buffers must be externally synchronized.
+    lock: Mutex<()>,
+    free: Mutex<FreeCommandBuffers>,
+}
+
+#[derive(Default)]
+struct FreeCommandBuffers {
+    primary: Vec<vk1_0::CommandBuffer>,
+    secondary: Vec<vk1_0::CommandBuffer>,
+}
+
+impl Drop for CommandPoolInner {
+    fn drop(&mut self) {
+        if let Some(device) = self.owner.upgrade() {
+            // Frees all command buffers allocated from the pool.
+            unsafe {
+                device
+                    .logical()
+                    .destroy_command_pool(Some(self.handle), None)
+            }
+        }
+    }
+}
+
+impl CommandPoolInner {
+    pub(super) fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock()
+    }
+
+    pub(super) fn release(&self, handle: vk1_0::CommandBuffer, secondary: bool) {
+        let mut free = self.free.lock();
+        if secondary {
+            free.secondary.push(handle);
+        } else {
+            free.primary.push(handle);
+        }
+    }
+}
+
+impl Debug for CommandPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if fmt.alternate() {
+            fmt.debug_struct("CommandPool")
+                .field("handle", &self.inner.handle)
+                .field("queue", &self.queue)
+                .field("device", &self.device)
+                .finish()
+        } else {
+            write!(fmt, "CommandPool({:p})", self.inner.handle)
+        }
+    }
+}
+
+impl CommandPool {
+    pub(super) fn new(
+        device: Device,
+        queue: QueueId,
+        capabilities: QueueCapabilityFlags,
+    ) -> Result<Self, OutOfMemory> {
+        let handle = unsafe {
+            device.logical().create_command_pool(
+                &vk1_0::CommandPoolCreateInfoBuilder::new()
+                    .flags(vk1_0::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+                    .queue_family_index(queue.family),
+                None,
+            )
+        }
+        .result()
+        .map_err(oom_error_from_erupt)?;
+
+        tracing::debug!("CommandPool created {:p}", handle);
+
+        Ok(CommandPool {
+            inner: Arc::new(CommandPoolInner {
+                handle,
+                owner: device.downgrade(),
+                lock: Mutex::new(()),
+                free: Mutex::new(FreeCommandBuffers::default()),
+            }),
+            device,
+            queue,
+            capabilities,
+        })
+    }
+
+    pub fn queue(&self) -> QueueId {
+        self.queue
+    }
+
+    /// Creates encoder for primary command buffer.
+    #[tracing::instrument]
+    pub fn create_encoder<'a>(&mut self, scope: &'a Scope<'a>) -> Result<Encoder<'a>, OutOfMemory> {
+        let handle = self.command_buffer(false)?;
+
+        let cbuf = CommandBuffer::from_pool(
+            handle,
+            self.queue,
+            self.device.downgrade(),
+            self.inner.clone(),
+            None,
+        );
+
+        Ok(Encoder::new(cbuf, self.capabilities, scope))
+    }
+
+    /// Creates encoder for secondary command buffer that continues
+    /// `subpass` of the render pass started with `framebuffer`.
+    ///
+    /// Resulting command buffer must be executed with
+    /// `RenderPassEncoder::execute_commands` within that subpass.
+    #[tracing::instrument(skip(scope))]
+    pub fn create_secondary_encoder<'a>(
+        &mut self,
+        framebuffer: &'a Framebuffer,
+        subpass: u32,
+        scope: &'a Scope<'a>,
+    ) -> Result<SecondaryEncoder<'a>, OutOfMemory> {
+        assert!(self.capabilities.supports_graphics());
+        assert_owner!(framebuffer, self.device);
+        assert!(
+            (subpass as usize) < framebuffer.info().render_pass.info().subpasses.len(),
+            "Subpass index is out of bounds"
+        );
+
+        let handle = self.command_buffer(true)?;
+
+        let cbuf = CommandBuffer::from_pool(
+            handle,
+            self.queue,
+            self.device.downgrade(),
+            self.inner.clone(),
+            Some((framebuffer.clone(), subpass)),
+        );
+
+        Ok(SecondaryEncoder::new(
+            cbuf,
+            self.capabilities,
+            framebuffer,
+            subpass,
+            scope,
+        ))
+    }
+
+    /// Reuses released command buffer or allocates new one.
+    fn command_buffer(&mut self, secondary: bool) -> Result<vk1_0::CommandBuffer, OutOfMemory> {
+        let free = {
+            let mut free = self.inner.free.lock();
+            if secondary {
+                free.secondary.pop()
+            } else {
+                free.primary.pop()
+            }
+        };
+
+        let _guard = self.inner.lock();
+
+        match free {
+            Some(handle) => {
+                unsafe {
+                    self.device.logical().reset_command_buffer(
+                        handle,
+                        Some(vk1_0::CommandBufferResetFlags::RELEASE_RESOURCES),
+                    )
+                }
+                .result()
+                .map_err(oom_error_from_erupt)?;
+
+                Ok(handle)
+            }
+            None => {
+                let level = if secondary {
+                    vk1_0::CommandBufferLevel::SECONDARY
+                } else {
+                    vk1_0::CommandBufferLevel::PRIMARY
+                };
+
+                let mut buffers = unsafe {
+                    self.device.logical().allocate_command_buffers(
+                        &vk1_0::CommandBufferAllocateInfoBuilder::new()
+                            .command_pool(self.inner.handle)
+                            .level(level)
+                            .command_buffer_count(1),
+                    )
+                }
+                .result()
+                .map_err(oom_error_from_erupt)?;
+
+                Ok(buffers.remove(0))
+            }
+        }
+    }
+}
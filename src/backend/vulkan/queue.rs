@@ -3,6 +3,7 @@ use {
         convert::{oom_error_from_erupt, ToErupt as _},
         device::Device,
         device_lost,
+        pool::CommandPool,
         swapchain::SwapchainImage,
         unexpected_result,
     },
@@ -107,6 +108,13 @@ impl Queue {
         }
     }
 
+    /// Creates command pool for this queue.
+    /// Pools allow recording command buffers on multiple threads in parallel.
+    #[tracing::instrument]
+    pub fn create_command_pool(&self) -> Result<CommandPool, OutOfMemory> {
+        CommandPool::new(self.device.clone(), self.id, self.capabilities)
+    }
+
     #[tracing::instrument(skip(cbufs))]
     pub fn submit(
         &mut self,
@@ -121,6 +129,10 @@ impl Queue {
         let handles = scope.to_scope_from_iter(cbufs.into_iter().map(|cbuf| {
             assert_owner!(cbuf, self.device);
             assert_eq!(self.id, cbuf.queue());
+            assert!(
+                !cbuf.is_secondary(),
+                "Secondary command buffers cannot be submitted"
+            );
             let handle = cbuf.handle();
             array.push(cbuf);
             handle
@@ -167,6 +179,10 @@ impl Queue {
     pub fn submit_one(&mut self, cbuf: CommandBuffer, fence: Option<&Fence>) {
         assert_owner!(cbuf, self.device);
         assert_eq!(self.id, cbuf.queue());
+        assert!(
+            !cbuf.is_secondary(),
+            "Secondary command buffers cannot be submitted"
+        );
         let handle = cbuf.handle();
 
         unsafe {
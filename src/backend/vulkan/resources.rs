@@ -10,11 +10,13 @@ use {
         pipeline::{
             ComputePipelineInfo, GraphicsPipelineInfo, PipelineLayoutInfo, RayTracingPipelineInfo,
         },
+        query::QueryPoolInfo,
         queue::QueueId,
+        reflect::ShaderReflection,
         render_pass::RenderPassInfo,
         sampler::SamplerInfo,
         shader::ShaderModuleInfo,
-        view::ImageViewInfo,
+        view::{BufferViewInfo, ImageViewInfo},
         DeviceAddress,
     },
     erupt::{extensions::khr_acceleration_structure as vkacc, vk1_0},
@@ -27,7 +29,10 @@ use {
         mem::ManuallyDrop,
         num::NonZeroU64,
         ops::Deref,
-        sync::Arc,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -510,6 +515,92 @@ impl ImageView {
     }
 }
 
+/// Handle to GPU buffer view object.
+///
+/// Formatted view into a [`Buffer`] region.
+/// [`BufferView`] is used as texel buffer descriptor.
+#[derive(Clone)]
+pub struct BufferView {
+    handle: vk1_0::BufferView,
+    inner: Arc<BufferViewInner>,
+}
+
+struct BufferViewInner {
+    info: BufferViewInfo,
+    owner: WeakDevice,
+    index: usize,
+}
+
+impl Drop for BufferViewInner {
+    fn drop(&mut self) {
+        resource_freed();
+
+        if let Some(device) = self.owner.upgrade() {
+            unsafe { device.destroy_buffer_view(self.index) }
+        }
+    }
+}
+
+impl Debug for BufferView {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if fmt.alternate() {
+            fmt.debug_struct("BufferView")
+                .field("info", &self.inner.info)
+                .field("handle", &self.handle)
+                .field("owner", &self.inner.owner)
+                .finish()
+        } else {
+            write!(fmt, "BufferView({:p})", self.handle)
+        }
+    }
+}
+
+impl PartialEq for BufferView {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.handle == rhs.handle
+    }
+}
+
+impl Eq for BufferView {}
+
+impl Hash for BufferView {
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        self.handle.hash(hasher)
+    }
+}
+
+impl BufferView {
+    pub fn info(&self) -> &BufferViewInfo {
+        &self.inner.info
+    }
+
+    pub(super) fn new(
+        info: BufferViewInfo,
+        owner: WeakDevice,
+        handle: vk1_0::BufferView,
+        index: usize,
+    ) -> Self {
+        resource_allocated();
+
+        BufferView {
+            handle,
+            inner: Arc::new(BufferViewInner { info, owner, index }),
+        }
+    }
+
+    pub(super) fn is_owned_by(&self, owner: &impl PartialEq<WeakDevice>) -> bool {
+        *owner == self.inner.owner
+    }
+
+    pub(super) fn handle(&self) -> vk1_0::BufferView {
+        debug_assert!(!self.handle.is_null());
+        self.handle
+    }
+}
+
 /// Handle to GPU fence object.
 ///
 /// Fence is object used for coarse grained GPU-CPU synchronization.
@@ -916,6 +1007,115 @@ impl Sampler {
     }
 }
 
+/// Pool of queries of single type.
+/// Queries are referenced by index in the pool.
+#[derive(Clone)]
+pub struct QueryPool {
+    handle: vk1_0::QueryPool,
+    info: QueryPoolInfo,
+    inner: Arc<QueryPoolInner>,
+}
+
+struct QueryPoolInner {
+    owner: WeakDevice,
+    index: usize,
+
+    // Smallest number of valid timestamp bits
+    // among queue families that wrote timestamps into the pool.
+    timestamp_valid_bits: AtomicU32,
+}
+
+impl Drop for QueryPoolInner {
+    fn drop(&mut self) {
+        resource_freed();
+
+        if let Some(device) = self.owner.upgrade() {
+            unsafe { device.destroy_query_pool(self.index) }
+        }
+    }
+}
+
+impl Debug for QueryPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if fmt.alternate() {
+            fmt.debug_struct("QueryPool")
+                .field("handle", &self.handle)
+                .field("owner", &self.inner.owner)
+                .field("info", &self.info)
+                .finish()
+        } else {
+            write!(fmt, "QueryPool({:p})", self.handle)
+        }
+    }
+}
+
+impl PartialEq for QueryPool {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.handle == rhs.handle
+    }
+}
+
+impl Eq for QueryPool {}
+
+impl Hash for QueryPool {
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        self.handle.hash(hasher)
+    }
+}
+
+impl QueryPool {
+    pub fn info(&self) -> &QueryPoolInfo {
+        &self.info
+    }
+
+    pub(super) fn new(
+        info: QueryPoolInfo,
+        owner: WeakDevice,
+        handle: vk1_0::QueryPool,
+        index: usize,
+    ) -> Self {
+        resource_allocated();
+
+        QueryPool {
+            info,
+            handle,
+            inner: Arc::new(QueryPoolInner {
+                owner,
+                index,
+                timestamp_valid_bits: AtomicU32::new(64),
+            }),
+        }
+    }
+
+    pub(super) fn is_owned_by(&self, owner: &impl PartialEq<WeakDevice>) -> bool {
+        *owner == self.inner.owner
+    }
+
+    pub(super) fn handle(&self) -> vk1_0::QueryPool {
+        debug_assert!(!self.handle.is_null());
+        self.handle
+    }
+
+    /// Records that timestamps with specified number of valid bits
+    /// are written into the pool.
+    pub(super) fn add_timestamp_valid_bits(&self, bits: u32) {
+        self.inner
+            .timestamp_valid_bits
+            .fetch_min(bits, Ordering::Relaxed);
+    }
+
+    /// Returns mask of valid bits of timestamps written into the pool.
+    pub(super) fn timestamp_mask(&self) -> u64 {
+        match self.inner.timestamp_valid_bits.load(Ordering::Relaxed) {
+            bits if bits >= 64 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+}
+
 /// Framebuffer is a collection of attachments for render pass.
 /// Images format and sample count should match attachment definitions.
 /// All image views must be 2D with 1 mip level and 1 array level.
@@ -1015,6 +1215,7 @@ pub struct ShaderModule {
 
 struct ShaderModuleInner {
     info: ShaderModuleInfo,
+    reflection: Option<ShaderReflection>,
     owner: WeakDevice,
     index: usize,
 }
@@ -1064,8 +1265,15 @@ impl ShaderModule {
         &self.inner.info
     }
 
+    /// Returns interface of the module entry points.
+    /// Returns `None` if module code could not be reflected.
+    pub fn reflection(&self) -> Option<&ShaderReflection> {
+        self.inner.reflection.as_ref()
+    }
+
     pub(super) fn new(
         info: ShaderModuleInfo,
+        reflection: Option<ShaderReflection>,
         owner: WeakDevice,
         handle: vk1_0::ShaderModule,
         index: usize,
@@ -1074,7 +1282,12 @@ impl ShaderModule {
 
         ShaderModule {
             handle,
-            inner: Arc::new(ShaderModuleInner { info, owner, index }),
+            inner: Arc::new(ShaderModuleInner {
+                info,
+                reflection,
+                owner,
+                index,
+            }),
         }
     }
 
@@ -1706,6 +1919,64 @@ impl RayTracingPipeline {
     }
 }
 
+/// Device objects that can be given debug names with `Device::set_object_name`.
+pub trait NamedObject: named::Sealed {}
+
+pub(super) mod named {
+    use {super::WeakDevice, erupt::vk1_0};
+
+    pub trait Sealed {
+        fn is_owned_by(&self, owner: &WeakDevice) -> bool;
+
+        fn object_type(&self) -> vk1_0::ObjectType;
+
+        fn object_handle(&self) -> u64;
+    }
+}
+
+macro_rules! impl_named_object {
+    ($($resource:ident => $handle:path),* $(,)?) => {
+        $(
+            impl named::Sealed for $resource {
+                fn is_owned_by(&self, owner: &WeakDevice) -> bool {
+                    $resource::is_owned_by(self, owner)
+                }
+
+                fn object_type(&self) -> vk1_0::ObjectType {
+                    <$handle>::TYPE
+                }
+
+                fn object_handle(&self) -> u64 {
+                    self.handle().object_handle()
+                }
+            }
+
+            impl NamedObject for $resource {}
+        )*
+    };
+}
+
+impl_named_object!(
+    Buffer => vk1_0::Buffer,
+    BufferView => vk1_0::BufferView,
+    Image => vk1_0::Image,
+    ImageView => vk1_0::ImageView,
+    Fence => vk1_0::Fence,
+    Semaphore => vk1_0::Semaphore,
+    RenderPass => vk1_0::RenderPass,
+    Sampler => vk1_0::Sampler,
+    QueryPool => vk1_0::QueryPool,
+    Framebuffer => vk1_0::Framebuffer,
+    ShaderModule => vk1_0::ShaderModule,
+    DescriptorSetLayout => vk1_0::DescriptorSetLayout,
+    DescriptorSet => vk1_0::DescriptorSet,
+    PipelineLayout => vk1_0::PipelineLayout,
+    ComputePipeline => vk1_0::Pipeline,
+    GraphicsPipeline => vk1_0::Pipeline,
+    AccelerationStructure => vkacc::AccelerationStructureKHR,
+    RayTracingPipeline => vk1_0::Pipeline,
+);
+
 #[cfg(feature = "leak-detection")]
 mod resource_counting {
     use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
@@ -2,6 +2,7 @@ use {
     super::{DescriptorBindingFlags, TypedDescriptorBinding},
     crate::{
         buffer::{Buffer, BufferRange},
+        image::Layout,
         Device, OutOfMemory,
     },
 };
@@ -17,7 +18,11 @@ impl TypedDescriptorBinding for Buffer {
     }
 
     #[inline]
-    fn get_descriptors(&self, _device: &Device) -> Result<[BufferRange; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[BufferRange; 1], OutOfMemory> {
         Ok([BufferRange::whole(self.clone())])
     }
 }
@@ -33,7 +38,11 @@ impl TypedDescriptorBinding for BufferRange {
     }
 
     #[inline]
-    fn get_descriptors(&self, _device: &Device) -> Result<[BufferRange; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[BufferRange; 1], OutOfMemory> {
         Ok([self.clone()])
     }
 }
@@ -49,7 +58,11 @@ impl<const N: usize> TypedDescriptorBinding for [BufferRange; N] {
     }
 
     #[inline]
-    fn get_descriptors(&self, _device: &Device) -> Result<[BufferRange; N], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[BufferRange; N], OutOfMemory> {
         Ok(self.clone())
     }
 }
@@ -68,7 +81,28 @@ impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<BufferRange,
     fn get_descriptors(
         &self,
         _device: &Device,
+        _layout: Layout,
     ) -> Result<arrayvec::ArrayVec<BufferRange, N>, OutOfMemory> {
         Ok(self.clone())
     }
 }
+
+impl TypedDescriptorBinding for Vec<BufferRange> {
+    const COUNT: u32 = 0;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = Vec<BufferRange>;
+
+    #[inline]
+    fn eq(&self, range: &Vec<BufferRange>) -> bool {
+        *self == *range
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<Vec<BufferRange>, OutOfMemory> {
+        Ok(self.clone())
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    super::{DescriptorBindingFlags, TypedDescriptorBinding},
+    crate::{image::Layout, view::BufferView, Device, OutOfMemory},
+};
+
+impl TypedDescriptorBinding for BufferView {
+    const COUNT: u32 = 1;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::empty();
+    type Descriptors = [BufferView; 1];
+
+    #[inline]
+    fn eq(&self, views: &[BufferView; 1]) -> bool {
+        *self == views[0]
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[BufferView; 1], OutOfMemory> {
+        Ok([self.clone()])
+    }
+}
+
+impl<const N: usize> TypedDescriptorBinding for [BufferView; N] {
+    const COUNT: u32 = N as u32;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::empty();
+    type Descriptors = [BufferView; N];
+
+    #[inline]
+    fn eq(&self, views: &[BufferView; N]) -> bool {
+        *self == *views
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[BufferView; N], OutOfMemory> {
+        Ok(self.clone())
+    }
+}
+
+impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<BufferView, N> {
+    const COUNT: u32 = N as u32;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = arrayvec::ArrayVec<BufferView, N>;
+
+    #[inline]
+    fn eq(&self, views: &arrayvec::ArrayVec<BufferView, N>) -> bool {
+        *self == *views
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<arrayvec::ArrayVec<BufferView, N>, OutOfMemory> {
+        Ok(self.clone())
+    }
+}
+
+impl TypedDescriptorBinding for Vec<BufferView> {
+    const COUNT: u32 = 0;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = Vec<BufferView>;
+
+    #[inline]
+    fn eq(&self, views: &Vec<BufferView>) -> bool {
+        *self == *views
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<Vec<BufferView>, OutOfMemory> {
+        Ok(self.clone())
+    }
+}
@@ -16,12 +16,13 @@ impl TypedDescriptorBinding for Image {
         *self == descriptors[0].view.info().image
     }
 
-    fn get_descriptors(&self, device: &Device) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        device: &Device,
+        layout: Layout,
+    ) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
         let view = device.create_image_view(ImageViewInfo::new(self.clone()))?;
-        Ok([ImageViewDescriptor {
-            view,
-            layout: Layout::ShaderReadOnlyOptimal,
-        }])
+        Ok([ImageViewDescriptor { view, layout }])
     }
 }
 
@@ -34,10 +35,14 @@ impl TypedDescriptorBinding for ImageView {
         *self == descriptors[0].view
     }
 
-    fn get_descriptors(&self, _device: &Device) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        layout: Layout,
+    ) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
         Ok([ImageViewDescriptor {
             view: self.clone(),
-            layout: Layout::ShaderReadOnlyOptimal,
+            layout,
         }])
     }
 }
@@ -51,7 +56,11 @@ impl TypedDescriptorBinding for ImageViewDescriptor {
         *self == descriptors[0]
     }
 
-    fn get_descriptors(&self, _device: &Device) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[ImageViewDescriptor; 1], OutOfMemory> {
         Ok([self.clone()])
     }
 }
@@ -67,14 +76,18 @@ impl<const N: usize> TypedDescriptorBinding for [ImageView; N] {
             .all(|(me, descriptor)| *me == descriptor.view)
     }
 
-    fn get_descriptors(&self, _device: &Device) -> Result<[ImageViewDescriptor; N], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        layout: Layout,
+    ) -> Result<[ImageViewDescriptor; N], OutOfMemory> {
         let mut result = arrayvec::ArrayVec::new();
 
         for me in self {
             unsafe {
                 result.push_unchecked(ImageViewDescriptor {
                     view: me.clone(),
-                    layout: Layout::ShaderReadOnlyOptimal,
+                    layout,
                 });
             }
         }
@@ -92,7 +105,11 @@ impl<const N: usize> TypedDescriptorBinding for [ImageViewDescriptor; N] {
         *self == *descriptors
     }
 
-    fn get_descriptors(&self, _device: &Device) -> Result<[ImageViewDescriptor; N], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[ImageViewDescriptor; N], OutOfMemory> {
         Ok(self.clone())
     }
 }
@@ -113,6 +130,7 @@ impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<ImageView, N>
     fn get_descriptors(
         &self,
         _device: &Device,
+        layout: Layout,
     ) -> Result<arrayvec::ArrayVec<ImageViewDescriptor, N>, OutOfMemory> {
         let mut result = arrayvec::ArrayVec::new();
 
@@ -120,7 +138,7 @@ impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<ImageView, N>
             unsafe {
                 result.push_unchecked(ImageViewDescriptor {
                     view: me.clone(),
-                    layout: Layout::ShaderReadOnlyOptimal,
+                    layout,
                 });
             }
         }
@@ -141,7 +159,54 @@ impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<ImageViewDesc
     fn get_descriptors(
         &self,
         _device: &Device,
+        _layout: Layout,
     ) -> Result<arrayvec::ArrayVec<ImageViewDescriptor, N>, OutOfMemory> {
         Ok(self.clone())
     }
 }
+
+impl TypedDescriptorBinding for Vec<ImageView> {
+    const COUNT: u32 = 0;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = Vec<ImageViewDescriptor>;
+
+    fn eq(&self, descriptors: &Vec<ImageViewDescriptor>) -> bool {
+        self.len() == descriptors.len()
+            && self
+                .iter()
+                .zip(descriptors)
+                .all(|(me, descriptor)| *me == descriptor.view)
+    }
+
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        layout: Layout,
+    ) -> Result<Vec<ImageViewDescriptor>, OutOfMemory> {
+        Ok(self
+            .iter()
+            .map(|me| ImageViewDescriptor {
+                view: me.clone(),
+                layout,
+            })
+            .collect())
+    }
+}
+
+impl TypedDescriptorBinding for Vec<ImageViewDescriptor> {
+    const COUNT: u32 = 0;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = Vec<ImageViewDescriptor>;
+
+    fn eq(&self, descriptors: &Vec<ImageViewDescriptor>) -> bool {
+        *self == *descriptors
+    }
+
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<Vec<ImageViewDescriptor>, OutOfMemory> {
+        Ok(self.clone())
+    }
+}
@@ -101,6 +101,14 @@ pub enum DescriptorType {
     /// Unlike [`SampledImage`] [`StorageImage`] can be overwritten by shader.
     StorageImage,
 
+    /// Buffer region with formatted texels that can be read by shader.
+    /// Contains [`BufferView`] instance.
+    UniformTexelBuffer,
+
+    /// Buffer region with formatted texels that can be used as storage.
+    /// Unlike [`UniformTexelBuffer`] [`StorageTexelBuffer`] can be overwritten by shader.
+    StorageTexelBuffer,
+
     /// Buffer with shader uniform data.
     UniformBuffer,
 
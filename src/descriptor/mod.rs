@@ -1,11 +1,12 @@
 mod buffer;
+mod buffer_view;
 mod image;
 mod layout;
 mod sampler;
-// mod sparse;
+mod sparse;
 
 pub use {
-    self::{buffer::*, image::*, layout::* /*, sparse::**/},
+    self::{buffer::*, image::*, layout::*, sparse::*},
     crate::{backend::DescriptorSet, queue::QueueId, stage::PipelineStageFlags},
 };
 
@@ -18,7 +19,7 @@ use crate::{
     image::Layout,
     // image::{ImageExtent, SubresourceRange},
     sampler::Sampler,
-    view::ImageView,
+    view::{BufferView, ImageView},
     // view::ImageViewKind,
     OutOfMemory,
 };
@@ -109,8 +110,12 @@ pub enum Descriptors<'a> {
     /// Storage image descriptors.
     StorageImage(&'a [ImageViewDescriptor]),
 
-    // UniformTexelBuffer(&'a BufferView),
-    // StorageTexelBuffer(&'a BufferView),
+    /// Uniform texel buffer views.
+    UniformTexelBuffer(&'a [BufferView]),
+
+    /// Storage texel buffer views.
+    StorageTexelBuffer(&'a [BufferView]),
+
     /// Uniform buffer regions.
     UniformBuffer(&'a [BufferRange]),
 
@@ -146,6 +151,14 @@ pub enum SampledImageDescriptor {}
 #[derive(Clone, Copy, Debug)]
 pub enum StorageImageDescriptor {}
 
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum UniformTexelBufferDescriptor {}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum StorageTexelBufferDescriptor {}
+
 #[doc(hidden)]
 #[derive(Clone, Copy, Debug)]
 pub enum UniformBufferDescriptor {}
@@ -173,7 +186,7 @@ pub enum AccelerationStructureDescriptor {}
 #[doc(hidden)]
 pub trait TypedDescriptor {
     const TYPE: DescriptorType;
-    type Descriptor: std::hash::Hash + Eq;
+    type Descriptor: std::hash::Hash + Eq + Clone + std::fmt::Debug;
 
     fn descriptors<'a>(slice: &'a [Self::Descriptor]) -> Descriptors<'a>;
 }
@@ -214,6 +227,24 @@ impl TypedDescriptor for StorageImageDescriptor {
     }
 }
 
+impl TypedDescriptor for UniformTexelBufferDescriptor {
+    const TYPE: DescriptorType = DescriptorType::UniformTexelBuffer;
+    type Descriptor = BufferView;
+
+    fn descriptors<'a>(slice: &'a [BufferView]) -> Descriptors<'a> {
+        Descriptors::UniformTexelBuffer(slice)
+    }
+}
+
+impl TypedDescriptor for StorageTexelBufferDescriptor {
+    const TYPE: DescriptorType = DescriptorType::StorageTexelBuffer;
+    type Descriptor = BufferView;
+
+    fn descriptors<'a>(slice: &'a [BufferView]) -> Descriptors<'a> {
+        Descriptors::StorageTexelBuffer(slice)
+    }
+}
+
 impl TypedDescriptor for UniformBufferDescriptor {
     const TYPE: DescriptorType = DescriptorType::UniformBuffer;
     type Descriptor = BufferRange;
@@ -268,6 +299,55 @@ impl TypedDescriptor for AccelerationStructureDescriptor {
     }
 }
 
+/// Returns range of descriptors in `new` that differ from `old`.
+/// Returns `None` if there is nothing to write.
+///
+/// Elements of `old` beyond the length of `new` are not reported.
+/// When array shrinks, descriptors previously written for them
+/// stay in the set as stale elements and must not be accessed by shaders.
+///
+/// This function is intended to be used by code generated by proc macro `#[descriptors]`
+/// to update only changed elements of descriptor arrays.
+#[doc(hidden)]
+pub fn changed_descriptors_range<T: PartialEq>(
+    old: Option<&[T]>,
+    new: &[T],
+) -> Option<std::ops::Range<usize>> {
+    let old = match old {
+        None => {
+            return if new.is_empty() {
+                None
+            } else {
+                Some(0..new.len())
+            }
+        }
+        Some(old) => old,
+    };
+
+    let common = old.len().min(new.len());
+    let start = old[..common]
+        .iter()
+        .zip(&new[..common])
+        .position(|(old, new)| old != new)
+        .unwrap_or(common);
+
+    let end = if new.len() > common {
+        new.len()
+    } else {
+        old[start..common]
+            .iter()
+            .zip(&new[start..common])
+            .rposition(|(old, new)| old != new)
+            .map_or(start, |index| start + index + 1)
+    };
+
+    if start < end {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
 /// Defines operation to copy descriptors range from one set to another.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct CopyDescriptorSet<'a> {
@@ -358,6 +438,8 @@ pub trait UpdatedPipelineDescriptors<P: ?Sized>: UpdatedDescriptors {
 /// Trait for all types that can be used as descriptor.
 pub trait TypedDescriptorBinding {
     /// Number of descriptors in the binding.
+    /// Zero for runtime-sized bindings which require
+    /// descriptors count to be specified in layout.
     const COUNT: u32;
 
     /// Flags necessary for this binding type.
@@ -371,6 +453,68 @@ pub trait TypedDescriptorBinding {
     /// and no update is required.
     fn eq(&self, descriptors: &Self::Descriptors) -> bool;
 
-    /// Returns `BufferRange` equivalent to self.
-    fn get_descriptors(&self, device: &Device) -> Result<Self::Descriptors, OutOfMemory>;
+    /// Returns descriptors equivalent to self.
+    ///
+    /// `layout` is the image layout used by descriptors that don't specify one.
+    /// It depends on descriptor type, e.g. storage images are accessed in `General` layout.
+    fn get_descriptors(
+        &self,
+        device: &Device,
+        layout: Layout,
+    ) -> Result<Self::Descriptors, OutOfMemory>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_descriptors_range;
+
+    #[test]
+    fn changed_range_without_old() {
+        assert_eq!(changed_descriptors_range(None, &[1, 2, 3]), Some(0..3));
+        assert_eq!(changed_descriptors_range::<u32>(None, &[]), None);
+    }
+
+    #[test]
+    fn changed_range_no_change() {
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2, 3]), &[1, 2, 3]),
+            None
+        );
+    }
+
+    #[test]
+    fn changed_range_in_the_middle() {
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2, 3, 4, 5]), &[1, 7, 8, 4, 5]),
+            Some(1..3)
+        );
+    }
+
+    #[test]
+    fn changed_range_insert_in_the_middle() {
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2, 3]), &[1, 9, 2, 3]),
+            Some(1..4)
+        );
+    }
+
+    #[test]
+    fn changed_range_grow() {
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2]), &[1, 2, 3, 4]),
+            Some(2..4)
+        );
+    }
+
+    #[test]
+    fn changed_range_shrink() {
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2, 3, 4]), &[1, 2]),
+            None
+        );
+        assert_eq!(
+            changed_descriptors_range(Some(&[1, 2, 3, 4]), &[1, 5]),
+            Some(1..2)
+        );
+    }
 }
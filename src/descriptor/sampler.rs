@@ -1,6 +1,6 @@
 use {
     super::{DescriptorBindingFlags, TypedDescriptorBinding},
-    crate::{sampler::Sampler, Device, OutOfMemory},
+    crate::{image::Layout, sampler::Sampler, Device, OutOfMemory},
 };
 
 impl TypedDescriptorBinding for Sampler {
@@ -14,7 +14,11 @@ impl TypedDescriptorBinding for Sampler {
     }
 
     #[inline]
-    fn get_descriptors(&self, _device: &Device) -> Result<[Sampler; 1], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[Sampler; 1], OutOfMemory> {
         Ok([self.clone()])
     }
 }
@@ -30,7 +34,11 @@ impl<const N: usize> TypedDescriptorBinding for [Sampler; N] {
     }
 
     #[inline]
-    fn get_descriptors(&self, _device: &Device) -> Result<[Sampler; N], OutOfMemory> {
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<[Sampler; N], OutOfMemory> {
         Ok(self.clone())
     }
 }
@@ -49,7 +57,28 @@ impl<const N: usize> TypedDescriptorBinding for arrayvec::ArrayVec<Sampler, N> {
     fn get_descriptors(
         &self,
         _device: &Device,
+        _layout: Layout,
     ) -> Result<arrayvec::ArrayVec<Sampler, N>, OutOfMemory> {
         Ok(self.clone())
     }
 }
+
+impl TypedDescriptorBinding for Vec<Sampler> {
+    const COUNT: u32 = 0;
+    const FLAGS: DescriptorBindingFlags = DescriptorBindingFlags::PARTIALLY_BOUND;
+    type Descriptors = Vec<Sampler>;
+
+    #[inline]
+    fn eq(&self, range: &Vec<Sampler>) -> bool {
+        *self == *range
+    }
+
+    #[inline]
+    fn get_descriptors(
+        &self,
+        _device: &Device,
+        _layout: Layout,
+    ) -> Result<Vec<Sampler>, OutOfMemory> {
+        Ok(self.clone())
+    }
+}
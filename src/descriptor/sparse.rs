@@ -5,17 +5,16 @@ use {
         DescriptorsAllocationError, DescriptorsInput, DescriptorsInstance, DescriptorsLayout,
         TypedDescriptor, UpdatedDescriptors, WriteDescriptorSet,
     },
-    crate::{encode::Encoder, shader::ShaderStageFlags, Device, OutOfMemory},
-    layered_bitset::{BitSet, BitSet4096, BitSetMut},
+    crate::{encode::Encoder, queue::QueueId, shader::ShaderStageFlags, Device, OutOfMemory},
     std::{
-        collections::hash_map::{Entry, HashMap},
+        collections::{hash_map::Entry, HashMap, VecDeque},
         hash::Hash,
         marker::PhantomData,
     },
 };
 
 /// Descriptors layout for `SparseDescriptors`.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SparseDescriptorsLayout<T> {
     raw: DescriptorSetLayout,
     cap: u32,
@@ -38,11 +37,34 @@ where
 }
 
 /// Descriptors input to be used in proc-macro pipelines.
+///
+/// Declares set with single array binding of `CAP` descriptors of type `T`
+/// accessible from `STAGES` (bits of `ShaderStageFlags`).
+/// Resources are inserted into and removed from the array with
+/// [`SparseDescriptorsInstance`] and accessed in shaders by index,
+/// making it a bindless resource table.
+///
+/// Requires `Feature::DescriptorBindingPartiallyBound`
+/// and `Feature::DescriptorBindingUpdateUnusedWhilePending`.
 #[derive(Debug)]
 pub struct SparseDescriptors<T, const CAP: u32, const STAGES: u32> {
     marker: PhantomData<fn() -> T>,
 }
 
+impl<T, const CAP: u32, const STAGES: u32> SparseDescriptors<T, CAP, STAGES> {
+    pub const fn new() -> Self {
+        SparseDescriptors {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, const CAP: u32, const STAGES: u32> Default for SparseDescriptors<T, CAP, STAGES> {
+    fn default() -> Self {
+        SparseDescriptors::new()
+    }
+}
+
 impl<T, const CAP: u32, const STAGES: u32> DescriptorsInput for SparseDescriptors<T, CAP, STAGES>
 where
     T: TypedDescriptor,
@@ -72,16 +94,42 @@ where
 }
 
 /// Descriptor instance with sparsely located resources.
+///
+/// Slots of removed resources are reused only after all work
+/// submitted before removal is complete,
+/// so shaders in flight never see descriptor replaced under them.
 #[derive(Debug)]
 pub struct SparseDescriptorsInstance<T: TypedDescriptor> {
     layout: DescriptorSetLayout,
     set: Option<SparseDescriptorSet>,
-    indices: HashMap<T::Descriptor, u32>,
+    slots: Slots<T::Descriptor>,
+}
+
+/// Allocation of array slots for resources.
+#[derive(Debug)]
+struct Slots<D> {
+    cap: u32,
+
+    indices: HashMap<D, u32>,
+    slots: Vec<Option<D>>,
 
-    upper_bounds: u32,
-    unused: BitSet4096,
+    // Slots ready to be reused.
+    free: Vec<u32>,
 
-    updates: Vec<T::Descriptor>,
+    // Slots removed since last update.
+    removed: Vec<(u32, D)>,
+
+    // Removed slots that may still be accessed by GPU.
+    retired: VecDeque<RetiredSlots<D>>,
+
+    // Slots to be written on next update.
+    updates: Vec<u32>,
+}
+
+#[derive(Debug)]
+struct RetiredSlots<D> {
+    epochs: Vec<(QueueId, u64)>,
+    slots: Vec<(u32, D)>,
 }
 
 #[derive(Debug)]
@@ -99,7 +147,6 @@ impl<T, const CAP: u32, const STAGES: u32> DescriptorsInstance<SparseDescriptors
     for SparseDescriptorsInstance<T>
 where
     T: TypedDescriptor,
-    T::Descriptor: Hash + Eq,
 {
     type Updated = SparseDescriptorSet;
 
@@ -111,29 +158,7 @@ where
         writes: &mut impl Extend<WriteDescriptorSet<'a>>,
         _encoder: &mut Encoder<'a>,
     ) -> Result<&'a SparseDescriptorSet, DescriptorsAllocationError> {
-        if self.set.is_none() {
-            self.set = Some(SparseDescriptorSet {
-                raw: device.create_descriptor_set(DescriptorSetInfo {
-                    layout: self.layout.clone(),
-                })?,
-            });
-        }
-
-        let set = self.set.as_ref().unwrap();
-        let indices = &self.indices;
-
-        writes.extend(self.updates.drain(..).filter_map(|descriptor| {
-            let (descriptor, idx) = indices.get_key_value(&descriptor)?;
-
-            Some(WriteDescriptorSet {
-                set: &set.raw,
-                binding: 0,
-                element: *idx,
-                descriptors: T::descriptors(std::slice::from_ref(descriptor)),
-            })
-        }));
-
-        Ok(set)
+        self.update(device, writes)
     }
 
     fn raw_layout(&self) -> &DescriptorSetLayout {
@@ -150,64 +175,217 @@ where
         SparseDescriptorsInstance {
             layout,
             set: None,
-            upper_bounds: 0,
-            indices: HashMap::new(),
-            unused: BitSet4096::default(),
-            updates: Vec::with_capacity(cap as usize),
+            slots: Slots::new(cap),
         }
     }
 
+    /// Returns number of resources in the array.
+    pub fn len(&self) -> usize {
+        self.slots.indices.len()
+    }
+
+    /// Returns `true` if there are no resources in the array.
+    pub fn is_empty(&self) -> bool {
+        self.slots.indices.is_empty()
+    }
+
+    /// Returns index of specified resource inside this array.
+    pub fn get(&self, descriptor: &T::Descriptor) -> Option<u32> {
+        self.slots.indices.get(descriptor).copied()
+    }
+
     /// Returns index for specified resource inside this array.
     /// Inserts resource if not in array yet.
     ///
     /// # Panics
     ///
+    /// This function panics if all slots are occupied
+    /// or wait for GPU to finish using them.
+    pub fn get_or_insert(&mut self, descriptor: T::Descriptor) -> u32 {
+        self.slots.get_or_insert(descriptor)
+    }
+
+    /// Removes specified resource from this array.
+    /// Returns `true` if resource was in the array.
     ///
-    pub fn get_or_insert(&mut self, descriptor: T::Descriptor) -> u32
-    where
-        T::Descriptor: Hash + Clone + Eq,
-    {
-        match self.indices.entry(descriptor.clone()) {
+    /// Resource is kept alive and its slot is not reused
+    /// until all work submitted before next update is complete.
+    pub fn remove(&mut self, descriptor: &T::Descriptor) -> bool {
+        self.slots.remove(descriptor)
+    }
+
+    /// Writes inserted resources into descriptor set
+    /// and releases slots no longer used by GPU.
+    pub fn update<'a>(
+        &'a mut self,
+        device: &Device,
+        writes: &mut impl Extend<WriteDescriptorSet<'a>>,
+    ) -> Result<&'a SparseDescriptorSet, DescriptorsAllocationError> {
+        if self.set.is_none() {
+            self.set = Some(SparseDescriptorSet {
+                raw: device.create_descriptor_set(DescriptorSetInfo {
+                    layout: self.layout.clone(),
+                })?,
+            });
+        }
+
+        self.slots.retire(
+            |epochs| device.epochs_closed(epochs),
+            || device.current_epochs(),
+        );
+
+        let updates = std::mem::take(&mut self.slots.updates);
+        let this: &'a Self = self;
+        let set = this.set.as_ref().unwrap();
+
+        writes.extend(updates.into_iter().map(|index| {
+            let descriptor = this.slots.slots[index as usize].as_ref().unwrap();
+
+            WriteDescriptorSet {
+                set: &set.raw,
+                binding: 0,
+                element: index,
+                descriptors: T::descriptors(std::slice::from_ref(descriptor)),
+            }
+        }));
+
+        Ok(set)
+    }
+}
+
+impl<D> Slots<D>
+where
+    D: Clone + Eq + Hash,
+{
+    fn new(cap: u32) -> Self {
+        Slots {
+            cap,
+            indices: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            removed: Vec::new(),
+            retired: VecDeque::new(),
+            updates: Vec::new(),
+        }
+    }
+
+    fn get_or_insert(&mut self, descriptor: D) -> u32 {
+        match self.indices.entry(descriptor) {
             Entry::Occupied(entry) => *entry.get(),
-            Entry::Vacant(entry) => match self.unused.find_set(0) {
-                None => {
-                    if self.upper_bounds == BitSet4096::UPPER_BOUND {
-                        panic!("Too many resources inserted");
+            Entry::Vacant(entry) => {
+                let index = match self.free.pop() {
+                    Some(index) => index,
+                    None => {
+                        let index = self.slots.len() as u32;
+                        if index >= self.cap {
+                            panic!("Too many resources inserted");
+                        }
+                        self.slots.push(None);
+                        index
                     }
-                    self.updates.push(descriptor.clone());
-                    entry.insert(self.upper_bounds);
-                    self.upper_bounds += 1;
-                    self.upper_bounds - 1
-                }
-                Some(idx) => *entry.insert(idx),
-            },
+                };
+
+                self.slots[index as usize] = Some(entry.key().clone());
+                self.updates.push(index);
+                *entry.insert(index)
+            }
         }
     }
 
-    /// Returns index for specified resource inside this array.
-    /// Inserts resource if not in array yet.
-    ///
-    /// # Panics
-    ///
-    ///
-    pub fn remove(&mut self, descriptor: T::Descriptor) -> bool
-    where
-        T::Descriptor: Hash + Eq,
-    {
-        match self.indices.get(&descriptor) {
+    fn remove(&mut self, descriptor: &D) -> bool {
+        match self.indices.remove(descriptor) {
             None => false,
-            Some(idx) => {
-                if *idx == self.upper_bounds {
-                    self.upper_bounds -= 1;
-
-                    while self.upper_bounds > 0 && self.unused.get(self.upper_bounds - 1) {
-                        self.unused.set(self.upper_bounds - 1, false);
-                        debug_assert!(self.unused.find_set(self.upper_bounds - 1).is_none());
-                        self.upper_bounds -= 1;
-                    }
-                }
+            Some(index) => {
+                let descriptor = self.slots[index as usize].take().unwrap();
+                self.updates.retain(|&update| update != index);
+                self.removed.push((index, descriptor));
                 true
             }
         }
     }
+
+    /// Frees retired slots for which `closed` returns `true`
+    /// and retires slots removed since last call until `current` epochs are closed.
+    fn retire(
+        &mut self,
+        closed: impl Fn(&[(QueueId, u64)]) -> bool,
+        current: impl FnOnce() -> Vec<(QueueId, u64)>,
+    ) {
+        while let Some(retired) = self.retired.front() {
+            if !closed(&retired.epochs) {
+                break;
+            }
+
+            let retired = self.retired.pop_front().unwrap();
+            self.free
+                .extend(retired.slots.into_iter().map(|(index, _)| index));
+        }
+
+        if !self.removed.is_empty() {
+            self.retired.push_back(RetiredSlots {
+                epochs: current(),
+                slots: std::mem::take(&mut self.removed),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::Slots, crate::queue::QueueId};
+
+    const QUEUE: QueueId = QueueId {
+        family: 0,
+        index: 0,
+    };
+
+    /// Retires slots as if `epoch` was current and all epochs before `closed` are closed.
+    fn retire(slots: &mut Slots<&'static str>, epoch: u64, closed: u64) {
+        slots.retire(
+            |epochs| epochs.iter().all(|&(_, epoch)| epoch < closed),
+            || vec![(QUEUE, epoch)],
+        );
+    }
+
+    #[test]
+    fn removed_slot_is_not_reused_before_epoch_is_closed() {
+        let mut slots = Slots::new(4);
+        assert_eq!(slots.get_or_insert("a"), 0);
+        assert_eq!(slots.get_or_insert("b"), 1);
+        assert!(slots.remove(&"a"));
+        assert!(!slots.remove(&"a"));
+
+        // Removal is captured with current epoch 1.
+        retire(&mut slots, 1, 0);
+        assert_eq!(slots.get_or_insert("c"), 2);
+
+        // Epoch 0 is closed, but epoch 1 is not.
+        retire(&mut slots, 2, 1);
+        assert_eq!(slots.get_or_insert("d"), 3);
+        assert!(slots.free.is_empty());
+    }
+
+    #[test]
+    fn removed_slot_is_reused_after_epoch_is_closed() {
+        let mut slots = Slots::new(2);
+        assert_eq!(slots.get_or_insert("a"), 0);
+        assert_eq!(slots.get_or_insert("b"), 1);
+        assert!(slots.remove(&"a"));
+        retire(&mut slots, 1, 0);
+
+        retire(&mut slots, 2, 2);
+        assert_eq!(slots.get_or_insert("c"), 0);
+        assert_eq!(slots.updates, [1, 0]);
+        assert_eq!(slots.slots, [Some("c"), Some("b")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many resources inserted")]
+    fn retired_slots_are_not_counted_as_free() {
+        let mut slots = Slots::new(1);
+        slots.get_or_insert("a");
+        slots.remove(&"a");
+        retire(&mut slots, 1, 1);
+        slots.get_or_insert("b");
+    }
 }
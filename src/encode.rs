@@ -1,29 +1,43 @@
-pub use crate::backend::CommandBuffer;
+pub use crate::backend::{CommandBuffer, CommandPool};
 use {
     crate::{
         accel::AccelerationStructureBuildGeometryInfo,
         access::AccessFlags,
         arith_le,
-        buffer::{Buffer, BufferMemoryBarrier},
+        buffer::{Buffer, BufferMemoryBarrier, BufferUsage},
         descriptor::{DescriptorSet, UpdatedPipelineDescriptors},
         framebuffer::{Framebuffer, FramebufferError},
-        image::{Image, ImageBlit, ImageMemoryBarrier, Layout, SubresourceLayers},
+        image::{
+            Image, ImageBlit, ImageMemoryBarrier, ImageUsage, Layout, Samples, SubresourceLayers,
+            SubresourceRange,
+        },
         memory::MemoryBarrier,
         pipeline::{
-            ComputePipeline, DynamicGraphicsPipeline, GraphicsPipeline, PipelineLayout,
-            RayTracingPipeline, ShaderBindingTable, TypedPipelineLayout, Viewport,
+            ComputePipeline, CreatePipelineError, DynamicGraphicsPipeline, GraphicsPipeline,
+            PipelineLayout, RayTracingPipeline, ShaderBindingTable, TypedPipelineLayout, Viewport,
         },
+        query::{QueryPool, QueryResultFlags, QueryType},
         queue::QueueCapabilityFlags,
-        render_pass::{ClearValue, RenderPass, RenderPassInstance},
+        render_pass::{
+            ClearColor, ClearDepth, ClearDepthStencil, ClearStencil, ClearValue, RenderPass,
+            RenderPassInstance,
+        },
         sampler::Filter,
         shader::ShaderStageFlags,
         stage::PipelineStageFlags,
-        Device, Extent3d, IndexType, Offset3d, OutOfMemory, Rect2d,
+        Device, Extent3d, IndexType, Offset3d, Rect2d,
     },
     arrayvec::ArrayVec,
-    bytemuck::{cast_slice, Pod},
+    bytemuck::{cast_slice, Pod, Zeroable},
     scoped_arena::Scope,
-    std::{fmt::Debug, mem::size_of_val, ops::Range},
+    smallvec::SmallVec,
+    std::{
+        ffi::CStr,
+        fmt::Debug,
+        iter::once,
+        mem::{size_of, size_of_val},
+        ops::Range,
+    },
 };
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -44,6 +58,17 @@ pub struct ImageCopy {
     pub extent: Extent3d,
 }
 
+/// Region of multisampled image to resolve into single-sampled image.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageResolve {
+    pub src_subresource: SubresourceLayers,
+    pub src_offset: Offset3d,
+    pub dst_subresource: SubresourceLayers,
+    pub dst_offset: Offset3d,
+    pub extent: Extent3d,
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
 pub struct BufferImageCopy {
@@ -55,14 +80,100 @@ pub struct BufferImageCopy {
     pub image_extent: Extent3d,
 }
 
+/// Attachment of current subpass to clear with `clear_attachments` command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClearAttachment {
+    /// Clears color attachment with specified index in subpass color attachments list.
+    Color(u32, ClearColor),
+
+    /// Clears depth aspect of subpass depth-stencil attachment.
+    Depth(ClearDepth),
+
+    /// Clears stencil aspect of subpass depth-stencil attachment.
+    Stencil(ClearStencil),
+
+    /// Clears both aspects of subpass depth-stencil attachment.
+    DepthStencil(ClearDepthStencil),
+}
+
+/// Region of attachments to clear with `clear_attachments` command.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClearRect {
+    pub rect: Rect2d,
+    pub first_layer: u32,
+    pub layer_count: u32,
+}
+
+impl From<Rect2d> for ClearRect {
+    fn from(rect: Rect2d) -> Self {
+        ClearRect {
+            rect,
+            first_layer: 0,
+            layer_count: 1,
+        }
+    }
+}
+
+/// Parameters of a single draw read by `draw_indirect` family of commands.
+/// Layout matches `VkDrawIndirectCommand`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(C)]
+pub struct DrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+unsafe impl Zeroable for DrawIndirectCommand {}
+unsafe impl Pod for DrawIndirectCommand {}
+
+/// Parameters of a single indexed draw read by `draw_indexed_indirect`
+/// family of commands.
+/// Layout matches `VkDrawIndexedIndirectCommand`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(C)]
+pub struct DrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
+
+unsafe impl Zeroable for DrawIndexedIndirectCommand {}
+unsafe impl Pod for DrawIndexedIndirectCommand {}
+
+/// Parameters of a dispatch read by `dispatch_indirect` command.
+/// Layout matches `VkDispatchIndirectCommand`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(C)]
+pub struct DispatchIndirectCommand {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+unsafe impl Zeroable for DispatchIndirectCommand {}
+unsafe impl Pod for DispatchIndirectCommand {}
+
 #[derive(Debug)]
 pub enum Command<'a> {
     BeginRenderPass {
         framebuffer: &'a Framebuffer,
         clears: &'a [ClearValue],
+        secondary: bool,
+    },
+    NextSubpass {
+        secondary: bool,
     },
     EndRenderPass,
 
+    ExecuteCommands {
+        cbufs: &'a mut [Option<CommandBuffer>],
+    },
+
     BindGraphicsPipeline {
         pipeline: &'a GraphicsPipeline,
     },
@@ -115,12 +226,70 @@ pub enum Command<'a> {
         instances: Range<u32>,
     },
 
+    DrawIndirect {
+        buffer: &'a Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+
+    DrawIndexedIndirect {
+        buffer: &'a Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+
+    DrawIndirectCount {
+        buffer: &'a Buffer,
+        offset: u64,
+        count_buffer: &'a Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    },
+
+    DrawIndexedIndirectCount {
+        buffer: &'a Buffer,
+        offset: u64,
+        count_buffer: &'a Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    },
+
     UpdateBuffer {
         buffer: &'a Buffer,
         offset: u64,
         data: &'a [u8],
     },
 
+    FillBuffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: u64,
+        data: u32,
+    },
+
+    ClearColorImage {
+        image: &'a Image,
+        layout: Layout,
+        color: ClearColor,
+        ranges: &'a [SubresourceRange],
+    },
+
+    ClearDepthStencilImage {
+        image: &'a Image,
+        layout: Layout,
+        depth_stencil: ClearDepthStencil,
+        ranges: &'a [SubresourceRange],
+    },
+
+    ClearAttachments {
+        attachments: &'a [ClearAttachment],
+        rects: &'a [ClearRect],
+    },
+
     BindVertexBuffers {
         first: u32,
         buffers: &'a [(&'a Buffer, u64)],
@@ -155,6 +324,14 @@ pub enum Command<'a> {
         regions: &'a [ImageCopy],
     },
 
+    ResolveImage {
+        src_image: &'a Image,
+        src_layout: Layout,
+        dst_image: &'a Image,
+        dst_layout: Layout,
+        regions: &'a [ImageResolve],
+    },
+
     CopyBufferImage {
         src_buffer: &'a Buffer,
         dst_image: &'a Image,
@@ -162,6 +339,13 @@ pub enum Command<'a> {
         regions: &'a [BufferImageCopy],
     },
 
+    CopyImageBuffer {
+        src_image: &'a Image,
+        src_layout: Layout,
+        dst_buffer: &'a Buffer,
+        regions: &'a [BufferImageCopy],
+    },
+
     BlitImage {
         src_image: &'a Image,
         src_layout: Layout,
@@ -191,6 +375,54 @@ pub enum Command<'a> {
         y: u32,
         z: u32,
     },
+
+    DispatchIndirect {
+        buffer: &'a Buffer,
+        offset: u64,
+    },
+
+    ResetQueryPool {
+        pool: &'a QueryPool,
+        queries: Range<u32>,
+    },
+
+    BeginQuery {
+        pool: &'a QueryPool,
+        query: u32,
+        precise: bool,
+    },
+
+    EndQuery {
+        pool: &'a QueryPool,
+        query: u32,
+    },
+
+    WriteTimestamp {
+        pool: &'a QueryPool,
+        query: u32,
+        stage: PipelineStageFlags,
+    },
+
+    PushDebugGroup {
+        name: &'a CStr,
+        color: [f32; 4],
+    },
+
+    PopDebugGroup,
+
+    InsertDebugLabel {
+        name: &'a CStr,
+        color: [f32; 4],
+    },
+
+    CopyQueryPoolResults {
+        pool: &'a QueryPool,
+        queries: Range<u32>,
+        buffer: &'a Buffer,
+        offset: u64,
+        stride: u64,
+        flags: QueryResultFlags,
+    },
 }
 
 #[derive(Debug)]
@@ -403,6 +635,94 @@ impl<'a> EncoderCommon<'a> {
             },
         );
     }
+
+    /// Opens labeled group of commands shown in debugging tools.
+    /// Each group must be closed with `pop_debug_group`.
+    ///
+    /// Zero `color` means no color.
+    pub fn push_debug_group(&mut self, name: &str, color: [f32; 4]) {
+        let name = debug_label(self.scope, name);
+
+        self.commands
+            .push(self.scope, Command::PushDebugGroup { name, color });
+    }
+
+    /// Closes group opened by last `push_debug_group`.
+    pub fn pop_debug_group(&mut self) {
+        self.commands.push(self.scope, Command::PopDebugGroup);
+    }
+
+    /// Inserts single label shown in debugging tools between commands.
+    ///
+    /// Zero `color` means no color.
+    pub fn insert_debug_label(&mut self, name: &str, color: [f32; 4]) {
+        let name = debug_label(self.scope, name);
+
+        self.commands
+            .push(self.scope, Command::InsertDebugLabel { name, color });
+    }
+
+    /// Begins occlusion or pipeline statistics query.
+    ///
+    /// With `precise` set, occlusion query counts exact number of samples passed.
+    /// This requires `Feature::OcclusionQueryPrecise`.
+    pub fn begin_query(&mut self, pool: &'a QueryPool, query: u32, precise: bool) {
+        assert!(
+            query < pool.info().count,
+            "Query index is out of pool bounds"
+        );
+        assert_ne!(
+            pool.info().ty,
+            QueryType::Timestamp,
+            "Timestamp queries are written with `write_timestamp`"
+        );
+        assert!(
+            !precise || pool.info().ty == QueryType::Occlusion,
+            "Only occlusion query can be precise"
+        );
+
+        self.commands.push(
+            self.scope,
+            Command::BeginQuery {
+                pool,
+                query,
+                precise,
+            },
+        );
+    }
+
+    /// Ends query previously started with `begin_query`.
+    pub fn end_query(&mut self, pool: &'a QueryPool, query: u32) {
+        assert!(
+            query < pool.info().count,
+            "Query index is out of pool bounds"
+        );
+
+        self.commands
+            .push(self.scope, Command::EndQuery { pool, query });
+    }
+
+    /// Writes device timestamp into query once all previously submitted
+    /// commands reach specified pipeline `stage`.
+    pub fn write_timestamp(&mut self, pool: &'a QueryPool, query: u32, stage: PipelineStageFlags) {
+        assert!(
+            query < pool.info().count,
+            "Query index is out of pool bounds"
+        );
+        assert_eq!(
+            pool.info().ty,
+            QueryType::Timestamp,
+            "Timestamp can be written only to timestamp query pool"
+        );
+        assert_eq!(
+            stage.bits().count_ones(),
+            1,
+            "Exactly one pipeline stage must be specified for timestamp"
+        );
+
+        self.commands
+            .push(self.scope, Command::WriteTimestamp { pool, query, stage });
+    }
 }
 
 /// Command encoder that can encode commands outside render pass.
@@ -455,6 +775,31 @@ impl<'a> Encoder<'a> {
         &mut self,
         framebuffer: &'a Framebuffer,
         clears: &'a [ClearValue],
+    ) -> RenderPassEncoder<'_, 'a> {
+        self.begin_render_pass(framebuffer, clears, SubpassContents::Inline)
+    }
+
+    /// Begins render pass which contents are recorded into secondary command
+    /// buffers. Returned `RenderPassEncoder` can only execute secondary command
+    /// buffers created with `CommandPool::create_secondary_encoder`.
+    ///
+    /// `framebuffer` - a framebuffer (set of attachments) for render pass to use.
+    /// `clears` - an array of clear values. render pass will clear attachments
+    ///            with `load_op == LoadOp::Clear` using those values.
+    ///            They will be used in order.
+    pub fn with_framebuffer_secondary(
+        &mut self,
+        framebuffer: &'a Framebuffer,
+        clears: &'a [ClearValue],
+    ) -> RenderPassEncoder<'_, 'a> {
+        self.begin_render_pass(framebuffer, clears, SubpassContents::Secondary)
+    }
+
+    fn begin_render_pass(
+        &mut self,
+        framebuffer: &'a Framebuffer,
+        clears: &'a [ClearValue],
+        contents: SubpassContents,
     ) -> RenderPassEncoder<'_, 'a> {
         assert!(self.inner.capabilities.supports_graphics());
 
@@ -463,6 +808,7 @@ impl<'a> Encoder<'a> {
             Command::BeginRenderPass {
                 framebuffer,
                 clears,
+                secondary: contents == SubpassContents::Secondary,
             },
         );
 
@@ -471,6 +817,7 @@ impl<'a> Encoder<'a> {
             render_pass: &framebuffer.info().render_pass,
             inner: &mut self.inner,
             subpass: 0,
+            contents,
         }
     }
 
@@ -531,6 +878,166 @@ impl<'a> Encoder<'a> {
         self.update_buffer(buffer, offset, data);
     }
 
+    /// Fills buffer range with repeated 4 byte `data` value.
+    ///
+    /// Both `offset` and `size` must be multiple of 4.
+    pub fn fill_buffer(&mut self, buffer: &'a Buffer, offset: u64, size: u64, data: u32) {
+        assert!(
+            buffer.info().usage.contains(BufferUsage::TRANSFER_DST),
+            "Buffer must be created with `TRANSFER_DST` usage to be filled"
+        );
+        assert_eq!(offset % 4, 0, "Fill offset must be multiple of 4");
+        assert_eq!(size % 4, 0, "Fill size must be multiple of 4");
+
+        debug_assert!(
+            matches!(offset.checked_add(size), Some(end) if end <= buffer.info().size),
+            "Fill range is out of buffer bounds"
+        );
+
+        if size == 0 {
+            return;
+        }
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::FillBuffer {
+                buffer,
+                offset,
+                size,
+                data,
+            },
+        )
+    }
+
+    /// Resets range of queries in the pool to unavailable state.
+    ///
+    /// Queries must be reset before use.
+    pub fn reset_query_pool(&mut self, pool: &'a QueryPool, queries: Range<u32>) {
+        assert!(
+            queries.start <= queries.end && queries.end <= pool.info().count,
+            "Query range is out of pool bounds"
+        );
+
+        self.inner
+            .commands
+            .push(self.inner.scope, Command::ResetQueryPool { pool, queries });
+    }
+
+    /// Copies results of queries in range into `buffer`.
+    ///
+    /// Results are written as `u64` values, `stride` bytes apart per query.
+    /// Timestamp values are written in ticks, see `Device::timestamp_period`.
+    pub fn copy_query_pool_results(
+        &mut self,
+        pool: &'a QueryPool,
+        queries: Range<u32>,
+        buffer: &'a Buffer,
+        offset: u64,
+        stride: u64,
+        flags: QueryResultFlags,
+    ) {
+        assert!(
+            queries.start <= queries.end && queries.end <= pool.info().count,
+            "Query range is out of pool bounds"
+        );
+        assert!(
+            buffer.info().usage.contains(BufferUsage::TRANSFER_DST),
+            "Buffer must be created with `TRANSFER_DST` usage to receive query results"
+        );
+        assert_eq!(offset % 8, 0, "Query results offset must be multiple of 8");
+        assert_eq!(stride % 8, 0, "Query results stride must be multiple of 8");
+
+        let values = u64::from(pool.info().ty.values_per_query())
+            + flags.contains(QueryResultFlags::WITH_AVAILABILITY) as u64;
+        assert!(
+            stride >= values * 8,
+            "Query results stride is too small to fit all values"
+        );
+
+        if queries.start == queries.end {
+            return;
+        }
+
+        debug_assert!(
+            matches!(
+                offset.checked_add(stride * u64::from(queries.end - queries.start - 1) + values * 8),
+                Some(end) if end <= buffer.info().size
+            ),
+            "Query results are out of buffer bounds"
+        );
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::CopyQueryPoolResults {
+                pool,
+                queries,
+                buffer,
+                offset,
+                stride,
+                flags,
+            },
+        );
+    }
+
+    /// Clears color image subresources outside of render pass.
+    ///
+    /// `layout` must be either `General` or `TransferDstOptimal`.
+    pub fn clear_color_image(
+        &mut self,
+        image: &'a Image,
+        layout: Layout,
+        color: ClearColor,
+        ranges: &'a [SubresourceRange],
+    ) {
+        assert!(
+            self.inner.capabilities.supports_graphics()
+                || self.inner.capabilities.supports_compute()
+        );
+        assert_clear_image(image, layout);
+        assert!(
+            image.info().format.is_color(),
+            "Attempt to clear depth-stencil image with color value"
+        );
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::ClearColorImage {
+                image,
+                layout,
+                color,
+                ranges,
+            },
+        )
+    }
+
+    /// Clears depth-stencil image subresources outside of render pass.
+    ///
+    /// `layout` must be either `General` or `TransferDstOptimal`.
+    pub fn clear_depth_stencil_image(
+        &mut self,
+        image: &'a Image,
+        layout: Layout,
+        depth_stencil: ClearDepthStencil,
+        ranges: &'a [SubresourceRange],
+    ) {
+        assert!(self.inner.capabilities.supports_graphics());
+        assert_clear_image(image, layout);
+        assert!(
+            image.info().format.is_depth() || image.info().format.is_stencil(),
+            "Attempt to clear color image with depth-stencil value"
+        );
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::ClearDepthStencilImage {
+                image,
+                layout,
+                depth_stencil,
+                ranges,
+            },
+        )
+    }
+
     /// Builds acceleration structures.
     pub fn build_acceleration_structure(
         &mut self,
@@ -627,6 +1134,38 @@ impl<'a> Encoder<'a> {
         )
     }
 
+    /// Resolves regions of multisampled image into single-sampled image.
+    pub fn resolve_image(
+        &mut self,
+        src_image: &'a Image,
+        src_layout: Layout,
+        dst_image: &'a Image,
+        dst_layout: Layout,
+        regions: &'a [ImageResolve],
+    ) {
+        assert_ne!(
+            src_image.info().samples,
+            Samples::Samples1,
+            "Source image of resolve must be multisampled"
+        );
+        assert_eq!(
+            dst_image.info().samples,
+            Samples::Samples1,
+            "Destination image of resolve must be single-sampled"
+        );
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::ResolveImage {
+                src_image,
+                src_layout,
+                dst_image,
+                dst_layout,
+                regions,
+            },
+        )
+    }
+
     pub fn copy_buffer_to_image(
         &mut self,
         src_buffer: &'a Buffer,
@@ -645,6 +1184,24 @@ impl<'a> Encoder<'a> {
         )
     }
 
+    pub fn copy_image_to_buffer(
+        &mut self,
+        src_image: &'a Image,
+        src_layout: Layout,
+        dst_buffer: &'a Buffer,
+        regions: &'a [BufferImageCopy],
+    ) {
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::CopyImageBuffer {
+                src_image,
+                src_layout,
+                dst_buffer,
+                regions,
+            },
+        )
+    }
+
     pub fn blit_image(
         &mut self,
         src_image: &'a Image,
@@ -677,6 +1234,18 @@ impl<'a> Encoder<'a> {
             .push(self.inner.scope, Command::Dispatch { x, y, z });
     }
 
+    /// Dispatches compute work with group counts read from `buffer`
+    /// at `offset` as `DispatchIndirectCommand`.
+    pub fn dispatch_indirect(&mut self, buffer: &'a Buffer, offset: u64) {
+        assert!(self.inner.capabilities.supports_compute());
+        assert_indirect_buffer(buffer, offset, size_of::<DispatchIndirectCommand>() as u64);
+
+        self.inner.commands.push(
+            self.inner.scope,
+            Command::DispatchIndirect { buffer, offset },
+        );
+    }
+
     pub fn memory_barrier(
         &mut self,
         src: PipelineStageFlags,
@@ -746,6 +1315,71 @@ impl<'a> Encoder<'a> {
     }
 }
 
+/// Command encoder that can encode commands into secondary command buffer
+/// that continues single subpass of a render pass.
+#[derive(Debug)]
+pub struct SecondaryEncoder<'a> {
+    inner: EncoderCommon<'a>,
+    command_buffer: CommandBuffer,
+    framebuffer: &'a Framebuffer,
+    subpass: u32,
+}
+
+impl<'a> SecondaryEncoder<'a> {
+    pub(crate) fn new(
+        command_buffer: CommandBuffer,
+        capabilities: QueueCapabilityFlags,
+        framebuffer: &'a Framebuffer,
+        subpass: u32,
+        scope: &'a Scope<'a>,
+    ) -> Self {
+        SecondaryEncoder {
+            inner: EncoderCommon {
+                capabilities,
+                commands: Commands::new(scope),
+                scope,
+            },
+            command_buffer,
+            framebuffer,
+            subpass,
+        }
+    }
+
+    /// Returns `RenderPassEncoder` to encode commands of the inherited subpass.
+    pub fn render_pass_encoder(&mut self) -> RenderPassEncoder<'_, 'a> {
+        RenderPassEncoder {
+            framebuffer: self.framebuffer,
+            render_pass: &self.framebuffer.info().render_pass,
+            subpass: self.subpass,
+            inner: &mut self.inner,
+            contents: SubpassContents::Nested,
+        }
+    }
+
+    /// Flushes commands recorded into this encoder to the underlying
+    /// secondary command buffer.
+    pub fn finish(mut self) -> CommandBuffer {
+        self.command_buffer
+            .write(self.inner.commands.drain(), self.inner.scope)
+            .expect("TODO: Handle command buffer writing error");
+
+        self.command_buffer
+    }
+}
+
+/// How commands of the current subpass are provided.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SubpassContents {
+    /// Commands are encoded directly.
+    Inline,
+
+    /// Only secondary command buffers are executed.
+    Secondary,
+
+    /// Encoding into secondary command buffer that continues render pass.
+    Nested,
+}
+
 /// Command encoder that can encode commands inside render pass.
 #[derive(Debug)]
 pub struct RenderPassEncoder<'a, 'b> {
@@ -753,6 +1387,7 @@ pub struct RenderPassEncoder<'a, 'b> {
     render_pass: &'b RenderPass,
     subpass: u32,
     inner: &'a mut EncoderCommon<'b>,
+    contents: SubpassContents,
 }
 
 impl<'a, 'b> RenderPassEncoder<'a, 'b> {
@@ -764,7 +1399,59 @@ impl<'a, 'b> RenderPassEncoder<'a, 'b> {
         self.framebuffer
     }
 
+    /// Returns index of the current subpass.
+    pub fn subpass(&self) -> u32 {
+        self.subpass
+    }
+
+    /// Ends current subpass and starts next subpass of the render pass.
+    ///
+    /// Contents of the next subpass are provided the same way as
+    /// for the first one.
+    pub fn next_subpass(&mut self) {
+        assert_ne!(
+            self.contents,
+            SubpassContents::Nested,
+            "Secondary command buffer cannot switch subpasses"
+        );
+
+        assert!(
+            (self.subpass as usize + 1) < self.render_pass.info().subpasses.len(),
+            "Render pass has no more subpasses"
+        );
+
+        self.subpass += 1;
+        self.inner.commands.push(
+            self.scope,
+            Command::NextSubpass {
+                secondary: self.contents == SubpassContents::Secondary,
+            },
+        );
+    }
+
+    /// Executes secondary command buffers recorded for current subpass.
+    ///
+    /// Render pass must be started with `Encoder::with_framebuffer_secondary`.
+    pub fn execute_commands(&mut self, cbufs: impl IntoIterator<Item = CommandBuffer>) {
+        assert_eq!(
+            self.contents,
+            SubpassContents::Secondary,
+            "Render pass must be started with `Encoder::with_framebuffer_secondary`"
+        );
+
+        let cbufs: SmallVec<[_; 8]> = cbufs.into_iter().map(Some).collect();
+        if cbufs.is_empty() {
+            return;
+        }
+
+        let cbufs = self.scope.to_scope_from_iter(cbufs);
+        self.inner
+            .commands
+            .push(self.scope, Command::ExecuteCommands { cbufs });
+    }
+
     pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.assert_not_secondary();
         self.inner.commands.push(
             self.scope,
             Command::Draw {
@@ -775,6 +1462,7 @@ impl<'a, 'b> RenderPassEncoder<'a, 'b> {
     }
 
     pub fn draw_indexed(&mut self, indices: Range<u32>, vertex_offset: i32, instances: Range<u32>) {
+        self.assert_not_secondary();
         self.inner.commands.push(
             self.scope,
             Command::DrawIndexed {
@@ -785,11 +1473,146 @@ impl<'a, 'b> RenderPassEncoder<'a, 'b> {
         );
     }
 
+    /// Draws `draw_count` times with parameters read from `buffer`
+    /// starting at `offset` as `DrawIndirectCommand`s spaced by `stride`.
+    ///
+    /// `draw_count` greater than 1 requires `Feature::MultiDrawIndirect` to be enabled.
+    pub fn draw_indirect(&mut self, buffer: &'b Buffer, offset: u64, draw_count: u32, stride: u32) {
+        self.assert_not_secondary();
+        assert_indirect_stride::<DrawIndirectCommand>(draw_count, stride);
+        assert_indirect_buffer(
+            buffer,
+            offset,
+            indirect_size::<DrawIndirectCommand>(draw_count, stride),
+        );
+
+        self.inner.commands.push(
+            self.scope,
+            Command::DrawIndirect {
+                buffer,
+                offset,
+                draw_count,
+                stride,
+            },
+        );
+    }
+
+    /// Draws `draw_count` times with parameters read from `buffer`
+    /// starting at `offset` as `DrawIndexedIndirectCommand`s spaced by `stride`.
+    ///
+    /// `draw_count` greater than 1 requires `Feature::MultiDrawIndirect` to be enabled.
+    pub fn draw_indexed_indirect(
+        &mut self,
+        buffer: &'b Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        self.assert_not_secondary();
+        assert_indirect_stride::<DrawIndexedIndirectCommand>(draw_count, stride);
+        assert_indirect_buffer(
+            buffer,
+            offset,
+            indirect_size::<DrawIndexedIndirectCommand>(draw_count, stride),
+        );
+
+        self.inner.commands.push(
+            self.scope,
+            Command::DrawIndexedIndirect {
+                buffer,
+                offset,
+                draw_count,
+                stride,
+            },
+        );
+    }
+
+    /// Same as `draw_indirect` but number of draws is read from
+    /// `count_buffer` at `count_buffer_offset` as `u32`
+    /// and clamped to `max_draw_count`.
+    ///
+    /// Requires `Feature::DrawIndirectCount` to be enabled.
+    pub fn draw_indirect_count(
+        &mut self,
+        buffer: &'b Buffer,
+        offset: u64,
+        count_buffer: &'b Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        self.assert_not_secondary();
+        assert_indirect_stride::<DrawIndirectCommand>(max_draw_count, stride);
+        assert_indirect_buffer(buffer, offset, 0);
+        assert_indirect_buffer(count_buffer, count_buffer_offset, 4);
+
+        self.inner.commands.push(
+            self.scope,
+            Command::DrawIndirectCount {
+                buffer,
+                offset,
+                count_buffer,
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            },
+        );
+    }
+
+    /// Same as `draw_indexed_indirect` but number of draws is read from
+    /// `count_buffer` at `count_buffer_offset` as `u32`
+    /// and clamped to `max_draw_count`.
+    ///
+    /// Requires `Feature::DrawIndirectCount` to be enabled.
+    pub fn draw_indexed_indirect_count(
+        &mut self,
+        buffer: &'b Buffer,
+        offset: u64,
+        count_buffer: &'b Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        self.assert_not_secondary();
+        assert_indirect_stride::<DrawIndexedIndirectCommand>(max_draw_count, stride);
+        assert_indirect_buffer(buffer, offset, 0);
+        assert_indirect_buffer(count_buffer, count_buffer_offset, 4);
+
+        self.inner.commands.push(
+            self.scope,
+            Command::DrawIndexedIndirectCount {
+                buffer,
+                offset,
+                count_buffer,
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            },
+        );
+    }
+
+    /// Clears regions of current subpass attachments.
+    pub fn clear_attachments(
+        &mut self,
+        attachments: &'b [ClearAttachment],
+        rects: &'b [ClearRect],
+    ) {
+        self.assert_not_secondary();
+        if attachments.is_empty() || rects.is_empty() {
+            return;
+        }
+
+        self.inner
+            .commands
+            .push(self.scope, Command::ClearAttachments { attachments, rects });
+    }
+
     pub fn bind_dynamic_graphics_pipeline(
         &mut self,
         pipeline: &'b mut DynamicGraphicsPipeline,
         device: &Device,
-    ) -> Result<(), OutOfMemory> {
+    ) -> Result<(), CreatePipelineError> {
+        self.assert_not_secondary();
         assert!(self.capabilities.supports_graphics());
 
         let mut set_viewport = false;
@@ -814,11 +1637,22 @@ impl<'a, 'b> RenderPassEncoder<'a, 'b> {
         self.inner.bind_graphics_pipeline(gp);
         Ok(())
     }
+
+    fn assert_not_secondary(&self) {
+        assert_ne!(
+            self.contents,
+            SubpassContents::Secondary,
+            "Render pass started with `Encoder::with_framebuffer_secondary` accepts only secondary command buffers"
+        );
+    }
 }
 
 impl Drop for RenderPassEncoder<'_, '_> {
     fn drop(&mut self) {
-        self.inner.commands.push(self.scope, Command::EndRenderPass);
+        // Secondary command buffer doesn't own the render pass instance.
+        if self.contents != SubpassContents::Nested {
+            self.inner.commands.push(self.scope, Command::EndRenderPass);
+        }
     }
 }
 
@@ -832,6 +1666,58 @@ impl<'a, 'b> std::ops::Deref for RenderPassEncoder<'a, 'b> {
 
 impl<'a, 'b> std::ops::DerefMut for RenderPassEncoder<'a, 'b> {
     fn deref_mut(&mut self) -> &mut EncoderCommon<'b> {
+        self.assert_not_secondary();
         self.inner
     }
 }
+
+/// Copies label into the scope as nul-terminated string.
+fn debug_label<'a>(scope: &'a Scope<'_>, name: &str) -> &'a CStr {
+    let bytes = scope.to_scope_from_iter(name.bytes().chain(once(0)));
+    CStr::from_bytes_with_nul(bytes).expect("Debug label must not contain nul bytes")
+}
+
+fn assert_clear_image(image: &Image, layout: Layout) {
+    assert!(
+        image.info().usage.contains(ImageUsage::TRANSFER_DST),
+        "Image must be created with `TRANSFER_DST` usage to be cleared"
+    );
+    assert!(
+        matches!(layout, Layout::General | Layout::TransferDstOptimal),
+        "Image must be in `General` or `TransferDstOptimal` layout to be cleared"
+    );
+}
+
+fn assert_indirect_buffer(buffer: &Buffer, offset: u64, size: u64) {
+    assert!(
+        buffer.info().usage.contains(BufferUsage::INDIRECT),
+        "Buffers used as indirect arguments must be created with `INDIRECT` usage"
+    );
+    assert_eq!(
+        offset % 4,
+        0,
+        "Indirect buffer offset must be multiple of 4"
+    );
+
+    debug_assert!(
+        matches!(offset.checked_add(size), Some(end) if end <= buffer.info().size),
+        "Indirect commands are out of buffer bounds"
+    );
+}
+
+fn assert_indirect_stride<T>(draw_count: u32, stride: u32) {
+    if draw_count > 1 {
+        assert_eq!(stride % 4, 0, "Indirect stride must be multiple of 4");
+        assert!(
+            arith_le(size_of::<T>(), stride),
+            "Indirect stride must not be less than size of the command"
+        );
+    }
+}
+
+fn indirect_size<T>(draw_count: u32, stride: u32) -> u64 {
+    match draw_count {
+        0 => 0,
+        n => u64::from(n - 1) * u64::from(stride) + size_of::<T>() as u64,
+    }
+}
@@ -1,97 +1,261 @@
+//! Generation of GLSL declarations from Rust types.
+//!
+//! `#[shader_repr]` structures implement [`GlslType`],
+//! `#[descriptors(glsl)]` structures implement [`GlslDescriptors`]
+//! and `#[pipeline(glsl)]` structures implement [`ShaderInclude`],
+//! so declarations in shaders are always in sync with Rust code.
+
 use {
-    crate::{repr::*, shader::ShaderStage},
-    std::{any::TypeId, borrow::Cow, collections::HashSet},
+    crate::{
+        descriptor::{
+            AccelerationStructureDescriptor, CombinedImageSamplerDescriptor,
+            SampledImageDescriptor, SamplerDescriptor, SparseDescriptors, StorageImageDescriptor,
+            StorageTexelBufferDescriptor, UniformTexelBufferDescriptor,
+        },
+        repr::*,
+        shader::{ShaderStage, ShaderStageFlags},
+    },
+    std::{any::TypeId, collections::HashSet, fmt::Write as _},
 };
 
-/// Provides shader input
+/// Provides generated code to include into GLSL shaders.
 pub trait ShaderInclude {
+    /// Returns generated declarations for specified shader stage.
     fn glsl(stage: ShaderStage) -> String;
 
+    /// Replaces `#include "sierra_generated.h"` lines in the shader code
+    /// with generated declarations.
     fn include_glsl(stage: ShaderStage, code: &str) -> String {
-        code.lines()
-            .map(|line| {
-                let include_here = line
-                    .strip_prefix("#include \"sierra_generated.h\"")
-                    .is_some();
-
-                if include_here {
-                    Cow::Owned(Self::glsl(stage))
-                } else {
-                    Cow::Borrowed(line)
-                }
-            })
-            .collect()
+        let mut result = String::with_capacity(code.len());
+
+        for line in code.lines() {
+            if line.trim() == "#include \"sierra_generated.h\"" {
+                result.push_str(&Self::glsl(stage));
+            } else {
+                result.push_str(line);
+                result.push('\n');
+            }
+        }
+
+        result
     }
 }
 
+/// Declarations of descriptors in GLSL.
+pub trait GlslDescriptors {
+    /// Declares descriptors accessible from `stage` as bound to `set`.
+    ///
+    /// `name` is used for bindings that has no name on their own.
+    fn glsl_declarations(stage: ShaderStage, set: u32, name: &str, ctx: &mut GlslTypeContext);
+}
+
+/// Collects definitions of types and declarations of
+/// resources for GLSL shader.
+#[derive(Debug, Default)]
 pub struct GlslTypeContext {
     generated: HashSet<TypeId>,
-    code: String,
+    types: String,
+    declarations: String,
 }
 
 impl GlslTypeContext {
     pub fn new() -> Self {
-        GlslTypeContext {
-            generated: HashSet::new(),
-            code: String::new(),
-        }
+        GlslTypeContext::default()
     }
 
-    pub fn add<T, U>(&mut self)
+    /// Adds definition of the type and all types it depends on.
+    /// Each type is defined only once.
+    pub fn add<T>(&mut self)
     where
-        T: GlslType,
-        U: Into<T>,
+        T: GlslType + ?Sized,
     {
         if self.generated.insert(TypeId::of::<T>()) {
             T::deps(self);
-            self.code += "\n";
-            self.code += &T::def();
+            let def = T::def();
+            if !def.is_empty() {
+                self.types.push_str(&def);
+                self.types.push('\n');
+            }
         }
     }
 
+    /// Declares opaque descriptor, e.g. sampler or image.
+    pub fn declare_opaque(&mut self, set: u32, binding: u32, count: u32, ty: &str, name: &str) {
+        writeln!(
+            self.declarations,
+            "layout(set = {}, binding = {}) uniform {} {}{};",
+            set,
+            binding,
+            ty,
+            name,
+            array_suffix(count),
+        )
+        .unwrap();
+    }
+
+    /// Declares storage image or storage texel buffer with format layout qualifier.
+    ///
+    /// Without format the image can be accessed only if
+    /// `shaderStorageImageReadWithoutFormat` and `shaderStorageImageWriteWithoutFormat`
+    /// features are enabled.
+    pub fn declare_storage_image(
+        &mut self,
+        set: u32,
+        binding: u32,
+        count: u32,
+        format: Option<&str>,
+        ty: &str,
+        name: &str,
+    ) {
+        match format {
+            None => self.declare_opaque(set, binding, count, ty, name),
+            Some(format) => writeln!(
+                self.declarations,
+                "layout(set = {}, binding = {}, {}) uniform {} {}{};",
+                set,
+                binding,
+                format,
+                ty,
+                name,
+                array_suffix(count),
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Declares uniform or storage buffer with single member of type `T`.
+    ///
+    /// Types with trailing runtime-sized array are inlined into the block instead,
+    /// and the block is named after the buffer.
+    pub fn declare_buffer<T>(
+        &mut self,
+        set: u32,
+        binding: u32,
+        count: u32,
+        storage: bool,
+        name: &str,
+    ) where
+        T: GlslType + ?Sized,
+    {
+        self.add::<T>();
+
+        let (layout, qualifier) = if storage {
+            ("std430", "buffer")
+        } else {
+            ("std140", "uniform")
+        };
+
+        let members = T::block_members();
+
+        writeln!(
+            self.declarations,
+            "layout(set = {}, binding = {}, {}) {} {}_block {{",
+            set, binding, layout, qualifier, name,
+        )
+        .unwrap();
+
+        match &members {
+            Some(members) => self.declarations.push_str(members),
+            None => writeln!(
+                self.declarations,
+                "    {} {}{};",
+                T::name(),
+                name,
+                T::suffix()
+            )
+            .unwrap(),
+        }
+
+        self.declarations.push('}');
+
+        if count == 1 {
+            if members.is_some() {
+                write!(self.declarations, " {}", name).unwrap();
+            }
+            self.declarations.push_str(";\n");
+        } else {
+            writeln!(
+                self.declarations,
+                " {}_blocks{};",
+                name,
+                array_suffix(count)
+            )
+            .unwrap();
+        }
+    }
+
+    /// Declares block with specified members.
+    /// Each member is added with [`GlslTypeContext::block_member`].
+    pub fn declare_block(&mut self, layout: &str, qualifier: &str, name: &str, members: &str) {
+        writeln!(
+            self.declarations,
+            "layout({}) {} {} {{\n{}}};",
+            layout, qualifier, name, members
+        )
+        .unwrap();
+    }
+
+    /// Returns declaration of block member of type `T`.
+    pub fn block_member<T>(&mut self, name: &str) -> String
+    where
+        T: GlslType + ?Sized,
+    {
+        self.add::<T>();
+        format!("    {} {}{};\n", T::name(), name, T::suffix())
+    }
+
+    /// Returns generated code.
+    /// Type definitions are followed by declarations.
     pub fn code(self) -> String {
-        self.code
+        let mut code = self.types;
+        code.push_str(&self.declarations);
+        code
+    }
+}
+
+fn array_suffix(count: u32) -> String {
+    match count {
+        0 => "[]".to_owned(),
+        1 => String::new(),
+        n => format!("[{}]", n),
     }
 }
 
 /// Generates declarations for struct in glsl shaders.
 pub trait GlslType: 'static {
+    /// Name of the type in GLSL.
     fn name() -> &'static str;
 
+    /// Suffix added after field name, e.g. array size.
     fn suffix() -> String {
         String::new()
     }
 
+    /// Adds types this type depends on.
     fn deps(_ctx: &mut GlslTypeContext) {}
 
+    /// Definition of the type. Empty for builtin types.
     fn def() -> String {
         String::new()
     }
+
+    /// Members of the buffer block for structures with trailing runtime-sized array.
+    /// Such structures can't be declared in GLSL and are inlined into the block instead.
+    fn block_members() -> Option<String> {
+        None
+    }
 }
 
 macro_rules! builtin_glsl_type {
-    ($ty:ty as $name:ident $(($($suffix:tt)+))?) => {
+    ($ty:ty as $name:ident) => {
         impl GlslType for $ty {
             fn name() -> &'static str {
                 std::stringify!($name)
             }
-
-            $(
-                fn suffix() -> String {
-                    std::stringify!($($suffix)+).to_owned()
-                }
-            )?
         }
     };
-    ($ty:ty as $name:expr) => {
-        impl GlslType for $ty {
-            fn name() -> &'static str {
-                $name
-            }
-        }
-    };
-    ($($ty:ty as $name:tt $(($($suffix:tt)+))?),* $(,)?) => {
-        $(builtin_glsl_type!($ty as $name $(($($suffix)+))?);)*
+    ($($ty:ty as $name:ident),* $(,)?) => {
+        $(builtin_glsl_type!($ty as $name);)*
     };
 }
 
@@ -100,9 +264,7 @@ builtin_glsl_type!(
     f64 as double,
     i32 as int,
     u32 as uint,
-    i16 as short,
-    u16 as ushort,
-    i8 as char,
+    boolean as bool,
     bool as bool,
 );
 
@@ -115,15 +277,28 @@ where
     }
 
     fn suffix() -> String {
-        "[]".to_owned()
+        format!("[]{}", T::suffix())
     }
 
     fn deps(ctx: &mut GlslTypeContext) {
-        T::deps(ctx)
+        ctx.add::<T>()
     }
+}
 
-    fn def() -> String {
-        String::new()
+impl<T> GlslType for RuntimeArray<T>
+where
+    T: GlslType,
+{
+    fn name() -> &'static str {
+        T::name()
+    }
+
+    fn suffix() -> String {
+        <[T]>::suffix()
+    }
+
+    fn deps(ctx: &mut GlslTypeContext) {
+        ctx.add::<T>()
     }
 }
 
@@ -136,79 +311,89 @@ where
     }
 
     fn suffix() -> String {
-        format!("[{}]", N)
+        format!("[{}]{}", N, T::suffix())
     }
 
     fn deps(ctx: &mut GlslTypeContext) {
-        T::deps(ctx)
+        ctx.add::<T>()
     }
+}
 
-    fn def() -> String {
-        String::new()
-    }
+builtin_glsl_type!(
+    vec2<f32> as vec2,
+    vec3<f32> as vec3,
+    vec4<f32> as vec4,
+    mat2x2<f32> as mat2x2,
+    mat3x2<f32> as mat3x2,
+    mat4x2<f32> as mat4x2,
+    mat2x3<f32> as mat2x3,
+    mat3x3<f32> as mat3x3,
+    mat4x3<f32> as mat4x3,
+    mat2x4<f32> as mat2x4,
+    mat3x4<f32> as mat3x4,
+    mat4x4<f32> as mat4x4,
+);
+
+builtin_glsl_type!(
+    vec2<f64> as dvec2,
+    vec3<f64> as dvec3,
+    vec4<f64> as dvec4,
+    mat2x2<f64> as dmat2x2,
+    mat3x2<f64> as dmat3x2,
+    mat4x2<f64> as dmat4x2,
+    mat2x3<f64> as dmat2x3,
+    mat3x3<f64> as dmat3x3,
+    mat4x3<f64> as dmat4x3,
+    mat2x4<f64> as dmat2x4,
+    mat3x4<f64> as dmat3x4,
+    mat4x4<f64> as dmat4x4,
+);
+
+builtin_glsl_type!(
+    vec2<i32> as ivec2,
+    vec3<i32> as ivec3,
+    vec4<i32> as ivec4,
+    vec2<u32> as uvec2,
+    vec3<u32> as uvec3,
+    vec4<u32> as uvec4,
+    vec2<boolean> as bvec2,
+    vec3<boolean> as bvec3,
+    vec4<boolean> as bvec4,
+);
+
+/// Descriptor types that can be declared in GLSL as opaque uniforms.
+pub trait GlslDescriptorType {
+    /// Name of the opaque type in GLSL.
+    const GLSL_TYPE: &'static str;
+}
+
+macro_rules! glsl_descriptor_type {
+    ($($ty:ty as $name:ident),* $(,)?) => {
+        $(
+            impl GlslDescriptorType for $ty {
+                const GLSL_TYPE: &'static str = std::stringify!($name);
+            }
+        )*
+    };
 }
 
-builtin_glsl_type!(vec2<f32> as vec2);
-builtin_glsl_type!(vec3<f32> as vec3);
-builtin_glsl_type!(vec4<f32> as vec4);
-builtin_glsl_type!(mat2x2<f32> as mat2x2);
-builtin_glsl_type!(mat3x2<f32> as mat3x2);
-builtin_glsl_type!(mat4x2<f32> as mat4x2);
-builtin_glsl_type!(mat2x3<f32> as mat2x3);
-builtin_glsl_type!(mat3x3<f32> as mat3x3);
-builtin_glsl_type!(mat4x3<f32> as mat4x3);
-builtin_glsl_type!(mat2x4<f32> as mat2x4);
-builtin_glsl_type!(mat3x4<f32> as mat3x4);
-builtin_glsl_type!(mat4x4<f32> as mat4x4);
-
-builtin_glsl_type!(vec2<f64> as dvec2);
-builtin_glsl_type!(vec3<f64> as dvec3);
-builtin_glsl_type!(vec4<f64> as dvec4);
-builtin_glsl_type!(mat2x2<f64> as dmat2x2);
-builtin_glsl_type!(mat3x2<f64> as dmat3x2);
-builtin_glsl_type!(mat4x2<f64> as dmat4x2);
-builtin_glsl_type!(mat2x3<f64> as dmat2x3);
-builtin_glsl_type!(mat3x3<f64> as dmat3x3);
-builtin_glsl_type!(mat4x3<f64> as dmat4x3);
-builtin_glsl_type!(mat2x4<f64> as dmat2x4);
-builtin_glsl_type!(mat3x4<f64> as dmat3x4);
-builtin_glsl_type!(mat4x4<f64> as dmat4x4);
-
-builtin_glsl_type!(vec2<i32> as ivec2);
-builtin_glsl_type!(vec3<i32> as ivec3);
-builtin_glsl_type!(vec4<i32> as ivec4);
-builtin_glsl_type!(mat2x2<i32> as imat2x2);
-builtin_glsl_type!(mat3x2<i32> as imat3x2);
-builtin_glsl_type!(mat4x2<i32> as imat4x2);
-builtin_glsl_type!(mat2x3<i32> as imat2x3);
-builtin_glsl_type!(mat3x3<i32> as imat3x3);
-builtin_glsl_type!(mat4x3<i32> as imat4x3);
-builtin_glsl_type!(mat2x4<i32> as imat2x4);
-builtin_glsl_type!(mat3x4<i32> as imat3x4);
-builtin_glsl_type!(mat4x4<i32> as imat4x4);
-
-builtin_glsl_type!(vec2<u32> as uvec2);
-builtin_glsl_type!(vec3<u32> as uvec3);
-builtin_glsl_type!(vec4<u32> as uvec4);
-builtin_glsl_type!(mat2x2<u32> as umat2x2);
-builtin_glsl_type!(mat3x2<u32> as umat3x2);
-builtin_glsl_type!(mat4x2<u32> as umat4x2);
-builtin_glsl_type!(mat2x3<u32> as umat2x3);
-builtin_glsl_type!(mat3x3<u32> as umat3x3);
-builtin_glsl_type!(mat4x3<u32> as umat4x3);
-builtin_glsl_type!(mat2x4<u32> as umat2x4);
-builtin_glsl_type!(mat3x4<u32> as umat3x4);
-builtin_glsl_type!(mat4x4<u32> as umat4x4);
-
-builtin_glsl_type!(vec2<bool> as bvec2);
-builtin_glsl_type!(vec3<bool> as bvec3);
-builtin_glsl_type!(vec4<bool> as bvec4);
-builtin_glsl_type!(mat2x2<bool> as bmat2x2);
-builtin_glsl_type!(mat3x2<bool> as bmat3x2);
-builtin_glsl_type!(mat4x2<bool> as bmat4x2);
-builtin_glsl_type!(mat2x3<bool> as bmat2x3);
-builtin_glsl_type!(mat3x3<bool> as bmat3x3);
-builtin_glsl_type!(mat4x3<bool> as bmat4x3);
-builtin_glsl_type!(mat2x4<bool> as bmat2x4);
-builtin_glsl_type!(mat3x4<bool> as bmat3x4);
-builtin_glsl_type!(mat4x4<bool> as bmat4x4);
+glsl_descriptor_type!(
+    SamplerDescriptor as sampler,
+    CombinedImageSamplerDescriptor as sampler2D,
+    SampledImageDescriptor as texture2D,
+    StorageImageDescriptor as image2D,
+    UniformTexelBufferDescriptor as textureBuffer,
+    StorageTexelBufferDescriptor as imageBuffer,
+    AccelerationStructureDescriptor as accelerationStructureEXT,
+);
+
+impl<T, const CAP: u32, const STAGES: u32> GlslDescriptors for SparseDescriptors<T, CAP, STAGES>
+where
+    T: GlslDescriptorType,
+{
+    fn glsl_declarations(stage: ShaderStage, set: u32, name: &str, ctx: &mut GlslTypeContext) {
+        if ShaderStageFlags::from_bits_truncate(STAGES).contains(stage.into()) {
+            ctx.declare_opaque(set, 0, CAP, T::GLSL_TYPE, name);
+        }
+    }
+}
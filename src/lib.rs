@@ -22,6 +22,8 @@ use std::{
 };
 
 pub mod backend;
+pub mod glsl;
+pub mod wgsl;
 
 mod accel;
 mod access;
@@ -31,12 +33,13 @@ mod encode;
 mod fence;
 mod format;
 mod framebuffer;
-// mod glsl;
 mod image;
 mod memory;
 mod physical;
 mod pipeline;
+mod query;
 mod queue;
+mod reflect;
 mod render_pass;
 mod repr;
 mod sampler;
@@ -50,7 +53,10 @@ mod view;
 pub use self::{
     accel::*,
     access::*,
-    backend::{Device, Graphics},
+    backend::{
+        DebugMessage, DebugMessageCallback, DebugMessageSeverity, DebugMessageType, Device,
+        Graphics, GraphicsConfig, NamedObject,
+    },
     buffer::*,
     descriptor::*,
     encode::*,
@@ -61,7 +67,9 @@ pub use self::{
     memory::*,
     physical::*,
     pipeline::*,
+    query::*,
     queue::*,
+    reflect::*,
     render_pass::*,
     repr::*,
     sampler::*,
@@ -73,7 +81,10 @@ pub use self::{
     view::*,
 };
 
-pub use sierra_proc::{descriptors, graphics_pipeline_desc, pass, pipeline, shader_repr};
+pub use sierra_proc::{
+    descriptors, graphics_pipeline_desc, include_shader, pass, pipeline, shader_descriptors,
+    shader_repr,
+};
 
 /// Re-exporting scoped_arena for code-gen.
 #[doc(hidden)]
@@ -71,6 +71,12 @@ pub enum Feature {
     RuntimeDescriptorArray,
     ScalarBlockLayout,
     SurfacePresentation,
+    DrawIndirectCount,
+    MultiDrawIndirect,
+    PipelineStatisticsQuery,
+    OcclusionQueryPrecise,
+    SampleRateShading,
+    AlphaToOne,
 }
 
 #[allow(dead_code)]
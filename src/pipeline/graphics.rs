@@ -1,11 +1,12 @@
 use {
-    super::PipelineLayout,
+    super::{CreatePipelineError, PipelineLayout},
     crate::{
         format::Format,
+        image::Samples,
         render_pass::RenderPass,
         sampler::CompareOp,
         shader::{FragmentShader, VertexShader},
-        Device, Extent2d, Extent3d, Offset2d, OutOfMemory,
+        Device, Extent2d, Extent3d, Offset2d,
     },
     ordered_float::OrderedFloat,
 };
@@ -322,6 +323,10 @@ pub struct Rasterizer {
     /// `PolygonMode::Fill`.
     pub polygon_mode: PolygonMode,
 
+    /// Multisample rasterization state.
+    /// Sample count must match samples of attachments used by the subpass.
+    pub multisample: Multisample,
+
     /// Depth test and operations.
     pub depth_test: Option<DepthTest>,
 
@@ -353,6 +358,7 @@ impl Rasterizer {
             front_face: FrontFace::Clockwise,
             culling: None,
             polygon_mode: PolygonMode::Fill,
+            multisample: Multisample::new(),
             depth_test: None,
             stencil_tests: None,
             depth_bounds: None,
@@ -380,6 +386,55 @@ impl Rasterizer {
     }
 }
 
+/// Multisample rasterization state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Multisample {
+    /// Number of samples used in rasterization.
+    pub samples: Samples,
+
+    /// Minimum fraction of samples for which fragment shader is invoked.
+    /// If `None` then sample shading is disabled.
+    ///
+    /// If `SampleRateShading` feature is not enabled this value must be `None`.
+    pub sample_shading: Option<OrderedFloat<f32>>,
+
+    /// Derive coverage mask from alpha component of the first color output.
+    pub alpha_to_coverage: bool,
+
+    /// Replace alpha component of the first color output with one.
+    ///
+    /// If `AlphaToOne` feature is not enabled this value must be `false`.
+    pub alpha_to_one: bool,
+}
+
+impl Default for Multisample {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Multisample {
+    pub const fn new() -> Self {
+        Multisample {
+            samples: Samples::Samples1,
+            sample_shading: None,
+            alpha_to_coverage: false,
+            alpha_to_one: false,
+        }
+    }
+
+    /// Multisample state with specified number of samples.
+    pub const fn with_samples(samples: Samples) -> Self {
+        Multisample {
+            samples,
+            sample_shading: None,
+            alpha_to_coverage: false,
+            alpha_to_one: false,
+        }
+    }
+}
+
 /// Polygon front face definition.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
@@ -809,7 +864,7 @@ impl DynamicGraphicsPipeline {
         render_pass: &RenderPass,
         subpass: u32,
         device: &Device,
-    ) -> Result<&GraphicsPipeline, OutOfMemory> {
+    ) -> Result<&GraphicsPipeline, CreatePipelineError> {
         match &mut self.graphics_pipeline {
             Some(graphics_pipeline) => {
                 let mut compatible = true;
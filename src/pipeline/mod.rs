@@ -11,7 +11,26 @@ pub use {
     },
 };
 
-use crate::{descriptor::DescriptorSetLayout, shader::ShaderStageFlags, Device, OutOfMemory};
+use crate::{
+    descriptor::DescriptorSetLayout, reflect::ShaderInterfaceMismatch, shader::ShaderStageFlags,
+    Device, OutOfMemory,
+};
+
+/// Error that may occur during pipeline creation.
+#[derive(Debug, thiserror::Error)]
+pub enum CreatePipelineError {
+    #[error(transparent)]
+    OutOfMemoryError {
+        #[from]
+        source: OutOfMemory,
+    },
+
+    #[error("Pipeline doesn't match shader interface")]
+    ShaderInterfaceMismatch {
+        #[from]
+        source: ShaderInterfaceMismatch,
+    },
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PushConstant {
@@ -0,0 +1,95 @@
+pub use crate::backend::QueryPool;
+
+bitflags::bitflags! {
+    /// Flags to specify set of pipeline statistics counters queried by pipeline statistics query.
+    /// Each set flag contributes one `u64` value per query in order of flag bits.
+    #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PipelineStatisticFlags: u32 {
+        /// Number of vertices processed by input assembly stage.
+        const INPUT_ASSEMBLY_VERTICES = 0x00000001;
+
+        /// Number of primitives processed by input assembly stage.
+        const INPUT_ASSEMBLY_PRIMITIVES = 0x00000002;
+
+        /// Number of vertex shader invocations.
+        const VERTEX_SHADER_INVOCATIONS = 0x00000004;
+
+        /// Number of geometry shader invocations.
+        const GEOMETRY_SHADER_INVOCATIONS = 0x00000008;
+
+        /// Number of primitives generated by geometry shader invocations.
+        const GEOMETRY_SHADER_PRIMITIVES = 0x00000010;
+
+        /// Number of primitives processed by primitive clipping stage.
+        const CLIPPING_INVOCATIONS = 0x00000020;
+
+        /// Number of primitives output by primitive clipping stage.
+        const CLIPPING_PRIMITIVES = 0x00000040;
+
+        /// Number of fragment shader invocations.
+        const FRAGMENT_SHADER_INVOCATIONS = 0x00000080;
+
+        /// Number of patches processed by tessellation control shader.
+        const TESSELLATION_CONTROL_SHADER_PATCHES = 0x00000100;
+
+        /// Number of tessellation evaluation shader invocations.
+        const TESSELLATION_EVALUATION_SHADER_INVOCATIONS = 0x00000200;
+
+        /// Number of compute shader invocations.
+        const COMPUTE_SHADER_INVOCATIONS = 0x00000400;
+    }
+}
+
+/// Kind of queries in the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub enum QueryType {
+    /// Counts samples that pass depth and stencil tests
+    /// between `begin_query` and `end_query`.
+    Occlusion,
+
+    /// Counts pipeline statistics specified by flags
+    /// between `begin_query` and `end_query`.
+    /// Requires `Feature::PipelineStatisticsQuery`.
+    PipelineStatistics(PipelineStatisticFlags),
+
+    /// Records device time at which all previous commands
+    /// reached specified pipeline stage.
+    Timestamp,
+}
+
+impl QueryType {
+    /// Returns number of `u64` values written for each query of this type.
+    pub fn values_per_query(&self) -> u32 {
+        match self {
+            QueryType::Occlusion | QueryType::Timestamp => 1,
+            QueryType::PipelineStatistics(flags) => flags.bits().count_ones(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryPoolInfo {
+    /// Type of all queries in the pool.
+    pub ty: QueryType,
+
+    /// Number of queries in the pool.
+    pub count: u32,
+}
+
+bitflags::bitflags! {
+    /// Flags to control how query results are fetched.
+    #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct QueryResultFlags: u32 {
+        /// Wait for results of all queries to become available.
+        const WAIT = 0x00000001;
+
+        /// Write availability value after results of each query.
+        /// Non-zero value indicates that results are available.
+        const WITH_AVAILABILITY = 0x00000002;
+
+        /// Allow writing partial results for unavailable queries.
+        const PARTIAL = 0x00000004;
+    }
+}
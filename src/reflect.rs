@@ -0,0 +1,777 @@
+use crate::{
+    descriptor::{DescriptorSetLayoutInfo, DescriptorType},
+    pipeline::{PipelineLayoutInfo, PushConstant, VertexInputAttribute},
+    shader::{InvalidShader, ShaderStage, ShaderStageFlags},
+};
+
+/// Information about shader module interface
+/// extracted from the module code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderReflection {
+    /// All entry points of the module.
+    pub entry_points: Vec<EntryPointReflection>,
+}
+
+/// Interface of single entry point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryPointReflection {
+    /// Name of the entry point.
+    pub name: String,
+
+    /// Shader stage of the entry point.
+    pub stage: ShaderStage,
+
+    /// Descriptor bindings used by the entry point.
+    pub bindings: Vec<BindingReflection>,
+
+    /// Size of push constants block used by the entry point.
+    pub push_constants: Option<u32>,
+
+    /// Vertex inputs of vertex shader entry point.
+    pub vertex_inputs: Vec<VertexInputReflection>,
+}
+
+/// Descriptor binding used by shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindingReflection {
+    /// Name of the variable bound to descriptor.
+    pub name: Option<String>,
+
+    /// Descriptor set index.
+    pub set: u32,
+
+    /// Binding index within descriptor set.
+    pub binding: u32,
+
+    /// Type of descriptor expected by shader.
+    pub ty: DescriptorType,
+
+    /// Number of descriptors in the binding.
+    /// Zero for runtime-sized arrays.
+    pub count: u32,
+}
+
+/// Vertex shader input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexInputReflection {
+    /// Name of the input.
+    pub name: Option<String>,
+
+    /// Input location.
+    pub location: u32,
+}
+
+/// Error that occurs when pipeline layout or vertex input
+/// doesn't match shader interface.
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ShaderInterfaceMismatch {
+    #[error("Entry point `{entry}` for stage {stage} is not found in shader module")]
+    MissingEntryPoint { entry: String, stage: ShaderStage },
+
+    #[error("Shader `{entry}` uses binding {binding} of set {set} missing in pipeline layout")]
+    MissingBinding {
+        entry: String,
+        set: u32,
+        binding: u32,
+    },
+
+    #[error("Shader `{entry}` expects {expected:?} at binding {binding} of set {set}, but layout declares {found:?}")]
+    DescriptorTypeMismatch {
+        entry: String,
+        set: u32,
+        binding: u32,
+        expected: DescriptorType,
+        found: DescriptorType,
+    },
+
+    #[error("Shader `{entry}` expects {expected} descriptors at binding {binding} of set {set}, but layout declares {found}")]
+    DescriptorCountMismatch {
+        entry: String,
+        set: u32,
+        binding: u32,
+        expected: u32,
+        found: u32,
+    },
+
+    #[error(
+        "Binding {binding} of set {set} is not accessible from stage {stage} of shader `{entry}`"
+    )]
+    BindingStageMismatch {
+        entry: String,
+        stage: ShaderStage,
+        set: u32,
+        binding: u32,
+    },
+
+    #[error("Shader `{entry}` uses {size} bytes of push constants not covered by pipeline layout for stage {stage}")]
+    PushConstantsMismatch {
+        entry: String,
+        stage: ShaderStage,
+        size: u32,
+    },
+
+    #[error("Vertex shader `{entry}` input at location {location} has no vertex attribute")]
+    MissingVertexAttribute { entry: String, location: u32 },
+}
+
+impl ShaderReflection {
+    /// Returns entry point with specified name and stage.
+    pub fn entry_point(&self, name: &str, stage: ShaderStage) -> Option<&EntryPointReflection> {
+        self.entry_points
+            .iter()
+            .find(|entry| entry.name == name && entry.stage == stage)
+    }
+
+    /// Validates SPIR-V code with naga and extracts reflection.
+    ///
+    /// Returns `Ok(None)` if code uses capabilities, extensions, instructions
+    /// or types that naga's SPIR-V front-end doesn't support.
+    /// Such modules can be neither validated nor reflected.
+    pub fn from_spirv(code: &[u8]) -> Result<Option<Self>, InvalidShader> {
+        let module = match parse_spirv(code) {
+            Ok(module) => module,
+            Err(err) if is_unsupported(&err) => {
+                tracing::warn!("SPIR-V is not validated: {}", err);
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(InvalidShader::SpirvParseError {
+                    reason: err.to_string(),
+                })
+            }
+        };
+
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|err| InvalidShader::ValidationError {
+            reason: err.to_string(),
+        })?;
+
+        Ok(ShaderReflection::from_naga(&module, &info))
+    }
+
+    /// Extracts reflection from SPIR-V code without validating it.
+    /// Returns `None` if code cannot be parsed.
+    pub(crate) fn from_spirv_unchecked(code: &[u8]) -> Option<Self> {
+        let module = parse_spirv(code).ok()?;
+
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::empty(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .ok()?;
+
+        ShaderReflection::from_naga(&module, &info)
+    }
+
+    /// Extracts reflection from naga module.
+    /// Returns `None` if type layouts of the module cannot be computed.
+    pub(crate) fn from_naga(module: &naga::Module, info: &naga::valid::ModuleInfo) -> Option<Self> {
+        let mut layouter = naga::proc::Layouter::default();
+        layouter.update(&module.types, &module.constants).ok()?;
+
+        let entry_points = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .map(|(index, entry_point)| {
+                let function_info = info.get_entry_point(index);
+
+                let mut bindings = Vec::new();
+                let mut push_constants = None;
+
+                for (handle, var) in module.global_variables.iter() {
+                    if function_info[handle].is_empty() {
+                        continue;
+                    }
+
+                    if var.class == naga::StorageClass::PushConstant {
+                        push_constants = Some(layouter[var.ty].size);
+                        continue;
+                    }
+
+                    if let Some(binding) = &var.binding {
+                        if let Some((ty, count)) = descriptor_type(module, var) {
+                            bindings.push(BindingReflection {
+                                name: var.name.clone(),
+                                set: binding.group,
+                                binding: binding.binding,
+                                ty,
+                                count,
+                            });
+                        }
+                    }
+                }
+
+                let mut vertex_inputs = Vec::new();
+                if entry_point.stage == naga::ShaderStage::Vertex {
+                    for argument in &entry_point.function.arguments {
+                        collect_vertex_inputs(
+                            module,
+                            argument.name.as_ref(),
+                            argument.binding.as_ref(),
+                            argument.ty,
+                            &mut vertex_inputs,
+                        );
+                    }
+                }
+
+                EntryPointReflection {
+                    name: entry_point.name.clone(),
+                    stage: match entry_point.stage {
+                        naga::ShaderStage::Vertex => ShaderStage::Vertex,
+                        naga::ShaderStage::Fragment => ShaderStage::Fragment,
+                        naga::ShaderStage::Compute => ShaderStage::Compute,
+                    },
+                    bindings,
+                    push_constants,
+                    vertex_inputs,
+                }
+            })
+            .collect();
+
+        Some(ShaderReflection { entry_points })
+    }
+}
+
+impl EntryPointReflection {
+    /// Checks that pipeline layout provides all bindings
+    /// and push constants used by the entry point.
+    pub fn validate_layout(
+        &self,
+        layout: &PipelineLayoutInfo,
+    ) -> Result<(), ShaderInterfaceMismatch> {
+        self.validate_layout_parts(
+            |set| layout.sets.get(set as usize).map(|set| set.info()),
+            &layout.push_constants,
+        )
+    }
+
+    /// Checks entry point against descriptor set layouts
+    /// returned by `sets` and push constant ranges.
+    fn validate_layout_parts<'a>(
+        &self,
+        sets: impl Fn(u32) -> Option<&'a DescriptorSetLayoutInfo>,
+        push_constants: &[PushConstant],
+    ) -> Result<(), ShaderInterfaceMismatch> {
+        let stage_flags = ShaderStageFlags::from(self.stage);
+
+        for reflected in &self.bindings {
+            let binding = sets(reflected.set)
+                .and_then(|set| {
+                    set.bindings
+                        .iter()
+                        .find(|binding| binding.binding == reflected.binding)
+                })
+                .ok_or_else(|| ShaderInterfaceMismatch::MissingBinding {
+                    entry: self.name.clone(),
+                    set: reflected.set,
+                    binding: reflected.binding,
+                })?;
+
+            if !descriptor_type_compatible(reflected.ty, binding.ty) {
+                return Err(ShaderInterfaceMismatch::DescriptorTypeMismatch {
+                    entry: self.name.clone(),
+                    set: reflected.set,
+                    binding: reflected.binding,
+                    expected: reflected.ty,
+                    found: binding.ty,
+                });
+            }
+
+            if binding.count < reflected.count {
+                return Err(ShaderInterfaceMismatch::DescriptorCountMismatch {
+                    entry: self.name.clone(),
+                    set: reflected.set,
+                    binding: reflected.binding,
+                    expected: reflected.count,
+                    found: binding.count,
+                });
+            }
+
+            if !binding.stages.contains(stage_flags) {
+                return Err(ShaderInterfaceMismatch::BindingStageMismatch {
+                    entry: self.name.clone(),
+                    stage: self.stage,
+                    set: reflected.set,
+                    binding: reflected.binding,
+                });
+            }
+        }
+
+        if let Some(size) = self.push_constants {
+            // Each byte used by shader must be covered by a range visible to the stage.
+            let mut covered = 0;
+            while covered < size {
+                let next = push_constants
+                    .iter()
+                    .filter(|range| range.stages.contains(stage_flags))
+                    .filter(|range| range.offset <= covered)
+                    .map(|range| range.offset + range.size)
+                    .max()
+                    .unwrap_or(covered);
+
+                if next <= covered {
+                    return Err(ShaderInterfaceMismatch::PushConstantsMismatch {
+                        entry: self.name.clone(),
+                        stage: self.stage,
+                        size,
+                    });
+                }
+                covered = next;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that vertex attributes provide all vertex inputs of the entry point.
+    pub fn validate_vertex_input(
+        &self,
+        attributes: &[VertexInputAttribute],
+    ) -> Result<(), ShaderInterfaceMismatch> {
+        for input in &self.vertex_inputs {
+            if !attributes
+                .iter()
+                .any(|attribute| attribute.location == input.location)
+            {
+                return Err(ShaderInterfaceMismatch::MissingVertexAttribute {
+                    entry: self.name.clone(),
+                    location: input.location,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_spirv(code: &[u8]) -> Result<naga::Module, naga::front::spv::Error> {
+    naga::front::spv::parse_u8_slice(
+        code,
+        &naga::front::spv::Options {
+            adjust_coordinate_space: false,
+            strict_capabilities: false,
+            flow_graph_dump_prefix: None,
+        },
+    )
+}
+
+/// Checks if SPIR-V can't be parsed because naga doesn't support
+/// some of its features rather than because it is invalid.
+fn is_unsupported(err: &naga::front::spv::Error) -> bool {
+    use naga::front::spv::Error;
+
+    matches!(
+        err,
+        Error::UnknownInstruction(_)
+            | Error::UnknownCapability(_)
+            | Error::UnsupportedInstruction(..)
+            | Error::UnsupportedCapability(_)
+            | Error::UnsupportedExtension(_)
+            | Error::UnsupportedExtSet(_)
+            | Error::UnsupportedExtInstSet(_)
+            | Error::UnsupportedExtInst(_)
+            | Error::UnsupportedType(_)
+            | Error::UnsupportedExecutionModel(_)
+            | Error::UnsupportedExecutionMode(_)
+            | Error::UnsupportedStorageClass(_)
+            | Error::UnsupportedImageDim(_)
+            | Error::UnsupportedImageFormat(_)
+            | Error::UnsupportedBuiltIn(_)
+            | Error::UnsupportedControlFlow(_)
+            | Error::UnsupportedBinaryOperator(_)
+            | Error::UnknownBinaryOperator(_)
+            | Error::UnknownRelationalFunction(_)
+    )
+}
+
+/// Returns descriptor type and count for global variable bound to descriptor.
+fn descriptor_type(
+    module: &naga::Module,
+    var: &naga::GlobalVariable,
+) -> Option<(DescriptorType, u32)> {
+    match var.class {
+        naga::StorageClass::Uniform => Some((DescriptorType::UniformBuffer, 1)),
+        naga::StorageClass::Storage { .. } => Some((DescriptorType::StorageBuffer, 1)),
+        naga::StorageClass::Handle => {
+            let (ty, count) = match &module.types[var.ty].inner {
+                naga::TypeInner::Array { base, size, .. } => {
+                    let count = match *size {
+                        naga::ArraySize::Constant(constant) => {
+                            match module.constants[constant].inner {
+                                naga::ConstantInner::Scalar {
+                                    value: naga::ScalarValue::Uint(value),
+                                    ..
+                                } => value as u32,
+                                naga::ConstantInner::Scalar {
+                                    value: naga::ScalarValue::Sint(value),
+                                    ..
+                                } => value as u32,
+                                _ => return None,
+                            }
+                        }
+                        naga::ArraySize::Dynamic => 0,
+                    };
+                    (*base, count)
+                }
+                _ => (var.ty, 1),
+            };
+
+            let ty = match &module.types[ty].inner {
+                naga::TypeInner::Image {
+                    class: naga::ImageClass::Storage { .. },
+                    ..
+                } => DescriptorType::StorageImage,
+                naga::TypeInner::Image { .. } => DescriptorType::SampledImage,
+                naga::TypeInner::Sampler { .. } => DescriptorType::Sampler,
+                _ => return None,
+            };
+
+            Some((ty, count))
+        }
+        _ => None,
+    }
+}
+
+fn collect_vertex_inputs(
+    module: &naga::Module,
+    name: Option<&String>,
+    binding: Option<&naga::Binding>,
+    ty: naga::Handle<naga::Type>,
+    inputs: &mut Vec<VertexInputReflection>,
+) {
+    match binding {
+        Some(naga::Binding::Location { location, .. }) => {
+            inputs.push(VertexInputReflection {
+                name: name.cloned(),
+                location: *location,
+            });
+        }
+        Some(naga::Binding::BuiltIn(_)) => {}
+        None => {
+            if let naga::TypeInner::Struct { members, .. } = &module.types[ty].inner {
+                for member in members {
+                    collect_vertex_inputs(
+                        module,
+                        member.name.as_ref(),
+                        member.binding.as_ref(),
+                        member.ty,
+                        inputs,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Checks if descriptor of `layout` type can be bound where shader expects `shader` type.
+fn descriptor_type_compatible(shader: DescriptorType, layout: DescriptorType) -> bool {
+    match (shader, layout) {
+        (shader, layout) if shader == layout => true,
+        (DescriptorType::UniformBuffer, DescriptorType::UniformBufferDynamic) => true,
+        (DescriptorType::StorageBuffer, DescriptorType::StorageBufferDynamic) => true,
+        (DescriptorType::SampledImage, DescriptorType::CombinedImageSampler) => true,
+        (DescriptorType::Sampler, DescriptorType::CombinedImageSampler) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            descriptor::{
+                DescriptorBindingFlags, DescriptorSetLayoutBinding, DescriptorSetLayoutFlags,
+            },
+            format::Format,
+            shader::{naga_to_spirv, parse_with_naga, ShaderModuleInfo},
+        },
+    };
+
+    const SHADER: &str = r#"
+[[block]]
+struct Globals {
+    view: mat4x4<f32>;
+};
+
+[[block]]
+struct Push {
+    offset: vec4<f32>;
+    scale: vec4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> globals: Globals;
+
+[[group(0), binding(1)]]
+var albedo: texture_2d<f32>;
+
+[[group(0), binding(2)]]
+var albedo_sampler: sampler;
+
+var<push_constant> push: Push;
+
+[[stage(vertex)]]
+fn vs_main([[location(0)]] position: vec3<f32>, [[location(1)]] uv: vec2<f32>) -> [[builtin(position)]] vec4<f32> {
+    return globals.view * vec4<f32>(position, 1.0) * push.scale + push.offset;
+}
+
+[[stage(fragment)]]
+fn fs_main() -> [[location(0)]] vec4<f32> {
+    return textureSample(albedo, albedo_sampler, vec2<f32>(0.5, 0.5));
+}
+"#;
+
+    fn reflect() -> ShaderReflection {
+        let (module, info) = parse_with_naga(&ShaderModuleInfo::wgsl(SHADER.as_bytes())).unwrap();
+        ShaderReflection::from_naga(&module, &info).unwrap()
+    }
+
+    fn binding(
+        binding: u32,
+        ty: DescriptorType,
+        stages: ShaderStageFlags,
+    ) -> DescriptorSetLayoutBinding {
+        DescriptorSetLayoutBinding {
+            binding,
+            ty,
+            count: 1,
+            stages,
+            flags: DescriptorBindingFlags::empty(),
+        }
+    }
+
+    fn layout() -> DescriptorSetLayoutInfo {
+        DescriptorSetLayoutInfo {
+            bindings: vec![
+                binding(0, DescriptorType::UniformBuffer, ShaderStageFlags::VERTEX),
+                binding(1, DescriptorType::SampledImage, ShaderStageFlags::FRAGMENT),
+                binding(2, DescriptorType::Sampler, ShaderStageFlags::FRAGMENT),
+            ],
+            flags: DescriptorSetLayoutFlags::empty(),
+        }
+    }
+
+    fn push_constant(stages: ShaderStageFlags, offset: u32, size: u32) -> PushConstant {
+        PushConstant {
+            stages,
+            offset,
+            size,
+        }
+    }
+
+    fn validate(
+        entry: &EntryPointReflection,
+        set: &DescriptorSetLayoutInfo,
+        push_constants: &[PushConstant],
+    ) -> Result<(), ShaderInterfaceMismatch> {
+        entry.validate_layout_parts(|index| Some(set).filter(|_| index == 0), push_constants)
+    }
+
+    #[test]
+    fn wgsl_module_is_reflected() {
+        let reflection = reflect();
+
+        let vs = reflection
+            .entry_point("vs_main", ShaderStage::Vertex)
+            .unwrap();
+        assert_eq!(
+            vs.bindings,
+            [BindingReflection {
+                name: Some("globals".to_owned()),
+                set: 0,
+                binding: 0,
+                ty: DescriptorType::UniformBuffer,
+                count: 1,
+            }]
+        );
+        assert_eq!(vs.push_constants, Some(32));
+        assert_eq!(
+            vs.vertex_inputs
+                .iter()
+                .map(|input| input.location)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        let fs = reflection
+            .entry_point("fs_main", ShaderStage::Fragment)
+            .unwrap();
+        assert_eq!(
+            fs.bindings
+                .iter()
+                .map(|binding| (binding.binding, binding.ty))
+                .collect::<Vec<_>>(),
+            [
+                (1, DescriptorType::SampledImage),
+                (2, DescriptorType::Sampler)
+            ]
+        );
+        assert_eq!(fs.push_constants, None);
+        assert!(fs.vertex_inputs.is_empty());
+
+        assert!(reflection
+            .entry_point("vs_main", ShaderStage::Fragment)
+            .is_none());
+    }
+
+    #[test]
+    fn spirv_is_validated_and_reflected() {
+        let (module, info) = parse_with_naga(&ShaderModuleInfo::wgsl(SHADER.as_bytes())).unwrap();
+        let spirv = naga_to_spirv(&module, &info).unwrap();
+
+        assert_eq!(ShaderReflection::from_spirv(&spirv), Ok(Some(reflect())));
+
+        match ShaderReflection::from_spirv(&spirv[..spirv.len() - 4]) {
+            Err(InvalidShader::SpirvParseError { .. }) => {}
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn layout_is_validated() {
+        let reflection = reflect();
+        let vs = reflection
+            .entry_point("vs_main", ShaderStage::Vertex)
+            .unwrap();
+        let fs = reflection
+            .entry_point("fs_main", ShaderStage::Fragment)
+            .unwrap();
+
+        let push_constants = [push_constant(ShaderStageFlags::VERTEX, 0, 32)];
+        assert_eq!(validate(vs, &layout(), &push_constants), Ok(()));
+        assert_eq!(validate(fs, &layout(), &[]), Ok(()));
+
+        let mut set = layout();
+        set.bindings.remove(0);
+        assert_eq!(
+            validate(vs, &set, &push_constants),
+            Err(ShaderInterfaceMismatch::MissingBinding {
+                entry: "vs_main".to_owned(),
+                set: 0,
+                binding: 0,
+            })
+        );
+
+        let mut set = layout();
+        set.bindings[0].ty = DescriptorType::StorageBuffer;
+        assert_eq!(
+            validate(vs, &set, &push_constants),
+            Err(ShaderInterfaceMismatch::DescriptorTypeMismatch {
+                entry: "vs_main".to_owned(),
+                set: 0,
+                binding: 0,
+                expected: DescriptorType::UniformBuffer,
+                found: DescriptorType::StorageBuffer,
+            })
+        );
+
+        let mut set = layout();
+        set.bindings[0].ty = DescriptorType::UniformBufferDynamic;
+        assert_eq!(validate(vs, &set, &push_constants), Ok(()));
+
+        let mut set = layout();
+        set.bindings[1].count = 0;
+        assert_eq!(
+            validate(fs, &set, &[]),
+            Err(ShaderInterfaceMismatch::DescriptorCountMismatch {
+                entry: "fs_main".to_owned(),
+                set: 0,
+                binding: 1,
+                expected: 1,
+                found: 0,
+            })
+        );
+
+        let mut set = layout();
+        set.bindings[2].stages = ShaderStageFlags::VERTEX;
+        assert_eq!(
+            validate(fs, &set, &[]),
+            Err(ShaderInterfaceMismatch::BindingStageMismatch {
+                entry: "fs_main".to_owned(),
+                stage: ShaderStage::Fragment,
+                set: 0,
+                binding: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn push_constants_must_be_covered() {
+        let reflection = reflect();
+        let vs = reflection
+            .entry_point("vs_main", ShaderStage::Vertex)
+            .unwrap();
+
+        let mismatch = Err(ShaderInterfaceMismatch::PushConstantsMismatch {
+            entry: "vs_main".to_owned(),
+            stage: ShaderStage::Vertex,
+            size: 32,
+        });
+
+        assert_eq!(validate(vs, &layout(), &[]), mismatch);
+        assert_eq!(
+            validate(
+                vs,
+                &layout(),
+                &[push_constant(ShaderStageFlags::VERTEX, 0, 16)]
+            ),
+            mismatch
+        );
+        assert_eq!(
+            validate(
+                vs,
+                &layout(),
+                &[
+                    push_constant(ShaderStageFlags::VERTEX, 0, 16),
+                    push_constant(ShaderStageFlags::FRAGMENT, 16, 16),
+                ]
+            ),
+            mismatch
+        );
+        assert_eq!(
+            validate(
+                vs,
+                &layout(),
+                &[
+                    push_constant(ShaderStageFlags::VERTEX, 16, 16),
+                    push_constant(ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT, 0, 16),
+                ]
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn vertex_input_is_validated() {
+        let reflection = reflect();
+        let vs = reflection
+            .entry_point("vs_main", ShaderStage::Vertex)
+            .unwrap();
+
+        let attribute = |location| VertexInputAttribute {
+            location,
+            format: Format::RGB32Sfloat,
+            binding: 0,
+            offset: 0,
+        };
+
+        assert_eq!(
+            vs.validate_vertex_input(&[attribute(0), attribute(1)]),
+            Ok(())
+        );
+        assert_eq!(
+            vs.validate_vertex_input(&[attribute(0), attribute(2)]),
+            Err(ShaderInterfaceMismatch::MissingVertexAttribute {
+                entry: "vs_main".to_owned(),
+                location: 1,
+            })
+        );
+    }
+}
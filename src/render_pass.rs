@@ -137,6 +137,23 @@ pub struct Subpass {
         serde(skip_serializing_if = "Option::is_none", default)
     )]
     pub depth: Option<(u32, Layout)>,
+
+    /// Indices of attachments that are read as input attachments in this
+    /// subpass.
+    #[cfg_attr(
+        feature = "serde-1",
+        serde(skip_serializing_if = "Vec::is_empty", default)
+    )]
+    pub inputs: Vec<(u32, Layout)>,
+
+    /// Indices of attachments into which color attachments are resolved
+    /// at the end of this subpass.
+    /// Must be either empty or have one element for each color attachment.
+    #[cfg_attr(
+        feature = "serde-1",
+        serde(skip_serializing_if = "Vec::is_empty", default)
+    )]
+    pub resolves: Vec<(u32, Layout)>,
 }
 
 /// Defines memory dependency between two subpasses
@@ -244,6 +261,31 @@ pub enum CreateRenderPassError {
         "Subpass {subpass} attachment index {attachment} for depth attachment is out of bounds"
     )]
     DepthAttachmentReferenceOutOfBound { subpass: usize, attachment: u32 },
+
+    #[error(
+        "Subpass {subpass} attachment index {attachment} for input attachment {index} is out of bounds"
+    )]
+    InputAttachmentReferenceOutOfBound {
+        subpass: usize,
+        index: usize,
+        attachment: u32,
+    },
+
+    #[error(
+        "Subpass {subpass} attachment index {attachment} for resolve attachment {index} is out of bounds"
+    )]
+    ResolveAttachmentReferenceOutOfBound {
+        subpass: usize,
+        index: usize,
+        attachment: u32,
+    },
+
+    #[error("Subpass {subpass} has {resolves} resolve attachments for {colors} color attachments")]
+    ResolveAttachmentCountMismatch {
+        subpass: usize,
+        colors: usize,
+        resolves: usize,
+    },
 }
 
 pub trait RenderPassInstance {
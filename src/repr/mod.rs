@@ -5,11 +5,12 @@ mod mat;
 mod native;
 mod pad;
 mod repr;
+mod runtime;
 mod scalar;
 mod vec;
 
 pub use {
-    self::{mat::*, native::*, pad::*, repr::*, scalar::*, vec::*},
+    self::{mat::*, native::*, pad::*, repr::*, runtime::*, scalar::*, vec::*},
     bytemuck::{Pod, Zeroable},
 };
 
@@ -54,3 +54,30 @@ where
         *repr = *self
     }
 }
+
+/// Description of a field in shader representation of a structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderReprField {
+    /// Name of the field.
+    pub name: &'static str,
+
+    /// Offset of the field in bytes.
+    pub offset: usize,
+
+    /// Size of the field representation in bytes.
+    pub size: usize,
+}
+
+/// Structure with shader representation generated by `#[shader_repr]`.
+pub trait ShaderReprStruct<T = Std140>: ShaderRepr<T> {
+    /// Fields of the representation in declaration order.
+    const FIELDS: &'static [ShaderReprField];
+
+    /// Size of the representation in bytes.
+    const SIZE: usize = std::mem::size_of::<<Self as ShaderRepr<T>>::Type>();
+
+    /// Returns fields of the representation in declaration order.
+    fn fields() -> &'static [ShaderReprField] {
+        Self::FIELDS
+    }
+}
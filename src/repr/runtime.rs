@@ -0,0 +1,130 @@
+use {
+    super::{
+        pad::Padded,
+        repr::{ShaderRepr, ShaderReprField, Std430},
+    },
+    bytemuck::Zeroable,
+    std::{
+        iter::FromIterator,
+        mem::size_of,
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// Runtime-sized array as the last field of `#[shader_repr]` struct.
+///
+/// Struct with such field is represented in shader as
+/// structure with trailing runtime-sized array, e.g.
+/// `buffer Particles { uint count; Particle items[]; }`,
+/// and implements [`ShaderReprRuntime`] instead of [`ShaderRepr`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RuntimeArray<T>(pub Vec<T>);
+
+impl<T> RuntimeArray<T> {
+    pub const fn new() -> Self {
+        RuntimeArray(Vec::new())
+    }
+
+    /// Returns stride of array elements in layout `L`.
+    pub fn stride<L>() -> usize
+    where
+        T: ShaderRepr<L>,
+    {
+        size_of::<Padded<T::Type, T::ArrayPadding>>()
+    }
+
+    /// Writes representation of elements in layout `L`
+    /// into `bytes` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `bytes` is too small to fit all elements.
+    pub fn write_repr<L>(&self, bytes: &mut [u8], offset: usize)
+    where
+        T: ShaderRepr<L>,
+    {
+        let stride = Self::stride::<L>();
+
+        for (index, value) in self.0.iter().enumerate() {
+            write_repr_at::<T, L>(value, bytes, offset + index * stride);
+        }
+    }
+}
+
+/// Writes representation of `value` in layout `L` into `bytes` at `offset`.
+///
+/// # Panics
+///
+/// This function panics if representation doesn't fit into `bytes`.
+pub fn write_repr_at<T, L>(value: &T, bytes: &mut [u8], offset: usize)
+where
+    T: ShaderRepr<L> + ?Sized,
+{
+    let mut repr = T::Type::zeroed();
+    value.copy_to_repr(&mut repr);
+    bytes[offset..offset + size_of::<T::Type>()].copy_from_slice(bytemuck::bytes_of(&repr));
+}
+
+impl<T> Deref for RuntimeArray<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for RuntimeArray<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<T>> for RuntimeArray<T> {
+    fn from(values: Vec<T>) -> Self {
+        RuntimeArray(values)
+    }
+}
+
+impl<T> FromIterator<T> for RuntimeArray<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        RuntimeArray(iter.into_iter().collect())
+    }
+}
+
+/// Structure with trailing runtime-sized array
+/// that can be represented in shader storage buffer.
+///
+/// Implemented by `#[shader_repr]` for structs with [`RuntimeArray`] last field.
+pub trait ShaderReprRuntime<T = Std430> {
+    /// Fields of the sized part of the representation in declaration order.
+    const FIELDS: &'static [ShaderReprField];
+
+    /// Offset of the runtime-sized array in bytes.
+    const ARRAY_OFFSET: usize;
+
+    /// Stride of the runtime-sized array elements in bytes.
+    const ARRAY_STRIDE: usize;
+
+    /// Returns number of elements in the runtime-sized array.
+    fn array_len(&self) -> usize;
+
+    /// Writes representation into `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `bytes` is shorter than `repr_size()`.
+    fn write_repr(&self, bytes: &mut [u8]);
+
+    /// Returns size of the representation in bytes.
+    fn repr_size(&self) -> usize {
+        Self::ARRAY_OFFSET + self.array_len() * Self::ARRAY_STRIDE
+    }
+
+    /// Returns representation as bytes
+    /// to be written into storage buffer.
+    fn to_repr_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.repr_size()];
+        self.write_repr(&mut bytes);
+        bytes
+    }
+}
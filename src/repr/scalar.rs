@@ -1,24 +1,63 @@
 use {
-    super::native::ShaderNative,
+    super::{
+        native::ShaderNative,
+        repr::{ShaderRepr, Std140, Std430},
+    },
     bytemuck::{Pod, Zeroable},
 };
 
 /// POD replacement for [`bool`].
+///
+/// Booleans in shader buffers take 4 bytes.
 #[repr(transparent)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct boolean(pub u8);
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct boolean(pub u32);
 
 unsafe impl Zeroable for boolean {}
 unsafe impl Pod for boolean {}
 
 unsafe impl ShaderNative for boolean {
-    const ALIGN_MASK: usize = 0;
-    const ARRAY_PADDING_140: usize = 15;
+    const ALIGN_MASK: usize = 3;
+    const ARRAY_PADDING_140: usize = 12;
     const ARRAY_PADDING_430: usize = 0;
-    type ArrayPadding140 = [u8; 15];
+    type ArrayPadding140 = [u8; 12];
     type ArrayPadding430 = [u8; 0];
 }
 
+impl From<bool> for boolean {
+    fn from(value: bool) -> Self {
+        boolean(value as u32)
+    }
+}
+
+impl From<boolean> for bool {
+    fn from(value: boolean) -> Self {
+        value.0 != 0
+    }
+}
+
+impl ShaderRepr<Std140> for bool {
+    const ALIGN_MASK: usize = <boolean as ShaderNative>::ALIGN_MASK;
+    const ARRAY_PADDING: usize = <boolean as ShaderNative>::ARRAY_PADDING_140;
+    type Type = boolean;
+    type ArrayPadding = <boolean as ShaderNative>::ArrayPadding140;
+
+    fn copy_to_repr(&self, repr: &mut boolean) {
+        *repr = boolean::from(*self)
+    }
+}
+
+impl ShaderRepr<Std430> for bool {
+    const ALIGN_MASK: usize = <boolean as ShaderNative>::ALIGN_MASK;
+    const ARRAY_PADDING: usize = <boolean as ShaderNative>::ARRAY_PADDING_430;
+    type Type = boolean;
+    type ArrayPadding = <boolean as ShaderNative>::ArrayPadding430;
+
+    fn copy_to_repr(&self, repr: &mut boolean) {
+        *repr = boolean::from(*self)
+    }
+}
+
 unsafe impl ShaderNative for i32 {
     const ALIGN_MASK: usize = 3;
     const ARRAY_PADDING_140: usize = 12;
@@ -27,10 +27,10 @@ impl<T: Pod, const N: usize> From<[T; N]> for vec<T, N> {
 pub type vec2<T = f32> = vec<T, 2>;
 
 unsafe impl ShaderNative for vec2<boolean> {
-    const ALIGN_MASK: usize = 1;
-    const ARRAY_PADDING_140: usize = 14;
+    const ALIGN_MASK: usize = 7;
+    const ARRAY_PADDING_140: usize = 8;
     const ARRAY_PADDING_430: usize = 0;
-    type ArrayPadding140 = [u8; 14];
+    type ArrayPadding140 = [u8; 8];
     type ArrayPadding430 = [u8; 0];
 }
 unsafe impl ShaderNative for vec2<i32> {
@@ -66,11 +66,11 @@ unsafe impl ShaderNative for vec2<f64> {
 pub type vec3<T = f32> = vec<T, 3>;
 
 unsafe impl ShaderNative for vec3<boolean> {
-    const ALIGN_MASK: usize = 3;
-    const ARRAY_PADDING_140: usize = 13;
-    const ARRAY_PADDING_430: usize = 1;
-    type ArrayPadding140 = [u8; 13];
-    type ArrayPadding430 = [u8; 1];
+    const ALIGN_MASK: usize = 15;
+    const ARRAY_PADDING_140: usize = 4;
+    const ARRAY_PADDING_430: usize = 4;
+    type ArrayPadding140 = [u8; 4];
+    type ArrayPadding430 = [u8; 4];
 }
 unsafe impl ShaderNative for vec3<i32> {
     const ALIGN_MASK: usize = 15;
@@ -105,10 +105,10 @@ unsafe impl ShaderNative for vec3<f64> {
 pub type vec4<T = f32> = vec<T, 4>;
 
 unsafe impl ShaderNative for vec4<boolean> {
-    const ALIGN_MASK: usize = 3;
-    const ARRAY_PADDING_140: usize = 12;
+    const ALIGN_MASK: usize = 15;
+    const ARRAY_PADDING_140: usize = 0;
     const ARRAY_PADDING_430: usize = 0;
-    type ArrayPadding140 = [u8; 12];
+    type ArrayPadding140 = [u8; 0];
     type ArrayPadding430 = [u8; 0];
 }
 unsafe impl ShaderNative for vec4<i32> {
@@ -3,8 +3,13 @@ use {
     crate::{assert_error, OutOfMemory},
     std::{
         borrow::Cow,
+        collections::BTreeMap,
         convert::TryFrom,
+        error::Error,
         fmt::{self, Debug, Display},
+        hash::{Hash, Hasher},
+        path::PathBuf,
+        sync::Arc,
     },
 };
 
@@ -120,6 +125,41 @@ pub struct ShaderModuleInfo {
 
     /// Source language.
     pub language: ShaderLanguage,
+
+    /// Preprocessor definitions.
+    /// Each pair acts as `#define key value` line before the source code.
+    #[cfg_attr(
+        feature = "serde-1",
+        serde(skip_serializing_if = "BTreeMap::is_empty", default)
+    )]
+    pub defines: BTreeMap<String, String>,
+
+    /// Resolves `#include` directives in the source code.
+    /// If `None` then source code must not contain includes.
+    #[cfg_attr(feature = "serde-1", serde(skip))]
+    pub includes: Option<IncludeResolver>,
+
+    /// Entry point and target profile.
+    /// Required for HLSL shaders.
+    #[cfg_attr(
+        feature = "serde-1",
+        serde(skip_serializing_if = "Option::is_none", default)
+    )]
+    pub hlsl: Option<HlslOptions>,
+
+    /// Skips validation of SPIR-V code.
+    ///
+    /// SPIR-V code is validated with naga unless it uses features
+    /// naga doesn't support.
+    /// Creating shader module from invalid SPIR-V without validation
+    /// causes undefined behavior.
+    #[cfg_attr(feature = "serde-1", serde(skip_serializing_if = "is_false", default))]
+    pub skip_validation: bool,
+}
+
+#[cfg(feature = "serde-1")]
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl Debug for ShaderModuleInfo {
@@ -131,48 +171,364 @@ impl Debug for ShaderModuleInfo {
             match std::str::from_utf8(&self.code) {
                 Ok(code) => ds.field("code", &code),
                 Err(_) => ds.field("code", &"<binary>"),
-            }
+            };
+            ds.field("defines", &self.defines);
+            ds.field("includes", &self.includes);
+            ds.field("hlsl", &self.hlsl);
+            ds.field("skip_validation", &self.skip_validation);
         } else {
-            ds.field("code", &"..")
+            ds.field("code", &"..");
         };
         ds.finish()
     }
 }
 
 impl ShaderModuleInfo {
-    /// Creates GLSL shader module info.
-    pub fn glsl(bytes: impl Into<Box<[u8]>>, stage: ShaderStage) -> Self {
+    /// Creates shader module info without defines and includes.
+    pub fn new(bytes: impl Into<Box<[u8]>>, language: ShaderLanguage) -> Self {
         ShaderModuleInfo {
             code: bytes.into(),
-            language: ShaderLanguage::GLSL { stage },
+            language,
+            defines: BTreeMap::new(),
+            includes: None,
+            hlsl: None,
+            skip_validation: false,
         }
     }
 
+    /// Creates GLSL shader module info.
+    pub fn glsl(bytes: impl Into<Box<[u8]>>, stage: ShaderStage) -> Self {
+        ShaderModuleInfo::new(bytes, ShaderLanguage::GLSL { stage })
+    }
+
     /// Creates WGSL shader module info.
     pub fn wgsl(bytes: impl Into<Box<[u8]>>) -> Self {
-        ShaderModuleInfo {
-            code: bytes.into(),
-            language: ShaderLanguage::WGSL,
-        }
+        ShaderModuleInfo::new(bytes, ShaderLanguage::WGSL)
     }
 
     /// Creates HLSL shader module info.
-    pub fn hlsl(bytes: impl Into<Box<[u8]>>) -> Self {
-        ShaderModuleInfo {
-            code: bytes.into(),
-            language: ShaderLanguage::HLSL,
-        }
+    /// Shader is compiled for `entry` point with target `profile`, e.g. `ps_6_0`.
+    pub fn hlsl(
+        bytes: impl Into<Box<[u8]>>,
+        entry: impl Into<String>,
+        profile: impl Into<String>,
+    ) -> Self {
+        let mut info = ShaderModuleInfo::new(bytes, ShaderLanguage::HLSL);
+        info.hlsl = Some(HlslOptions::new(entry, profile));
+        info
     }
 
     /// Creates SPIR-V shader module info.
     pub fn spirv(bytes: impl Into<Box<[u8]>>) -> Self {
-        ShaderModuleInfo {
-            code: bytes.into(),
-            language: ShaderLanguage::SPIRV,
+        ShaderModuleInfo::new(bytes, ShaderLanguage::SPIRV)
+    }
+
+    /// Adds preprocessor definition.
+    pub fn with_define(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defines.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets resolver for `#include` directives.
+    pub fn with_includes(mut self, includes: IncludeResolver) -> Self {
+        self.includes = Some(includes);
+        self
+    }
+
+    /// Skips validation of SPIR-V code.
+    /// See `skip_validation` field.
+    pub fn without_validation(mut self) -> Self {
+        self.skip_validation = true;
+        self
+    }
+}
+
+/// Resolves `#include` directives in shader source code.
+#[derive(Clone)]
+pub enum IncludeResolver {
+    /// Included paths are relative to the directory.
+    Directory(PathBuf),
+
+    /// Function that returns content of included file.
+    /// Returns `None` if file is not found.
+    Custom(Arc<IncludeFn>),
+}
+
+/// Function that resolves `#include` directives.
+pub type IncludeFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl IncludeResolver {
+    /// Creates resolver from function.
+    pub fn custom(f: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> Self {
+        IncludeResolver::Custom(Arc::new(f))
+    }
+
+    /// Returns content of included file.
+    pub fn resolve(&self, path: &str) -> Option<String> {
+        match self {
+            IncludeResolver::Directory(root) => std::fs::read_to_string(root.join(path)).ok(),
+            IncludeResolver::Custom(f) => f(path),
+        }
+    }
+}
+
+impl Debug for IncludeResolver {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IncludeResolver::Directory(root) => fmt.debug_tuple("Directory").field(root).finish(),
+            IncludeResolver::Custom(f) => write!(fmt, "Custom({:p})", Arc::as_ptr(f)),
+        }
+    }
+}
+
+impl PartialEq for IncludeResolver {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (IncludeResolver::Directory(lhs), IncludeResolver::Directory(rhs)) => lhs == rhs,
+            (IncludeResolver::Custom(lhs), IncludeResolver::Custom(rhs)) => Arc::ptr_eq(lhs, rhs),
+            _ => false,
         }
     }
 }
 
+impl Eq for IncludeResolver {}
+
+impl Hash for IncludeResolver {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            IncludeResolver::Directory(root) => root.hash(state),
+            IncludeResolver::Custom(f) => (Arc::as_ptr(f) as *const u8).hash(state),
+        }
+    }
+}
+
+/// Options for compiling HLSL shader into SPIR-V.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+pub struct HlslOptions {
+    /// Name of the entry point function.
+    /// SPIR-V entry point has the same name.
+    pub entry: String,
+
+    /// Target profile, e.g. `vs_6_0`, `ps_6_0` or `lib_6_3`.
+    pub profile: String,
+}
+
+impl HlslOptions {
+    pub fn new(entry: impl Into<String>, profile: impl Into<String>) -> Self {
+        HlslOptions {
+            entry: entry.into(),
+            profile: profile.into(),
+        }
+    }
+
+    /// Returns shader stage targeted by profile.
+    /// Returns `None` for library profiles and unknown profiles.
+    pub fn stage(&self) -> Option<ShaderStage> {
+        match self.profile.split('_').next()? {
+            "vs" => Some(ShaderStage::Vertex),
+            "hs" => Some(ShaderStage::TessellationControl),
+            "ds" => Some(ShaderStage::TessellationEvaluation),
+            "gs" => Some(ShaderStage::Geometry),
+            "ps" => Some(ShaderStage::Fragment),
+            "cs" => Some(ShaderStage::Compute),
+            _ => None,
+        }
+    }
+}
+
+impl ShaderModuleInfo {
+    /// Returns key that identifies compiled SPIR-V of this shader.
+    ///
+    /// Key is stable across runs and covers source code with all included files,
+    /// language, defines and HLSL options.
+    /// It does not cover compiler that is used for languages and stages
+    /// built-in front-end doesn't support.
+    pub fn cache_key(&self) -> Result<u128, CreateShaderModuleError> {
+        let mut hasher = Fnv128::new();
+        hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.write(self.language.to_string().as_bytes());
+
+        match self.language {
+            ShaderLanguage::SPIRV => hasher.write(&self.code),
+            _ => {
+                let code = std::str::from_utf8(&self.code)?;
+                hasher.write(
+                    expand_includes(code, &self.defines, self.includes.as_ref())?.as_bytes(),
+                );
+            }
+        }
+
+        for (name, value) in &self.defines {
+            hasher.write(name.as_bytes());
+            hasher.write(value.as_bytes());
+        }
+
+        if let Some(hlsl) = &self.hlsl {
+            hasher.write(hlsl.entry.as_bytes());
+            hasher.write(hlsl.profile.as_bytes());
+        }
+
+        Ok(hasher.finish())
+    }
+}
+
+/// FNV-1a hash with 128 bit output.
+/// Unlike `DefaultHasher` it is guaranteed to be stable.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new() -> Self {
+        Fnv128(0x6c62272e07bb014262b821756295c58d)
+    }
+
+    /// Hashes length-prefixed bytes so that adjacent writes can't be confused.
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000000001000000000000000000013b);
+        }
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+/// Compiles shader into SPIR-V with built-in front-end.
+///
+/// Does not require `Device`, so it can be used in build scripts
+/// to compile shaders ahead of time.
+/// Returns `UnsupportedShaderLanguage` error for languages and stages
+/// that require `ShaderCompiler`.
+pub fn compile_to_spirv(info: &ShaderModuleInfo) -> Result<Box<[u8]>, CreateShaderModuleError> {
+    match info.language {
+        ShaderLanguage::SPIRV => Ok(info.code.clone()),
+        _ => {
+            let (module, module_info) = parse_with_naga(info)?;
+            Ok(naga_to_spirv(&module, &module_info)?)
+        }
+    }
+}
+
+/// Parses and validates shader with naga front-end.
+pub(crate) fn parse_with_naga(
+    info: &ShaderModuleInfo,
+) -> Result<(naga::Module, naga::valid::ModuleInfo), CreateShaderModuleError> {
+    let module = match info.language {
+        ShaderLanguage::GLSL { stage } => {
+            let stage = match stage {
+                ShaderStage::Vertex => naga::ShaderStage::Vertex,
+                ShaderStage::Fragment => naga::ShaderStage::Fragment,
+                ShaderStage::Compute => naga::ShaderStage::Compute,
+                _ => {
+                    return Err(CreateShaderModuleError::UnsupportedShaderLanguage {
+                        language: info.language,
+                    })
+                }
+            };
+
+            let code = std::str::from_utf8(&info.code)?;
+            let code = expand_includes(code, &info.defines, info.includes.as_ref())?;
+
+            naga::front::glsl::Parser::default()
+                .parse(
+                    &naga::front::glsl::Options {
+                        stage,
+                        defines: info
+                            .defines
+                            .iter()
+                            .map(|(name, value)| (name.clone(), value.clone()))
+                            .collect(),
+                    },
+                    &code,
+                )
+                .map_err(|errors| CreateShaderModuleError::NagaGlslParseError { errors })?
+        }
+        ShaderLanguage::WGSL => {
+            let code = std::str::from_utf8(&info.code)?;
+            naga::front::wgsl::parse_str(code).map_err(|err| {
+                CreateShaderModuleError::NagaWgslParseError {
+                    source: Box::from(err.emit_to_string(".")),
+                }
+            })?
+        }
+        _ => {
+            return Err(CreateShaderModuleError::UnsupportedShaderLanguage {
+                language: info.language,
+            })
+        }
+    };
+
+    let module_info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)?;
+
+    Ok((module, module_info))
+}
+
+/// Writes naga module as SPIR-V.
+pub(crate) fn naga_to_spirv(
+    module: &naga::Module,
+    module_info: &naga::valid::ModuleInfo,
+) -> Result<Box<[u8]>, naga::back::spv::Error> {
+    let spv =
+        naga::back::spv::write_vec(module, module_info, &naga::back::spv::Options::default())?;
+
+    Ok(bytemuck::cast_slice(&spv).into())
+}
+
+/// Replaces `#include` directives in source code with content of included files.
+///
+/// See `sierra_include::expand` for details.
+pub(crate) fn expand_includes<'a>(
+    code: &'a str,
+    defines: &BTreeMap<String, String>,
+    includes: Option<&IncludeResolver>,
+) -> Result<Cow<'a, str>, CreateShaderModuleError> {
+    let defines = defines
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()));
+
+    sierra_include::expand(code, None, defines, &mut includes.map(IncludeFiles)).map_err(|err| {
+        match err {
+            sierra_include::Error::Unresolved { path } => {
+                CreateShaderModuleError::IncludeError { path }
+            }
+            sierra_include::Error::DepthExceeded { path, depth } => {
+                CreateShaderModuleError::IncludeDepthExceeded { path, depth }
+            }
+        }
+    })
+}
+
+/// Adapts `IncludeResolver` for `sierra_include`.
+/// Paths are resolved regardless of including file.
+struct IncludeFiles<'a>(&'a IncludeResolver);
+
+impl sierra_include::Resolver for IncludeFiles<'_> {
+    type File = ();
+
+    fn resolve(&mut self, path: &str, _includer: Option<&()>) -> Option<((), String)> {
+        self.0.resolve(path).map(|content| ((), content))
+    }
+}
+
+/// Front-end that compiles shader source code into SPIR-V.
+///
+/// Built-in front-end handles only WGSL and GLSL vertex, fragment and compute
+/// shaders. Other languages and stages are passed to the compiler provided
+/// in `GraphicsConfig::shader_compiler`.
+pub trait ShaderCompiler: Send + Sync {
+    /// Checks if this compiler can compile shaders in specified language.
+    fn supports(&self, language: ShaderLanguage) -> bool;
+
+    /// Compiles shader source code into SPIR-V binary.
+    fn compile(&self, info: &ShaderModuleInfo) -> Result<Box<[u8]>, Box<dyn Error + Send + Sync>>;
+}
+
 /// Valid SPIR-V shader code.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
@@ -195,10 +551,7 @@ impl Spirv {
 
 impl From<Spirv> for ShaderModuleInfo {
     fn from(shader: Spirv) -> Self {
-        ShaderModuleInfo {
-            code: shader.code.into(),
-            language: ShaderLanguage::SPIRV,
-        }
+        ShaderModuleInfo::new(shader.code, ShaderLanguage::SPIRV)
     }
 }
 
@@ -226,12 +579,12 @@ impl Glsl {
 
 impl From<Glsl> for ShaderModuleInfo {
     fn from(shader: Glsl) -> Self {
-        ShaderModuleInfo {
-            code: shader.code.into(),
-            language: ShaderLanguage::GLSL {
+        ShaderModuleInfo::new(
+            shader.code.into_boxed_bytes(),
+            ShaderLanguage::GLSL {
                 stage: shader.stage,
             },
-        }
+        )
     }
 }
 
@@ -242,25 +595,31 @@ impl From<Glsl> for ShaderModuleInfo {
 #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hlsl {
     code: Box<str>,
+    options: HlslOptions,
 }
 
 impl Hlsl {
     /// Wraps string that must contain valid HLSL shader code.
+    /// Shader is compiled for `entry` point with target `profile`.
     ///
     /// FIXME: Actually check validity.
-    pub fn new(string: impl Into<Box<str>>) -> Self {
+    pub fn new(
+        string: impl Into<Box<str>>,
+        entry: impl Into<String>,
+        profile: impl Into<String>,
+    ) -> Self {
         Hlsl {
             code: string.into(),
+            options: HlslOptions::new(entry, profile),
         }
     }
 }
 
 impl From<Hlsl> for ShaderModuleInfo {
     fn from(shader: Hlsl) -> Self {
-        ShaderModuleInfo {
-            code: shader.code.into(),
-            language: ShaderLanguage::HLSL,
-        }
+        let mut info = ShaderModuleInfo::new(shader.code.into_boxed_bytes(), ShaderLanguage::HLSL);
+        info.hlsl = Some(shader.options);
+        info
     }
 }
 
@@ -301,7 +660,7 @@ impl Shader {
     }
 }
 
-#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
 pub enum InvalidShader {
     #[error("Source is empty")]
@@ -312,6 +671,12 @@ pub enum InvalidShader {
 
     #[error("Wrong spir-v magic. Expected 0x07230203, found 0x{found:x}")]
     WrongMagic { found: u32 },
+
+    #[error("Failed to parse spir-v: {reason}")]
+    SpirvParseError { reason: String },
+
+    #[error("Shader validation failed: {reason}")]
+    ValidationError { reason: String },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -359,6 +724,21 @@ pub enum CreateShaderModuleError {
         #[from]
         source: naga::valid::ValidationError,
     },
+
+    #[error("HLSL shader requires entry point and target profile")]
+    MissingHlslOptions,
+
+    #[error("Failed to resolve `#include` of `{path}`")]
+    IncludeError { path: String },
+
+    #[error("`#include` of `{path}` exceeds maximum nesting depth of {depth}")]
+    IncludeDepthExceeded { path: String, depth: usize },
+
+    #[error("Shader compiler failed to compile shader: {source}")]
+    CompileError {
+        #[source]
+        source: Box<dyn Error + Send + Sync>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, thiserror::Error)]
@@ -1025,6 +1405,18 @@ pub mod shader_compiler {
             source: std::str::Utf8Error,
         },
 
+        #[error("Failed to initialize shaderc")]
+        InitFailed,
+
+        #[error("Shader language {language:?} is unsupported")]
+        Unsupported { language: ShaderLanguage },
+
+        #[error("HLSL shader requires entry point and target profile")]
+        MissingHlslOptions,
+
+        #[error("HLSL target profile `{profile}` is unsupported")]
+        UnsupportedHlslProfile { profile: String },
+
         #[error("Shaderc failed to compile shader source code: {source}")]
         Shaderc {
             #[from]
@@ -1032,37 +1424,93 @@ pub mod shader_compiler {
         },
     }
 
+    /// Shader compiler backed by shaderc.
+    /// Compiles GLSL shaders of all stages and HLSL shaders
+    /// with stage-specific profiles.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Shaderc;
+
+    impl ShaderCompiler for Shaderc {
+        fn supports(&self, language: ShaderLanguage) -> bool {
+            matches!(language, ShaderLanguage::GLSL { .. } | ShaderLanguage::HLSL)
+        }
+
+        fn compile(
+            &self,
+            info: &ShaderModuleInfo,
+        ) -> Result<Box<[u8]>, Box<dyn Error + Send + Sync>> {
+            Ok(compile_shader(info, "main", "shader")?)
+        }
+    }
+
+    /// Compiles shader into SPIR-V with shaderc.
+    /// For HLSL shaders entry point from `ShaderModuleInfo::hlsl` is used instead of `entry`.
     pub fn compile_shader(
-        code: &[u8],
+        info: &ShaderModuleInfo,
         entry: &str,
-        language: ShaderLanguage,
         source_name: &str,
-        include: impl Fn(&str, shaderc::IncludeType) -> Option<String>,
     ) -> Result<Box<[u8]>, ShaderCompileFailed> {
-        let mut options = shaderc::CompileOptions::new().unwrap();
-
-        options.set_source_language(match language {
-            ShaderLanguage::GLSL => shaderc::SourceLanguage::GLSL,
-            ShaderLanguage::HLSL => shaderc::SourceLanguage::HLSL,
-            ShaderLanguage::SPIRV => return Ok(code.into()),
-            // _ => return Err(ShaderCompileFailed::Unsupported { language }),
-        });
-
-        options.set_include_callback(|path, ty, _, _| {
-            let content =
-                include(path, ty).ok_or_else(|| format!("Failed to load shader file {}", path))?;
-
-            Ok(shaderc::ResolvedInclude {
-                resolved_name: path.to_owned(),
-                content,
-            })
-        });
+        let mut options = shaderc::CompileOptions::new().ok_or(ShaderCompileFailed::InitFailed)?;
+
+        // Ray-tracing stages require SPIR-V 1.4.
+        options.set_target_env(
+            shaderc::TargetEnv::Vulkan,
+            shaderc::EnvVersion::Vulkan1_2 as u32,
+        );
+
+        let mut entry = entry;
 
-        let mut compiler = shaderc::Compiler::new().unwrap();
+        let kind = match info.language {
+            ShaderLanguage::GLSL { stage } => {
+                options.set_source_language(shaderc::SourceLanguage::GLSL);
+                shader_kind(stage)
+            }
+            ShaderLanguage::HLSL => {
+                let hlsl = info
+                    .hlsl
+                    .as_ref()
+                    .ok_or(ShaderCompileFailed::MissingHlslOptions)?;
+
+                let stage =
+                    hlsl.stage()
+                        .ok_or_else(|| ShaderCompileFailed::UnsupportedHlslProfile {
+                            profile: hlsl.profile.clone(),
+                        })?;
+
+                options.set_source_language(shaderc::SourceLanguage::HLSL);
+                entry = &hlsl.entry;
+                shader_kind(stage)
+            }
+            ShaderLanguage::SPIRV => return Ok(info.code.clone()),
+            language => return Err(ShaderCompileFailed::Unsupported { language }),
+        };
+
+        for (name, value) in &info.defines {
+            options.add_macro_definition(name, Some(value.as_str()));
+        }
+
+        if let Some(includes) = &info.includes {
+            options.set_include_callback(move |path, _, _, depth| {
+                if depth > sierra_include::MAX_DEPTH {
+                    return Err(format!("Include depth limit exceeded at {}", path));
+                }
+
+                let content = includes
+                    .resolve(path)
+                    .ok_or_else(|| format!("Failed to load shader file {}", path))?;
+
+                Ok(shaderc::ResolvedInclude {
+                    resolved_name: path.to_owned(),
+                    content,
+                })
+            });
+        }
+
+        let mut compiler = shaderc::Compiler::new().ok_or(ShaderCompileFailed::InitFailed)?;
 
         let binary_result = compiler.compile_into_spirv(
-            std::str::from_utf8(code)?,
-            shaderc::ShaderKind::InferFromSource,
+            std::str::from_utf8(&info.code)?,
+            kind,
             source_name,
             entry,
             Some(&options),
@@ -1074,4 +1522,185 @@ pub mod shader_compiler {
 
         Ok(binary_result.as_binary_u8().into())
     }
+
+    fn shader_kind(stage: ShaderStage) -> shaderc::ShaderKind {
+        match stage {
+            ShaderStage::Vertex => shaderc::ShaderKind::Vertex,
+            ShaderStage::TessellationControl => shaderc::ShaderKind::TessControl,
+            ShaderStage::TessellationEvaluation => shaderc::ShaderKind::TessEvaluation,
+            ShaderStage::Geometry => shaderc::ShaderKind::Geometry,
+            ShaderStage::Fragment => shaderc::ShaderKind::Fragment,
+            ShaderStage::Compute => shaderc::ShaderKind::Compute,
+            ShaderStage::Raygen => shaderc::ShaderKind::RayGeneration,
+            ShaderStage::AnyHit => shaderc::ShaderKind::AnyHit,
+            ShaderStage::ClosestHit => shaderc::ShaderKind::ClosestHit,
+            ShaderStage::Miss => shaderc::ShaderKind::Miss,
+            ShaderStage::Intersection => shaderc::ShaderKind::Intersection,
+        }
+    }
+}
+
+#[cfg(feature = "dxc")]
+pub mod dxc {
+    use {
+        super::*,
+        std::{
+            ffi::OsString,
+            process::Command,
+            sync::atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum DxcError {
+        #[error("HLSL shader requires entry point and target profile")]
+        MissingHlslOptions,
+
+        #[error("Failed to run DXC: {source}")]
+        Io {
+            #[from]
+            source: std::io::Error,
+        },
+
+        #[error("DXC failed to compile shader:\n{diagnostics}")]
+        CompilationFailed { diagnostics: String },
+    }
+
+    /// Shader compiler that runs external DXC executable
+    /// to compile HLSL shaders.
+    #[derive(Clone, Debug)]
+    pub struct Dxc {
+        path: PathBuf,
+        args: Vec<OsString>,
+    }
+
+    impl Default for Dxc {
+        /// Runs `dxc` found in `PATH`.
+        fn default() -> Self {
+            Dxc::new("dxc")
+        }
+    }
+
+    impl Dxc {
+        /// Creates compiler that runs DXC executable at `path`.
+        pub fn new(path: impl Into<PathBuf>) -> Self {
+            Dxc {
+                path: path.into(),
+                args: Vec::new(),
+            }
+        }
+
+        /// Adds argument passed to DXC for each shader.
+        pub fn with_arg(mut self, arg: impl Into<OsString>) -> Self {
+            self.args.push(arg.into());
+            self
+        }
+    }
+
+    impl ShaderCompiler for Dxc {
+        fn supports(&self, language: ShaderLanguage) -> bool {
+            language == ShaderLanguage::HLSL
+        }
+
+        fn compile(
+            &self,
+            info: &ShaderModuleInfo,
+        ) -> Result<Box<[u8]>, Box<dyn Error + Send + Sync>> {
+            static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+            let hlsl = info.hlsl.as_ref().ok_or(DxcError::MissingHlslOptions)?;
+            let code = std::str::from_utf8(&info.code)?;
+            let code = expand_includes(code, &info.defines, info.includes.as_ref())?;
+
+            let input = std::env::temp_dir().join(format!(
+                "sierra-{}-{}.hlsl",
+                std::process::id(),
+                NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+            ));
+            let output = input.with_extension("spv");
+
+            std::fs::write(&input, code.as_bytes()).map_err(DxcError::from)?;
+
+            let mut command = Command::new(&self.path);
+            command
+                .arg("-spirv")
+                .arg("-fspv-target-env=vulkan1.2")
+                .arg("-E")
+                .arg(&hlsl.entry)
+                .arg("-T")
+                .arg(&hlsl.profile);
+
+            for (name, value) in &info.defines {
+                command.arg(format!("-D{}={}", name, value));
+            }
+
+            let result = command
+                .args(&self.args)
+                .arg("-Fo")
+                .arg(&output)
+                .arg(&input)
+                .output();
+
+            let _ = std::fs::remove_file(&input);
+            let result = result.map_err(DxcError::from)?;
+
+            if !result.status.success() {
+                let _ = std::fs::remove_file(&output);
+                return Err(DxcError::CompilationFailed {
+                    diagnostics: String::from_utf8_lossy(&result.stderr).into_owned(),
+                }
+                .into());
+            }
+
+            if !result.stderr.is_empty() {
+                tracing::warn!("{}", String::from_utf8_lossy(&result.stderr));
+            }
+
+            let spv = std::fs::read(&output);
+            let _ = std::fs::remove_file(&output);
+            Ok(spv.map_err(DxcError::from)?.into_boxed_slice())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> IncludeResolver {
+        IncludeResolver::custom(|path| match path {
+            "common.glsl" => Some("float common;\n".to_owned()),
+            "self.glsl" => Some("#include \"self.glsl\"\n".to_owned()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn conditional_includes_follow_defines() {
+        let code = "#ifdef USE_SHADOWS\n#include \"missing.glsl\"\n#endif\n";
+
+        let expanded = expand_includes(code, &BTreeMap::new(), Some(&resolver())).unwrap();
+        assert_eq!(expanded, "#ifdef USE_SHADOWS\n\n#endif\n");
+
+        let mut defines = BTreeMap::new();
+        defines.insert("USE_SHADOWS".to_owned(), String::new());
+
+        match expand_includes(code, &defines, Some(&resolver())) {
+            Err(CreateShaderModuleError::IncludeError { path }) => assert_eq!(path, "missing.glsl"),
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn recursive_include_exceeds_depth() {
+        let code = "#include \"self.glsl\"\n";
+
+        match expand_includes(code, &BTreeMap::new(), Some(&resolver())) {
+            Err(CreateShaderModuleError::IncludeDepthExceeded { path, depth }) => {
+                assert_eq!(path, "self.glsl");
+                assert_eq!(depth, sierra_include::MAX_DEPTH);
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
 }
@@ -1,8 +1,10 @@
-pub use crate::backend::ImageView;
+pub use crate::backend::{BufferView, ImageView};
 use crate::{
     access::AccessFlags,
     backend::Device,
+    buffer::{Buffer, BufferRange},
     encode::Encoder,
+    format::Format,
     image::{Image, ImageExtent, ImageMemoryBarrier, Layout, SubresourceRange},
     queue::{Ownership, QueueId},
     stage::PipelineStageFlags,
@@ -63,6 +65,33 @@ impl ImageViewInfo {
     }
 }
 
+/// Information required to create a buffer view.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct BufferViewInfo {
+    /// A buffer view is bound to.
+    pub buffer: Buffer,
+
+    /// Format of texels in the view.
+    pub format: Format,
+
+    /// Offset of the view in the buffer.
+    pub offset: u64,
+
+    /// Size of the view in bytes.
+    pub size: u64,
+}
+
+impl BufferViewInfo {
+    pub fn new(range: BufferRange, format: Format) -> Self {
+        BufferViewInfo {
+            buffer: range.buffer,
+            format,
+            offset: range.offset,
+            size: range.size,
+        }
+    }
+}
+
 #[doc(hidden)]
 pub trait MakeImageView {
     fn make_view<'a>(&'a self, device: &Device) -> Result<ImageView, OutOfMemory>;
@@ -0,0 +1,393 @@
+//! Generation of WGSL declarations from Rust types.
+//!
+//! `#[shader_repr]` structures implement [`WgslType`] for both [`Std140`] and [`Std430`]
+//! layouts, `#[descriptors(wgsl)]` structures implement [`WgslDescriptors`]
+//! and `#[pipeline(wgsl)]` structures implement [`WgslInclude`].
+//!
+//! Generated code uses WGSL dialect accepted by the shader compiler used by sierra,
+//! i.e. `[[attribute]]` syntax and `[[block]]` structures for buffers.
+//!
+//! Struct members are declared with explicit `align` and `size` attributes
+//! and arrays with explicit `stride`, so WGSL structures have exactly
+//! the same layout as representations generated for Rust structures.
+
+use {
+    crate::{descriptor::SparseDescriptors, repr::*},
+    std::{any::TypeId, collections::HashSet, fmt::Write as _, mem::size_of},
+};
+
+/// Provides generated code to include into WGSL shaders.
+pub trait WgslInclude {
+    /// Returns generated declarations.
+    fn wgsl() -> String;
+
+    /// Replaces `#include "sierra_generated.wgsl"` lines in the shader code
+    /// with generated declarations.
+    fn include_wgsl(code: &str) -> String {
+        let mut result = String::with_capacity(code.len());
+
+        for line in code.lines() {
+            if line.trim() == "#include \"sierra_generated.wgsl\"" {
+                result.push_str(&Self::wgsl());
+            } else {
+                result.push_str(line);
+                result.push('\n');
+            }
+        }
+
+        result
+    }
+}
+
+/// Declarations of descriptors in WGSL.
+pub trait WgslDescriptors {
+    /// Declares descriptors as bound to `group`.
+    ///
+    /// `name` is used for bindings that has no name on their own.
+    fn wgsl_declarations(group: u32, name: &str, ctx: &mut WgslTypeContext);
+}
+
+/// Collects definitions of types and declarations of
+/// resources for WGSL shader.
+#[derive(Debug, Default)]
+pub struct WgslTypeContext {
+    generated: HashSet<(TypeId, TypeId)>,
+    types: String,
+    declarations: String,
+}
+
+impl WgslTypeContext {
+    pub fn new() -> Self {
+        WgslTypeContext::default()
+    }
+
+    /// Adds definition of the type with layout `L` and all types it depends on.
+    /// Each type is defined only once.
+    pub fn add<T, L>(&mut self)
+    where
+        T: WgslType<L> + ?Sized,
+        L: 'static,
+    {
+        if self
+            .generated
+            .insert((TypeId::of::<T>(), TypeId::of::<L>()))
+        {
+            T::deps(self);
+            let def = T::def();
+            if !def.is_empty() {
+                self.types.push_str(&def);
+                self.types.push('\n');
+            }
+        }
+    }
+
+    /// Declares opaque descriptor, e.g. sampler or texture.
+    ///
+    /// Arrays of opaque descriptors can't be declared in WGSL
+    /// and are commented instead.
+    pub fn declare_opaque(&mut self, group: u32, binding: u32, count: u32, ty: &str, name: &str) {
+        if count == 1 {
+            writeln!(
+                self.declarations,
+                "[[group({}), binding({})]] var {}: {};",
+                group, binding, name, ty
+            )
+            .unwrap();
+        } else {
+            self.declare_unsupported(group, binding, &format!("array of `{}`", ty), name);
+        }
+    }
+
+    /// Comments descriptor that can't be declared in WGSL.
+    pub fn declare_unsupported(&mut self, group: u32, binding: u32, what: &str, name: &str) {
+        writeln!(
+            self.declarations,
+            "// group({}), binding({}): {} `{}` is not supported in WGSL",
+            group, binding, what, name
+        )
+        .unwrap();
+    }
+
+    /// Declares uniform buffer with `value` member of type `T` in std140 layout
+    /// or storage buffer with `value` member of type `T` in std430 layout.
+    ///
+    /// Types with trailing runtime-sized array are inlined into the block instead.
+    pub fn declare_buffer<T>(
+        &mut self,
+        group: u32,
+        binding: u32,
+        count: u32,
+        storage: bool,
+        name: &str,
+    ) where
+        T: WgslType<Std140> + WgslType<Std430> + ?Sized,
+    {
+        if count != 1 {
+            self.declare_unsupported(group, binding, "array of buffers", name);
+            return;
+        }
+
+        let (ty, members, class) = if storage {
+            self.add::<T, Std430>();
+            (
+                <T as WgslType<Std430>>::name(),
+                <T as WgslType<Std430>>::block_members(),
+                "storage, read_write",
+            )
+        } else {
+            self.add::<T, Std140>();
+            (
+                <T as WgslType<Std140>>::name(),
+                <T as WgslType<Std140>>::block_members(),
+                "uniform",
+            )
+        };
+
+        match members {
+            Some(members) => {
+                writeln!(
+                    self.types,
+                    "[[block]]\nstruct {}_block {{\n{}}};",
+                    name, members
+                )
+            }
+            None => writeln!(
+                self.types,
+                "[[block]]\nstruct {}_block {{\n    value: {};\n}};",
+                name, ty
+            ),
+        }
+        .unwrap();
+
+        writeln!(
+            self.declarations,
+            "[[group({}), binding({})]] var<{}> {}: {}_block;",
+            group, binding, class, name, name
+        )
+        .unwrap();
+    }
+
+    /// Declares block structure with specified members
+    /// and variable of this type.
+    /// Each member is returned by [`struct_member`]
+    /// and its type must be added to the context.
+    pub fn declare_block(
+        &mut self,
+        attributes: &str,
+        class: &str,
+        ty: &str,
+        members: &str,
+        name: &str,
+    ) {
+        writeln!(self.types, "[[block]]\nstruct {} {{\n{}}};", ty, members).unwrap();
+
+        if !attributes.is_empty() {
+            self.declarations.push_str(attributes);
+            self.declarations.push(' ');
+        }
+
+        writeln!(self.declarations, "var<{}> {}: {};", class, name, ty).unwrap();
+    }
+
+    /// Returns generated code.
+    /// Type definitions are followed by declarations.
+    pub fn code(self) -> String {
+        let mut code = self.types;
+        code.push_str(&self.declarations);
+        code
+    }
+}
+
+/// Returns declaration of struct member of type `T` with layout `L`.
+///
+/// `align_mask` is alignment mask of the member.
+/// First member must use alignment mask of the whole structure
+/// to get same size of the structure as in Rust.
+pub fn struct_member<T, L>(name: &str, align_mask: usize) -> String
+where
+    T: WgslType<L> + ShaderRepr<L>,
+{
+    format!(
+        "    [[align({}), size({})]] {}: {};\n",
+        align_mask + 1,
+        size_of::<T::Type>(),
+        name,
+        T::name()
+    )
+}
+
+/// Returns declaration of runtime-sized array member
+/// with elements of type `T` with layout `L`.
+pub fn runtime_array_member<T, L>(name: &str) -> String
+where
+    T: WgslType<L> + ShaderRepr<L>,
+    L: 'static,
+{
+    format!(
+        "    [[align({})]] {}: {};\n",
+        T::ALIGN_MASK + 1,
+        name,
+        <[T]>::name()
+    )
+}
+
+/// Generates declarations for struct in WGSL shaders
+/// with layout `L` which is either [`Std140`] or [`Std430`].
+pub trait WgslType<L = Std140>: 'static {
+    /// Name of the type in WGSL.
+    fn name() -> String;
+
+    /// Adds types this type depends on.
+    fn deps(_ctx: &mut WgslTypeContext) {}
+
+    /// Definition of the type. Empty for builtin types.
+    fn def() -> String {
+        String::new()
+    }
+
+    /// Members of the buffer block for structures with trailing runtime-sized array.
+    /// Such structures are inlined into the block instead of being declared.
+    fn block_members() -> Option<String> {
+        None
+    }
+}
+
+macro_rules! builtin_wgsl_type {
+    ($($ty:ty as $name:literal),* $(,)?) => {
+        $(
+            impl<L: 'static> WgslType<L> for $ty {
+                fn name() -> String {
+                    $name.to_owned()
+                }
+            }
+        )*
+    };
+}
+
+builtin_wgsl_type!(
+    f32 as "f32",
+    f64 as "f64",
+    i32 as "i32",
+    u32 as "u32",
+    vec2<f32> as "vec2<f32>",
+    vec3<f32> as "vec3<f32>",
+    vec4<f32> as "vec4<f32>",
+    vec2<f64> as "vec2<f64>",
+    vec3<f64> as "vec3<f64>",
+    vec4<f64> as "vec4<f64>",
+    vec2<i32> as "vec2<i32>",
+    vec3<i32> as "vec3<i32>",
+    vec4<i32> as "vec4<i32>",
+    vec2<u32> as "vec2<u32>",
+    vec3<u32> as "vec3<u32>",
+    vec4<u32> as "vec4<u32>",
+);
+
+// Booleans can't be stored in WGSL buffers and are declared as unsigned integers.
+builtin_wgsl_type!(
+    bool as "u32",
+    boolean as "u32",
+    vec2<boolean> as "vec2<u32>",
+    vec3<boolean> as "vec3<u32>",
+    vec4<boolean> as "vec4<u32>",
+);
+
+/// Returns stride of array elements of type `T` with layout `L`.
+fn array_stride<T, L>() -> usize
+where
+    T: ShaderRepr<L>,
+{
+    size_of::<Padded<T::Type, T::ArrayPadding>>()
+}
+
+impl<T, L> WgslType<L> for [T]
+where
+    T: WgslType<L> + ShaderRepr<L>,
+    L: 'static,
+{
+    fn name() -> String {
+        format!(
+            "[[stride({})]] array<{}>",
+            array_stride::<T, L>(),
+            T::name()
+        )
+    }
+
+    fn deps(ctx: &mut WgslTypeContext) {
+        ctx.add::<T, L>()
+    }
+}
+
+impl<T, L> WgslType<L> for RuntimeArray<T>
+where
+    T: WgslType<L> + ShaderRepr<L>,
+    L: 'static,
+{
+    fn name() -> String {
+        <[T]>::name()
+    }
+
+    fn deps(ctx: &mut WgslTypeContext) {
+        ctx.add::<T, L>()
+    }
+}
+
+impl<T, L, const N: usize> WgslType<L> for [T; N]
+where
+    T: WgslType<L> + ShaderRepr<L>,
+    L: 'static,
+{
+    fn name() -> String {
+        format!(
+            "[[stride({})]] array<{}, {}>",
+            array_stride::<T, L>(),
+            T::name(),
+            N
+        )
+    }
+
+    fn deps(ctx: &mut WgslTypeContext) {
+        ctx.add::<T, L>()
+    }
+}
+
+macro_rules! impl_wgsl_mats {
+    ($($mat:ident ($n:literal, $m:literal, $vec:ident)),* $(,)?) => {$(
+        impl<L> WgslType<L> for $mat<f32>
+        where
+            $vec<f32>: ShaderRepr<L>,
+            L: 'static,
+        {
+            fn name() -> String {
+                let stride = array_stride::<$vec<f32>, L>();
+
+                // WGSL matrices have columns aligned to their own size.
+                // Columns of two rows are padded to 16 bytes in std140,
+                // which is expressed as array of vectors.
+                if stride == size_of::<$vec<f32>>().next_power_of_two() {
+                    std::format!("mat{}x{}<f32>", $n, $m)
+                } else {
+                    std::format!("[[stride({})]] array<vec{}<f32>, {}>", stride, $m, $n)
+                }
+            }
+        }
+    )*};
+}
+
+impl_wgsl_mats!(
+    mat2x2(2, 2, vec2),
+    mat3x2(3, 2, vec2),
+    mat4x2(4, 2, vec2),
+    mat2x3(2, 3, vec3),
+    mat3x3(3, 3, vec3),
+    mat4x3(4, 3, vec3),
+    mat2x4(2, 4, vec4),
+    mat3x4(3, 4, vec4),
+    mat4x4(4, 4, vec4),
+);
+
+impl<T, const CAP: u32, const STAGES: u32> WgslDescriptors for SparseDescriptors<T, CAP, STAGES> {
+    fn wgsl_declarations(group: u32, name: &str, ctx: &mut WgslTypeContext) {
+        ctx.declare_unsupported(group, 0, "sparse descriptors array", name);
+    }
+}
@@ -0,0 +1,428 @@
+//! Checks that `#[descriptors]` and `#[pipeline]` compile with and without
+//! generated shader declarations, and that naga accepts the declarations.
+
+use sierra::{
+    glsl::ShaderInclude, wgsl::WgslInclude, ShaderReprStruct, ShaderStage, Std140, Std430,
+};
+
+#[sierra::shader_repr]
+#[derive(Clone, Copy)]
+pub struct Globals {
+    pub view: sierra::mat4,
+    pub count: u32,
+}
+
+/// Buffer types without shader representation
+/// are accepted when declarations are not generated.
+#[sierra::descriptors]
+pub struct Untyped {
+    #[buffer(storage, ty = [u8])]
+    #[stages(Compute)]
+    pub bytes: sierra::BufferRange,
+
+    #[uniform]
+    #[stages(Compute)]
+    pub transform: sierra::mat4x4<f64>,
+}
+
+#[sierra::pipeline]
+pub struct UntypedPipeline {
+    #[set]
+    pub set: Untyped,
+}
+
+#[sierra::descriptors(glsl, wgsl)]
+pub struct Typed {
+    #[buffer(ty = Globals)]
+    #[stages(Vertex, Fragment)]
+    pub globals: sierra::BufferRange,
+
+    #[sampled_image]
+    #[stages(Fragment)]
+    pub albedo: sierra::ImageView,
+}
+
+#[sierra::pipeline(glsl, wgsl)]
+pub struct TypedPipeline {
+    #[set]
+    pub set: Typed,
+}
+
+#[test]
+fn glsl_declarations() {
+    let vertex = TypedPipeline::glsl(ShaderStage::Vertex);
+    assert!(vertex.contains("Globals"));
+    assert!(!vertex.contains("albedo"));
+
+    let fragment = TypedPipeline::glsl(ShaderStage::Fragment);
+    assert!(fragment.contains("albedo"));
+}
+
+#[test]
+fn wgsl_declarations() {
+    let code = TypedPipeline::wgsl();
+    assert!(code.contains("Globals"));
+    assert!(code.contains("albedo"));
+}
+
+#[sierra::shader_repr]
+#[derive(Clone, Copy)]
+pub struct DrawConstants {
+    pub transform: sierra::mat4,
+    pub index: u32,
+}
+
+#[sierra::descriptors(glsl, wgsl)]
+pub struct Lights {
+    #[buffer(ty = Globals)]
+    #[stages(Vertex | Fragment)]
+    pub lights: sierra::BufferRange,
+}
+
+/// Stages are separated with `,` or `|` in both `#[stages]` and `#[push_constants]`.
+#[sierra::pipeline(glsl, wgsl)]
+pub struct PushConstantsPipeline {
+    #[set]
+    pub set: Lights,
+
+    #[push_constants(stages = Vertex, Fragment)]
+    pub constants: DrawConstants,
+}
+
+#[test]
+fn push_constants_declarations() {
+    for stage in [ShaderStage::Vertex, ShaderStage::Fragment] {
+        let code = PushConstantsPipeline::glsl(stage);
+        assert!(code.contains("DrawConstants"));
+        assert!(code.contains("lights"));
+    }
+
+    let compute = PushConstantsPipeline::glsl(ShaderStage::Compute);
+    assert!(!compute.contains("DrawConstants"));
+    assert!(!compute.contains("lights"));
+
+    let code = format!(
+        "{}\n[[stage(vertex)]]\nfn main() -> [[builtin(position)]] vec4<f32> {{\n    return vec4<f32>(0.0);\n}}\n",
+        PushConstantsPipeline::wgsl()
+    );
+    let module = naga::front::wgsl::parse_str(&code).unwrap();
+    assert!(module
+        .global_variables
+        .iter()
+        .any(|(_, var)| var.class == naga::StorageClass::PushConstant));
+}
+
+#[sierra::descriptors]
+pub struct Storage {
+    #[storage_image]
+    #[stages(Compute)]
+    pub image: sierra::ImageView,
+
+    #[storage_image]
+    #[stages(Compute)]
+    pub explicit: sierra::ImageViewDescriptor,
+
+    #[uniform_texel_buffer]
+    #[stages(Compute)]
+    pub lut: sierra::BufferView,
+
+    #[storage_texel_buffer(count = 4)]
+    #[stages(Compute)]
+    pub texels: [sierra::BufferView; 4],
+}
+
+#[test]
+fn storage_descriptors_layout() {
+    let info = StorageLayout::info();
+
+    assert_eq!(
+        info.bindings
+            .iter()
+            .map(|binding| (binding.binding, binding.ty, binding.count, binding.stages))
+            .collect::<Vec<_>>(),
+        [
+            (
+                0,
+                sierra::DescriptorType::StorageImage,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                1,
+                sierra::DescriptorType::StorageImage,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                2,
+                sierra::DescriptorType::UniformTexelBuffer,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                3,
+                sierra::DescriptorType::StorageTexelBuffer,
+                4,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+        ]
+    );
+}
+
+/// Fields end at offsets aligned to the struct alignment in both layouts,
+/// as naga 0.6 GLSL front-end doesn't round struct size up to its alignment.
+#[sierra::shader_repr]
+#[derive(Clone, Copy)]
+pub struct Light {
+    pub pos: sierra::vec3,
+    pub radius: f32,
+    pub weights: [f32; 3],
+    pub uv: sierra::vec2,
+    pub extent: sierra::vec2,
+}
+
+#[sierra::shader_repr]
+#[derive(Clone, Copy)]
+pub struct Scene {
+    pub view: sierra::mat4,
+    pub light: Light,
+    pub ambient: sierra::vec3,
+    pub exposure: f32,
+    pub flags: [u32; 4],
+}
+
+#[sierra::descriptors(glsl, wgsl)]
+pub struct Images {
+    #[buffer(ty = Scene)]
+    #[stages(Fragment)]
+    pub scene: sierra::BufferRange,
+
+    #[buffer(storage, ty = Scene)]
+    #[stages(Fragment)]
+    pub scenes: sierra::BufferRange,
+
+    #[sampled_image(kind = Cube, array)]
+    #[stages(Fragment)]
+    pub environment: sierra::ImageView,
+
+    #[sampled_image(kind = D3, sample = i32)]
+    #[stages(Fragment)]
+    pub volume: sierra::ImageView,
+
+    #[sampled_image(kind = D1, array, sample = u32)]
+    #[stages(Fragment)]
+    pub ids: sierra::ImageView,
+
+    #[storage_image(array, format = R32Uint)]
+    #[stages(Fragment)]
+    pub counters: sierra::ImageView,
+
+    #[storage_image(kind = D3, format = RGBA16Sfloat)]
+    #[stages(Fragment)]
+    pub radiance: sierra::ImageView,
+
+    #[storage_image]
+    #[stages(Fragment)]
+    pub unformatted: sierra::ImageView,
+
+    #[uniform_texel_buffer(sample = u32)]
+    #[stages(Fragment)]
+    pub indices: sierra::BufferView,
+
+    #[storage_texel_buffer(format = RG32Sint)]
+    #[stages(Fragment)]
+    pub offsets: sierra::BufferView,
+}
+
+#[sierra::pipeline(glsl, wgsl)]
+pub struct ImagesPipeline {
+    #[set]
+    pub set: Images,
+}
+
+/// Returns size and field offsets of `Scene` and nested `Light`.
+fn repr_layouts<L>() -> Vec<(u32, Vec<u32>)>
+where
+    Scene: ShaderReprStruct<L>,
+    Light: ShaderReprStruct<L>,
+{
+    let layout = |size: usize, fields: &[sierra::ShaderReprField]| {
+        (
+            size as u32,
+            fields.iter().map(|field| field.offset as u32).collect(),
+        )
+    };
+
+    vec![
+        layout(
+            <Scene as ShaderReprStruct<L>>::SIZE,
+            <Scene as ShaderReprStruct<L>>::FIELDS,
+        ),
+        layout(
+            <Light as ShaderReprStruct<L>>::SIZE,
+            <Light as ShaderReprStruct<L>>::FIELDS,
+        ),
+    ]
+}
+
+/// Returns size and member offsets of the struct in buffer block
+/// and structs nested in it, as computed by naga.
+fn naga_layouts(module: &naga::Module, storage: bool) -> Vec<(u32, Vec<u32>)> {
+    let mut layouter = naga::proc::Layouter::default();
+    layouter.update(&module.types, &module.constants).unwrap();
+
+    let (_, block) = module
+        .global_variables
+        .iter()
+        .find(|(_, var)| match var.class {
+            naga::StorageClass::Uniform => !storage,
+            naga::StorageClass::Storage { .. } => storage,
+            _ => false,
+        })
+        .expect("Buffer is not declared");
+
+    let members = |ty: naga::Handle<naga::Type>| match &module.types[ty].inner {
+        naga::TypeInner::Struct { members, .. } => members.clone(),
+        inner => panic!("Expected struct, found {:?}", inner),
+    };
+
+    let mut layouts = Vec::new();
+    let mut ty = members(block.ty)[0].ty;
+    loop {
+        let members = members(ty);
+        layouts.push((
+            layouter[ty].size,
+            members.iter().map(|member| member.offset).collect(),
+        ));
+
+        match members.iter().find(|member| {
+            matches!(
+                module.types[member.ty].inner,
+                naga::TypeInner::Struct { .. }
+            )
+        }) {
+            Some(member) => ty = member.ty,
+            None => return layouts,
+        }
+    }
+}
+
+#[test]
+fn wgsl_layouts_match_repr() {
+    let code = format!(
+        "{}\n[[stage(fragment)]]\nfn main() {{}}\n",
+        ImagesPipeline::wgsl()
+    );
+    let module = naga::front::wgsl::parse_str(&code).unwrap();
+
+    assert_eq!(naga_layouts(&module, false), repr_layouts::<Std140>());
+    assert_eq!(naga_layouts(&module, true), repr_layouts::<Std430>());
+}
+
+#[test]
+fn glsl_layouts_match_repr() {
+    // naga 0.6 GLSL front-end doesn't parse storage images and texel buffers.
+    let declarations = ImagesPipeline::glsl(ShaderStage::Fragment)
+        .lines()
+        .filter(|line| !line.contains("image") && !line.contains("Buffer"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let code = format!("#version 450\n{}\nvoid main() {{}}\n", declarations);
+    let module = naga::front::glsl::Parser::default()
+        .parse(
+            &naga::front::glsl::Options {
+                stage: naga::ShaderStage::Fragment,
+                defines: Default::default(),
+            },
+            &code,
+        )
+        .unwrap();
+
+    assert_eq!(naga_layouts(&module, false), repr_layouts::<Std140>());
+    assert_eq!(naga_layouts(&module, true), repr_layouts::<Std430>());
+}
+
+#[test]
+fn glsl_image_declarations() {
+    let code = ImagesPipeline::glsl(ShaderStage::Fragment);
+
+    for declaration in [
+        "uniform textureCubeArray environment;",
+        "uniform itexture3D volume;",
+        "uniform utexture1DArray ids;",
+        "r32ui) uniform uimage2DArray counters;",
+        "rgba16f) uniform image3D radiance;",
+        "binding = 7) uniform image2D unformatted;",
+        "uniform utextureBuffer indices;",
+        "rg32i) uniform iimageBuffer offsets;",
+    ] {
+        assert!(code.contains(declaration), "{}", declaration);
+    }
+}
+
+#[test]
+fn wgsl_image_declarations() {
+    let code = format!(
+        "{}\n[[stage(fragment)]]\nfn main() {{}}\n",
+        ImagesPipeline::wgsl()
+    );
+    let module = naga::front::wgsl::parse_str(&code).unwrap();
+
+    let images = module
+        .global_variables
+        .iter()
+        .filter_map(|(_, var)| match module.types[var.ty].inner {
+            naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => Some((var.name.as_deref().unwrap(), dim, arrayed, class)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let sampled = |kind| naga::ImageClass::Sampled { kind, multi: false };
+    let storage = |format| naga::ImageClass::Storage {
+        format,
+        access: naga::StorageAccess::all(),
+    };
+
+    assert_eq!(
+        images,
+        [
+            (
+                "environment",
+                naga::ImageDimension::Cube,
+                true,
+                sampled(naga::ScalarKind::Float)
+            ),
+            (
+                "volume",
+                naga::ImageDimension::D3,
+                false,
+                sampled(naga::ScalarKind::Sint)
+            ),
+            (
+                "ids",
+                naga::ImageDimension::D1,
+                true,
+                sampled(naga::ScalarKind::Uint)
+            ),
+            (
+                "counters",
+                naga::ImageDimension::D2,
+                true,
+                storage(naga::StorageFormat::R32Uint)
+            ),
+            (
+                "radiance",
+                naga::ImageDimension::D3,
+                false,
+                storage(naga::StorageFormat::Rgba16Float)
+            ),
+        ]
+    );
+
+    assert!(code.contains("storage image without format `unformatted` is not supported"));
+}
@@ -0,0 +1,10 @@
+//! Checks that `include_shader!` expands includes and embeds SPIR-V.
+
+#[test]
+fn include_shader_embeds_spirv() {
+    let info = sierra::include_shader!("tests/shaders/include.frag", USE_TINT = "1");
+
+    assert_eq!(info.language, sierra::ShaderLanguage::SPIRV);
+    assert_eq!(info.code.len() % 4, 0);
+    assert_eq!(info.code[..4], 0x0723_0203u32.to_le_bytes());
+}
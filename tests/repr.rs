@@ -0,0 +1,67 @@
+//! Checks layouts of `#[shader_repr]` structures.
+
+use {
+    bytemuck::Zeroable as _,
+    sierra::{ShaderRepr, ShaderReprStruct, Std140, Std430},
+};
+
+/// Array of `f32` has 16 bytes stride in std140 and 4 bytes stride in std430,
+/// so std140 representation ends at offset unaligned for `vec2`.
+#[sierra::shader_repr]
+#[derive(Clone, Copy)]
+pub struct Padded {
+    pub a: sierra::vec2,
+    pub b: [f32; 3],
+    pub c: f32,
+}
+
+fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    bytemuck::cast_slice::<u8, f32>(bytes)[offset / 4]
+}
+
+#[test]
+fn std140_and_std430_offsets_differ() {
+    assert_eq!(PaddedReprStd140::OFFSET_A, 0);
+    assert_eq!(PaddedReprStd140::OFFSET_B, 16);
+    assert_eq!(PaddedReprStd140::OFFSET_C, 64);
+    assert_eq!(PaddedReprStd430::OFFSET_A, 0);
+    assert_eq!(PaddedReprStd430::OFFSET_B, 8);
+    assert_eq!(PaddedReprStd430::OFFSET_C, 20);
+
+    let offsets = |fields: &[sierra::ShaderReprField]| {
+        fields.iter().map(|field| field.offset).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        offsets(<Padded as ShaderReprStruct<Std140>>::FIELDS),
+        [0, 16, 64]
+    );
+    assert_eq!(
+        offsets(<Padded as ShaderReprStruct<Std430>>::FIELDS),
+        [0, 8, 20]
+    );
+    assert_eq!(<Padded as ShaderReprStruct<Std140>>::SIZE, 80);
+    assert_eq!(<Padded as ShaderReprStruct<Std430>>::SIZE, 24);
+}
+
+#[test]
+fn fields_are_written_at_their_offsets() {
+    let value = Padded {
+        a: sierra::vec2::from([1.0, 2.0]),
+        b: [3.0, 4.0, 5.0],
+        c: 6.0,
+    };
+
+    let mut repr = PaddedReprStd430::zeroed();
+    ShaderRepr::<Std430>::copy_to_repr(&value, &mut repr);
+    let bytes = bytemuck::bytes_of(&repr);
+    assert_eq!(f32_at(bytes, PaddedReprStd430::OFFSET_A), 1.0);
+    assert_eq!(f32_at(bytes, PaddedReprStd430::OFFSET_B + 8), 5.0);
+    assert_eq!(f32_at(bytes, PaddedReprStd430::OFFSET_C), 6.0);
+
+    let mut repr = PaddedReprStd140::zeroed();
+    ShaderRepr::<Std140>::copy_to_repr(&value, &mut repr);
+    let bytes = bytemuck::bytes_of(&repr);
+    assert_eq!(f32_at(bytes, PaddedReprStd140::OFFSET_A), 1.0);
+    assert_eq!(f32_at(bytes, PaddedReprStd140::OFFSET_B + 32), 5.0);
+    assert_eq!(f32_at(bytes, PaddedReprStd140::OFFSET_C), 6.0);
+}
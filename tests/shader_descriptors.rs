@@ -0,0 +1,66 @@
+//! Checks that `#[shader_descriptors]` generates compilable `#[descriptors]` struct.
+
+#[sierra::shader_descriptors("tests/shaders/descriptors.wgsl")]
+pub struct Descriptors;
+
+#[sierra::pipeline]
+pub struct Pipeline {
+    #[set]
+    pub set: Descriptors,
+}
+
+/// Fields are generated for all bindings with matching types.
+#[allow(dead_code)]
+fn fields(
+    descriptors: Descriptors,
+) -> (
+    sierra::BufferRange,
+    sierra::BufferRange,
+    sierra::ImageView,
+    sierra::Sampler,
+) {
+    (
+        descriptors.globals,
+        descriptors.values,
+        descriptors.albedo,
+        descriptors.albedo_sampler,
+    )
+}
+
+#[test]
+fn layout_matches_shader() {
+    let info = DescriptorsLayout::info();
+
+    assert_eq!(
+        info.bindings
+            .iter()
+            .map(|binding| (binding.binding, binding.ty, binding.count, binding.stages))
+            .collect::<Vec<_>>(),
+        [
+            (
+                0,
+                sierra::DescriptorType::UniformBuffer,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                1,
+                sierra::DescriptorType::StorageBuffer,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                2,
+                sierra::DescriptorType::SampledImage,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+            (
+                3,
+                sierra::DescriptorType::Sampler,
+                1,
+                sierra::ShaderStageFlags::COMPUTE
+            ),
+        ]
+    );
+}
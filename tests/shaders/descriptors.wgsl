@@ -0,0 +1,27 @@
+[[block]]
+struct Globals {
+    scale: f32;
+};
+
+[[block]]
+struct Values {
+    values: [[stride(4)]] array<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> globals: Globals;
+
+[[group(0), binding(1)]]
+var<storage, read_write> values: Values;
+
+[[group(0), binding(2)]]
+var albedo: texture_2d<f32>;
+
+[[group(0), binding(3)]]
+var albedo_sampler: sampler;
+
+[[stage(compute), workgroup_size(64)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let color = textureSampleLevel(albedo, albedo_sampler, vec2<f32>(0.0, 0.0), 0.0);
+    values.values[id.x] = values.values[id.x] * globals.scale + color.x;
+}
@@ -0,0 +1,14 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+
+#ifdef USE_TINT
+#include "tint.glsl"
+#else
+#include "missing.glsl"
+#endif
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = tint();
+}
@@ -0,0 +1,3 @@
+vec4 tint() {
+    return vec4(1.0, 0.5, 0.0, 1.0);
+}